@@ -0,0 +1,33 @@
+//! Timings for every way of generating a maze, at a few sizes. Run with `cargo bench --bench generation`.
+
+mod timing;
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use maze_generation::{Maze, Generator};
+use timing::fastest;
+
+const SIZES: [usize; 3] = [100, 300, 1000];
+
+fn main() {
+    for &size in SIZES.iter() {
+        println!("{}x{} cells, fastest of 5 runs", size, size);
+
+        fastest("from_seed", || Maze::from_seed(size, size, 0).unwrap());
+
+        let mut generator = Generator::from_seed(0);
+        let mut maze = generator.generate(size, size).unwrap();
+        fastest("Generator::generate_into", || generator.generate_into(&mut maze, size, size).unwrap());
+
+        // Steps through every random walk one event at a time, so loop erasure dominates
+        fastest("generation_trace", || Maze::generation_trace(size, size, ChaCha8Rng::seed_from_u64(0)).unwrap().count());
+
+        fastest("eller_rows", || Maze::eller_rows(size, size, ChaCha8Rng::seed_from_u64(0)).unwrap().count());
+
+        #[cfg(feature = "parallel")]
+        fastest("from_seed_parallel", || Maze::from_seed_parallel(size, size, 0, size / 4).unwrap());
+
+        println!();
+    }
+}
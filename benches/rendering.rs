@@ -0,0 +1,39 @@
+//! Timings for every renderer, at a few sizes. Run with `cargo bench --bench rendering`.
+
+mod timing;
+
+use maze_generation::Maze;
+use timing::fastest;
+
+const SIZES: [usize; 3] = [100, 300, 1000];
+
+fn main() {
+    for &size in SIZES.iter() {
+        println!("{}x{} cells, fastest of 5 runs", size, size);
+
+        let maze = Maze::from_seed(size, size, 0).unwrap();
+        fastest("to_rgba_bytes", || maze.to_rgba_bytes());
+        fastest("to_string", || maze.to_string());
+        #[cfg(feature = "image")]
+        {
+            fastest("build_image", || maze.build_image());
+            let mut img = maze.build_image();
+            fastest("build_image_into", || maze.build_image_into(&mut img));
+            fastest("build_marked_image", || maze.build_marked_image());
+            fastest("write_png", || {
+                let mut png = Vec::new();
+                maze.write_png(&mut png).unwrap();
+                png
+            });
+            let (wall, path) = (image::Rgb([0, 0, 0]), image::Rgb([255, 255, 255]));
+            fastest("build_styled_image x8", || maze.build_styled_image(8, wall, path).unwrap());
+            fastest("write_styled_png x8", || {
+                let mut png = Vec::new();
+                maze.write_styled_png(&mut png, 8, wall, path).unwrap();
+                png
+            });
+        }
+
+        println!();
+    }
+}
@@ -0,0 +1,24 @@
+//! Rough timings for the operations that lean hardest on cell storage. Run with `cargo bench --bench storage`,
+//! and again with `--features z-order` to compare the two cell layouts.
+
+mod timing;
+
+use maze_generation::{Maze, Manhattan};
+use timing::fastest;
+
+const SIZE: usize = 1000;
+
+fn main() {
+    println!("{}x{} cells, fastest of 5 runs", SIZE, SIZE);
+
+    let maze = Maze::from_seed(SIZE, SIZE, 0).unwrap();
+    fastest("solve corner to corner", || maze.solve((0, 0), (SIZE - 1, SIZE - 1)).unwrap());
+    fastest("A* corner to corner", || maze.solve_astar((0, 0), (SIZE - 1, SIZE - 1), &Manhattan).unwrap());
+    fastest("dead end filling", || maze.solve_dead_end_filling((0, 0), (SIZE - 1, SIZE - 1)).unwrap());
+    fastest("flood fill regions", || maze.regions());
+    fastest("longest path", || maze.longest_path().unwrap());
+    fastest("to_rgba_bytes", || maze.to_rgba_bytes());
+    fastest("read every cell", || maze.cells().filter(|(_, cell)| cell.is_carved()).count());
+    fastest("to_bytes", || maze.to_bytes());
+    fastest("to_raw", || maze.to_raw());
+}
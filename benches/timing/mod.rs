@@ -0,0 +1,17 @@
+//! Shared timing for the benchmarks. There's no benchmark framework, so each operation is run a few times and the fastest run is reported.
+
+use std::time::{Duration, Instant};
+
+const RUNS: usize = 5;
+
+/// Runs the operation a few times and prints the fastest time under the given name
+pub fn fastest<T, F: FnMut() -> T>(name: &str, mut operation: F) {
+    let mut best = Duration::MAX;
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        let result = operation();
+        best = best.min(start.elapsed());
+        drop(result);
+    }
+    println!("{:<32} {:>10.2?}", name, best);
+}
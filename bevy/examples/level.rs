@@ -0,0 +1,23 @@
+//! Shows a maze level. Press R for a new one.
+
+use bevy::prelude::*;
+
+use maze_generation_bevy::{MazePlugin, RegenerateMaze};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, MazePlugin::default()))
+        .add_systems(Startup, spawn_camera)
+        .add_systems(Update, regenerate_on_keypress)
+        .run();
+}
+
+fn spawn_camera(mut commands: Commands) {
+    commands.spawn(Camera2dBundle::default());
+}
+
+fn regenerate_on_keypress(keys: Res<Input<KeyCode>>, mut events: EventWriter<RegenerateMaze>) {
+    if keys.just_pressed(KeyCode::R) {
+        events.send(RegenerateMaze::default());
+    }
+}
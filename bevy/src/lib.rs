@@ -0,0 +1,133 @@
+//! A Bevy plugin that turns a generated maze into a level made of wall and floor sprites
+//!
+//! Add `MazePlugin` to the app, and send a `RegenerateMaze` event whenever a new level is wanted.
+//! The current maze is kept in the `MazeLevel` resource, so game systems can use its query API for movement and AI.
+
+use bevy::prelude::*;
+
+use maze_generation::{Maze, CellType, EntrancePlacement};
+
+/// Generates a maze level on startup and whenever a `RegenerateMaze` event is sent
+#[derive(Debug, Clone)]
+pub struct MazePlugin {
+    /// Size of the maze in cells
+    pub size: (usize, usize),
+    /// Width and height of each tile in world units. Cells and the walls between them are one tile each.
+    pub tile_size: f32,
+    /// Seed for the first maze, or None for a random one
+    pub seed: Option<u64>,
+}
+
+impl Default for MazePlugin {
+    fn default() -> Self {
+        MazePlugin { size: (20, 15), tile_size: 16.0, seed: None }
+    }
+}
+
+/// The maze the current level was built from
+#[derive(Resource, Debug, Clone)]
+pub struct MazeLevel {
+    pub maze: Maze,
+    /// The (entrance, exit) cells
+    pub entrances: ((usize, usize), (usize, usize)),
+}
+
+/// How levels get built, copied from the plugin
+#[derive(Resource, Debug, Clone)]
+pub struct MazeSettings {
+    pub size: (usize, usize),
+    pub tile_size: f32,
+}
+
+/// Send this to throw away the current level and generate a new one
+#[derive(Event, Debug, Clone, Copy, Default)]
+pub struct RegenerateMaze {
+    /// Seed for the new maze, or None for a random one
+    pub seed: Option<u64>,
+}
+
+/// Marks every entity spawned for the level, so the level can be cleared
+#[derive(Component, Debug, Clone, Copy)]
+pub struct MazeTile {
+    /// Position in the tile grid, where cell (x, y) of the maze is tile (2x + 1, 2y + 1)
+    pub tile: (usize, usize),
+}
+
+/// Marks wall tiles, for collision
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Wall;
+
+/// Marks floor tiles
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Floor;
+
+impl Plugin for MazePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(MazeSettings { size: self.size, tile_size: self.tile_size })
+            .insert_resource(generate_level(self.size, self.seed))
+            .add_event::<RegenerateMaze>()
+            .add_systems(Update, (regenerate_level, spawn_level.run_if(resource_changed::<MazeLevel>())).chain());
+    }
+}
+
+impl MazeSettings {
+    /// World position of the center of a tile. The level is centered on the origin, with tile (0, 0) at the top left.
+    pub fn tile_position(&self, tile: (usize, usize)) -> Vec2 {
+        let (width, height) = ((self.size.0 * 2 + 1) as f32, (self.size.1 * 2 + 1) as f32);
+        Vec2::new(
+            (tile.0 as f32 - width / 2.0 + 0.5) * self.tile_size,
+            (height / 2.0 - tile.1 as f32 - 0.5) * self.tile_size,
+        )
+    }
+
+    /// World position of the center of a maze cell
+    pub fn cell_position(&self, cell: (usize, usize)) -> Vec2 {
+        self.tile_position((cell.0 * 2 + 1, cell.1 * 2 + 1))
+    }
+}
+
+fn generate_level(size: (usize, usize), seed: Option<u64>) -> MazeLevel {
+    let maze = match seed {
+        Some(seed) => Maze::from_seed(size.0, size.1, seed),
+        None => Maze::new(size.0, size.1),
+    };
+    let mut maze = maze.expect("MazePlugin size must be at least 1x1");
+    // Safe to unwrap since every generated maze has path cells all along its border
+    let entrances = maze.open_entrances(EntrancePlacement::Farthest).unwrap();
+
+    MazeLevel { maze, entrances }
+}
+
+fn regenerate_level(mut events: EventReader<RegenerateMaze>, settings: Res<MazeSettings>, mut level: ResMut<MazeLevel>) {
+    if let Some(event) = events.read().last() {
+        *level = generate_level(settings.size, event.seed);
+    }
+}
+
+fn spawn_level(mut commands: Commands, level: Res<MazeLevel>, settings: Res<MazeSettings>, tiles: Query<Entity, With<MazeTile>>) {
+    for entity in tiles.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    // Safe to unwrap since a factor of 1 is always valid
+    let grid = level.maze.scale(1).unwrap();
+    for (x, col) in grid.iter().enumerate() {
+        for (y, &tile_type) in col.iter().enumerate() {
+            let tile = MazeTile { tile: (x, y) };
+            let color = match tile_type {
+                CellType::Wall => Color::BLACK,
+                CellType::Path => Color::WHITE,
+            };
+            let sprite = SpriteBundle {
+                sprite: Sprite { color, custom_size: Some(Vec2::splat(settings.tile_size)), ..default() },
+                transform: Transform::from_translation(settings.tile_position((x, y)).extend(0.0)),
+                ..default()
+            };
+
+            match tile_type {
+                CellType::Wall => commands.spawn((sprite, tile, Wall)),
+                CellType::Path => commands.spawn((sprite, tile, Floor)),
+            };
+        }
+    }
+}
@@ -0,0 +1,44 @@
+#ifndef MAZE_GENERATION_H
+#define MAZE_GENERATION_H
+
+#include <stddef.h>
+#include <stdint.h>
+#include <sys/types.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Opaque handle to a generated maze. Coordinates are in cells, with (0, 0) at the top left. */
+typedef struct Maze Maze;
+
+/* Generates a maze of the given size in cells. Returns NULL if the size is invalid. Free with maze_free. */
+Maze *maze_generate(size_t width, size_t height, uint64_t seed);
+
+/* Releases a maze. Passing NULL does nothing. */
+void maze_free(Maze *maze);
+
+/* Writes the maze's size in cells. */
+void maze_size(const Maze *maze, size_t *width, size_t *height);
+
+/* The cell at (x, y) as bits: 1, 2, 4 and 8 for open sides up, right, down and left, and 16 once carved. Returns -1 outside the maze. */
+int maze_get_cell(const Maze *maze, size_t x, size_t y);
+
+/* Opens an entrance and exit as far apart as possible, writing each as {x, y}. Returns 0 on success, -1 on failure. */
+int maze_open_entrances(Maze *maze, size_t entrance[2], size_t exit[2]);
+
+/* Writes up to capacity cells of the shortest path to path as x, y pairs. Returns the full path length in cells, or -1 if there is no path. */
+ssize_t maze_solve(const Maze *maze, size_t start_x, size_t start_y, size_t goal_x, size_t goal_y, size_t *path, size_t capacity);
+
+/* Renders the maze as RGBA bytes, row by row, (2 * width + 1) by (2 * height + 1) pixels. Returns the size of the image in bytes,
+   and only writes it to pixels if capacity is at least that. Pass NULL and 0 to measure it first. */
+size_t maze_render_rgba(const Maze *maze, uint8_t *pixels, size_t capacity);
+
+/* Saves the rendered maze as a PNG. Returns 0 on success, -1 on failure. */
+int maze_render_png(const Maze *maze, const char *path);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
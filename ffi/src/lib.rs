@@ -0,0 +1,135 @@
+//! C bindings for maze-generation, see `include/maze_generation.h`
+//! Mazes are handed out as opaque pointers from `maze_generate` and must be released with `maze_free`.
+
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+
+use image::ImageFormat;
+
+use maze_generation::{Maze, EntrancePlacement, Direction};
+
+/// Generates a maze of the given size in cells from a seed. Returns null if the size is invalid.
+#[no_mangle]
+pub extern "C" fn maze_generate(width: usize, height: usize, seed: u64) -> *mut Maze {
+    match Maze::from_seed(width, height, seed) {
+        Ok(maze) => Box::into_raw(Box::new(maze)),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Releases a maze returned by `maze_generate`. Passing null does nothing.
+///
+/// # Safety
+/// `maze` must be null or a pointer from `maze_generate` that hasn't been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn maze_free(maze: *mut Maze) {
+    if !maze.is_null() {
+        drop(Box::from_raw(maze));
+    }
+}
+
+/// Writes the maze's size in cells to `width` and `height`
+///
+/// # Safety
+/// `maze` must be a live pointer from `maze_generate`, and `width` and `height` must be valid to write to.
+#[no_mangle]
+pub unsafe extern "C" fn maze_size(maze: *const Maze, width: *mut usize, height: *mut usize) {
+    let (maze_width, maze_height) = (*maze).size();
+    *width = maze_width;
+    *height = maze_height;
+}
+
+/// The cell at (x, y) as bits: 1, 2, 4 and 8 for open sides up, right, down and left, and 16 once it's carved into the maze
+/// The same layout as the cells in `Maze::to_raw`. Returns -1 if the cell is outside the maze.
+///
+/// # Safety
+/// `maze` must be a live pointer from `maze_generate`.
+#[no_mangle]
+pub unsafe extern "C" fn maze_get_cell(maze: *const Maze, x: usize, y: usize) -> c_int {
+    let maze = &*maze;
+    let (width, height) = maze.size();
+    if x >= width || y >= height {
+        return -1;
+    }
+
+    let cell = maze[(x, y)];
+    let sides = Direction::ALL.iter()
+        .enumerate()
+        .filter(|&(_, &direction)| cell.is_open(direction))
+        .fold(0, |bits, (i, _)| bits | 1 << i);
+    if cell.is_carved() { sides | 16 } else { sides }
+}
+
+/// Opens the entrance and exit as far apart as possible, writing their cells to `entrance` and `exit` as [x, y]
+/// Returns 0 on success, or -1 if they couldn't be placed.
+///
+/// # Safety
+/// `maze` must be a live pointer from `maze_generate`, and `entrance` and `exit` must each point to two writable `size_t`s.
+#[no_mangle]
+pub unsafe extern "C" fn maze_open_entrances(maze: *mut Maze, entrance: *mut usize, exit: *mut usize) -> c_int {
+    match (*maze).open_entrances(EntrancePlacement::Farthest) {
+        Some((entrance_cell, exit_cell)) => {
+            *entrance = entrance_cell.0;
+            *entrance.add(1) = entrance_cell.1;
+            *exit = exit_cell.0;
+            *exit.add(1) = exit_cell.1;
+            0
+        },
+        None => -1,
+    }
+}
+
+/// Finds the shortest path between two cells, writing up to `capacity` cells to `path` as x, y pairs
+/// Returns the number of cells in the full path, or -1 if there is no path. If that's more than `capacity`, only the start of the path was written,
+/// so call again with a bigger buffer. Passing a null `path` with a capacity of 0 just measures the path.
+///
+/// # Safety
+/// `maze` must be a live pointer from `maze_generate`, and `path` must have room for `2 * capacity` `size_t`s.
+#[no_mangle]
+pub unsafe extern "C" fn maze_solve(maze: *const Maze, start_x: usize, start_y: usize, goal_x: usize, goal_y: usize, path: *mut usize, capacity: usize) -> isize {
+    let solution = match (*maze).solve((start_x, start_y), (goal_x, goal_y)) {
+        Some(solution) => solution,
+        None => return -1,
+    };
+
+    for (i, &(x, y)) in solution.iter().take(capacity).enumerate() {
+        *path.add(i * 2) = x;
+        *path.add(i * 2 + 1) = y;
+    }
+
+    solution.len() as isize
+}
+
+/// Renders the maze as RGBA bytes, row by row, into `pixels`, laid out like `Maze::to_rgba_bytes`
+/// Returns the number of bytes the image takes, `4 * (2 * width + 1) * (2 * height + 1)`. Nothing is written unless `capacity` is at least that,
+/// so passing a null `pixels` with a capacity of 0 just measures the image.
+///
+/// # Safety
+/// `maze` must be a live pointer from `maze_generate`, and `pixels` must have room for `capacity` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn maze_render_rgba(maze: *const Maze, pixels: *mut u8, capacity: usize) -> usize {
+    let (width, height) = (*maze).size();
+    let len = 4 * (2 * width + 1) * (2 * height + 1);
+    if capacity >= len {
+        ptr::copy_nonoverlapping((*maze).to_rgba_bytes().as_ptr(), pixels, len);
+    }
+    len
+}
+
+/// Renders the maze and saves it as a PNG at the given path. Returns 0 on success, or -1 if saving failed.
+///
+/// # Safety
+/// `maze` must be a live pointer from `maze_generate`, and `path` must be a null terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn maze_render_png(maze: *const Maze, path: *const c_char) -> c_int {
+    let path = match CStr::from_ptr(path).to_str() {
+        Ok(path) => path,
+        Err(_) => return -1,
+    };
+
+    match (*maze).build_image().save_with_format(path, ImageFormat::Png) {
+        Ok(()) => 0,
+        Err(_) => -1,
+    }
+}
@@ -0,0 +1,102 @@
+//! A tiny maze game: walk from the entrance to the exit with the arrow keys or WASD
+//! Movement goes through `Maze::can_move`, so the walls are solid without any collision code of our own.
+
+use macroquad::prelude::*;
+
+use maze_generation::{Maze, CellIndex, CellType, Direction, EntrancePlacement};
+
+const MAZE_SIZE: (usize, usize) = (20, 15);
+const TILE_SIZE: f32 = 20.0;
+
+struct Game {
+    maze: Maze,
+    /// Every tile of the rendered maze, indexed as tiles[x][y]
+    tiles: Vec<Vec<CellType>>,
+    player: CellIndex,
+    exit: CellIndex,
+    moves: usize,
+}
+
+impl Game {
+    fn new() -> Self {
+        let mut maze = Maze::new(MAZE_SIZE.0, MAZE_SIZE.1).unwrap(); // Safe to unwrap since the size is a valid constant
+        let (entrance, exit) = maze.open_entrances(EntrancePlacement::Farthest).unwrap(); // Safe to unwrap since every generated maze has path cells all along its border
+        let tiles = maze.scale(1).unwrap(); // Safe to unwrap since a factor of 1 is always valid
+
+        Game { maze, tiles, player: entrance, exit, moves: 0 }
+    }
+
+    fn try_move(&mut self, direction: Direction) {
+        if !self.maze.can_move(self.player, direction) {
+            return;
+        }
+
+        self.player = match direction {
+            Direction::Up => (self.player.0, self.player.1 - 1),
+            Direction::Right => (self.player.0 + 1, self.player.1),
+            Direction::Down => (self.player.0, self.player.1 + 1),
+            Direction::Left => (self.player.0 - 1, self.player.1),
+        };
+        self.moves += 1;
+    }
+
+    fn draw(&self) {
+        for (x, col) in self.tiles.iter().enumerate() {
+            for (y, &tile) in col.iter().enumerate() {
+                let color = if tile == CellType::Wall { DARKGRAY } else { BEIGE };
+                draw_rectangle(x as f32 * TILE_SIZE, y as f32 * TILE_SIZE, TILE_SIZE, TILE_SIZE, color);
+            }
+        }
+
+        let cell_center = |cell: CellIndex| ((cell.0 * 2 + 1) as f32 * TILE_SIZE + TILE_SIZE / 2.0, (cell.1 * 2 + 1) as f32 * TILE_SIZE + TILE_SIZE / 2.0);
+        let (exit_x, exit_y) = cell_center(self.exit);
+        draw_rectangle(exit_x - TILE_SIZE / 2.0, exit_y - TILE_SIZE / 2.0, TILE_SIZE, TILE_SIZE, GREEN);
+        let (player_x, player_y) = cell_center(self.player);
+        draw_circle(player_x, player_y, TILE_SIZE * 0.4, RED);
+
+        let status = if self.player == self.exit {
+            format!("Escaped in {} moves! Press N for a new maze", self.moves)
+        } else {
+            format!("Moves: {}", self.moves)
+        };
+        draw_text(&status, 10.0, screen_height() - 10.0, 24.0, WHITE);
+    }
+}
+
+fn window_conf() -> Conf {
+    Conf {
+        window_title: "Maze".to_owned(),
+        window_width: ((MAZE_SIZE.0 * 2 + 1) as f32 * TILE_SIZE) as i32,
+        window_height: ((MAZE_SIZE.1 * 2 + 1) as f32 * TILE_SIZE + 40.0) as i32,
+        ..Default::default()
+    }
+}
+
+#[macroquad::main(window_conf)]
+async fn main() {
+    let mut game = Game::new();
+
+    loop {
+        let controls = [
+            (KeyCode::Up, KeyCode::W, Direction::Up),
+            (KeyCode::Right, KeyCode::D, Direction::Right),
+            (KeyCode::Down, KeyCode::S, Direction::Down),
+            (KeyCode::Left, KeyCode::A, Direction::Left),
+        ];
+        if game.player != game.exit {
+            for &(arrow, letter, direction) in controls.iter() {
+                if is_key_pressed(arrow) || is_key_pressed(letter) {
+                    game.try_move(direction);
+                }
+            }
+        }
+        if is_key_pressed(KeyCode::N) {
+            game = Game::new();
+        }
+
+        clear_background(BLACK);
+        game.draw();
+
+        next_frame().await
+    }
+}
@@ -0,0 +1,25 @@
+//! Generates a 20,000 x 20,000 cell maze and times drawing views of it at a few zoom levels
+//! Run with `cargo run --release --example preview`. Generating the maze takes a while, drawing it doesn't.
+
+use std::time::Instant;
+
+use maze_generation::Maze;
+use maze_generation_gpu::{GpuRenderer, Theme, View};
+
+fn main() {
+    let start = Instant::now();
+    let maze = Maze::from_seed_parallel(20_000, 20_000, 0, 500).unwrap();
+    println!("Generated in {:?}", start.elapsed());
+
+    let start = Instant::now();
+    let renderer = GpuRenderer::new_blocking(&maze).unwrap_or_else(|err| panic!("{}", err));
+    println!("Uploaded in {:?}", start.elapsed());
+
+    let theme = Theme { wall: [30, 30, 60, 255], path: [230, 220, 180, 255], ..Theme::default() };
+    for &zoom in [8.0, 1.0, 0.05].iter() {
+        let view = View { origin: (20_000.0, 20_000.0), zoom, size: (1920, 1080) };
+        let start = Instant::now();
+        let pixels = renderer.render(&view, &theme);
+        println!("1920x1080 view at zoom {} drawn in {:?} ({} bytes)", zoom, start.elapsed(), pixels.len());
+    }
+}
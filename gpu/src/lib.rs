@@ -0,0 +1,238 @@
+//! Renders mazes on the GPU with wgpu, for interactive previews of mazes far too big for `Maze::build_image`
+//!
+//! The packed cell grid from `Maze::to_raw` is uploaded once, and each `View` of it is drawn by a compute shader,
+//! so panning and zooming around a 20,000 x 20,000 cell maze only costs one small dispatch per frame.
+
+use std::fmt;
+
+use wgpu::util::DeviceExt;
+
+use maze_generation::Maze;
+
+/// Bytes before the words in `Maze::to_raw`
+const RAW_HEADER_LEN: usize = 32;
+/// Bytes in the shader's `View` uniform
+const VIEW_LEN: usize = 40;
+/// Output pixels each workgroup covers in each direction, matching `@workgroup_size` in the shader
+const WORKGROUP_SIZE: u32 = 16;
+
+/// Colors for a rendered maze, as RGBA bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Theme {
+    pub wall: [u8; 4],
+    pub path: [u8; 4],
+    /// Used for any part of the view past the edges of the maze
+    pub background: [u8; 4],
+}
+
+impl Default for Theme {
+    /// Black walls and white paths like `Maze::build_image`, on a transparent background
+    fn default() -> Self {
+        Theme { wall: [0, 0, 0, 255], path: [255, 255, 255, 255], background: [0, 0, 0, 0] }
+    }
+}
+
+/// The part of the maze to draw, and how big
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct View {
+    /// The maze pixel at the top left of the view, laid out like `Maze::build_image`. Fractions allowed, for smooth panning.
+    pub origin: (f32, f32),
+    /// Output pixels per maze pixel. Below 1 zooms out, with each output pixel showing the maze pixel under it.
+    pub zoom: f32,
+    /// Size of the output in pixels
+    pub size: (u32, u32),
+}
+
+impl View {
+    /// The whole maze, with every maze pixel drawn `zoom` output pixels across
+    pub fn whole(maze: &Maze, zoom: f32) -> Self {
+        let (width, height) = maze.size();
+        let size = (((2 * width + 1) as f32 * zoom).ceil() as u32, ((2 * height + 1) as f32 * zoom).ceil() as u32);
+        View { origin: (0.0, 0.0), zoom, size }
+    }
+}
+
+/// Reasons the GPU renderer couldn't be set up
+#[derive(Debug)]
+pub enum GpuError {
+    /// No graphics adapter was found
+    NoAdapter,
+    /// The adapter wouldn't give us a device
+    Device(wgpu::RequestDeviceError),
+    /// The packed maze doesn't fit in one storage buffer on this adapter
+    TooBig {
+        /// Bytes the packed cells take
+        bytes: u64,
+        /// The biggest storage buffer the adapter allows
+        limit: u64,
+    },
+}
+
+impl fmt::Display for GpuError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GpuError::NoAdapter => write!(f, "No graphics adapter found"),
+            GpuError::Device(err) => write!(f, "Couldn't open the graphics device: {}", err),
+            GpuError::TooBig { bytes, limit } => write!(f, "The maze takes {} bytes but the GPU allows at most {} in a buffer", bytes, limit),
+        }
+    }
+}
+
+impl std::error::Error for GpuError {}
+
+impl From<wgpu::RequestDeviceError> for GpuError {
+    fn from(err: wgpu::RequestDeviceError) -> Self {
+        GpuError::Device(err)
+    }
+}
+
+/// A maze uploaded to the GPU, ready to draw any number of views of
+pub struct GpuRenderer {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::ComputePipeline,
+    cells: wgpu::Buffer,
+    maze_size: (u32, u32),
+}
+
+impl GpuRenderer {
+    /// Opens the default adapter and uploads the maze's cells. Asks for the adapter's own buffer limits, since big mazes need more than the defaults.
+    pub async fn new(maze: &Maze) -> Result<GpuRenderer, GpuError> {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+        let adapter = instance.request_adapter(&wgpu::RequestAdapterOptions::default()).await.ok_or(GpuError::NoAdapter)?;
+        let (device, queue) = adapter.request_device(&wgpu::DeviceDescriptor {
+            label: Some("maze renderer"),
+            required_features: wgpu::Features::empty(),
+            required_limits: adapter.limits(),
+        }, None).await?;
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("maze renderer"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("render.wgsl").into()),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("maze renderer"),
+            layout: None,
+            module: &shader,
+            entry_point: "main",
+        });
+
+        let cells = upload_cells(&device, maze)?;
+        let maze_size = (maze.size().0 as u32, maze.size().1 as u32);
+        Ok(GpuRenderer { device, queue, pipeline, cells, maze_size })
+    }
+
+    /// Same as `new`, blocking until the device is ready
+    pub fn new_blocking(maze: &Maze) -> Result<GpuRenderer, GpuError> {
+        pollster::block_on(GpuRenderer::new(maze))
+    }
+
+    /// Replaces the uploaded maze with another one, e.g. after it's been edited
+    pub fn upload(&mut self, maze: &Maze) -> Result<(), GpuError> {
+        self.cells = upload_cells(&self.device, maze)?;
+        self.maze_size = (maze.size().0 as u32, maze.size().1 as u32);
+        Ok(())
+    }
+
+    /// The device and queue, for recording `encode` into the app's own command buffers
+    pub fn device(&self) -> (&wgpu::Device, &wgpu::Queue) {
+        (&self.device, &self.queue)
+    }
+
+    /// Records drawing a view into `output`, a storage buffer of at least `4 * width * height` bytes, as RGBA pixels row by row
+    /// For apps that keep the frame on the GPU, e.g. copying it into a texture to show. `render` does this and reads the pixels back.
+    pub fn encode(&self, encoder: &mut wgpu::CommandEncoder, view: &View, theme: &Theme, output: &wgpu::Buffer) {
+        let uniform = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("maze view"),
+            contents: &self.view_bytes(view, theme),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("maze view"),
+            layout: &self.pipeline.get_bind_group_layout(0),
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: uniform.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 1, resource: self.cells.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 2, resource: output.as_entire_binding() },
+            ],
+        });
+
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor { label: Some("maze view"), timestamp_writes: None });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.dispatch_workgroups(view.size.0.div_ceil(WORKGROUP_SIZE), view.size.1.div_ceil(WORKGROUP_SIZE), 1);
+    }
+
+    /// Draws a view and reads it back as RGBA bytes, row by row, laid out like `Maze::to_rgba_bytes`
+    pub fn render(&self, view: &View, theme: &Theme) -> Vec<u8> {
+        let len = 4 * view.size.0 as u64 * view.size.1 as u64;
+        if len == 0 {
+            return Vec::new();
+        }
+
+        let output = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("maze pixels"),
+            size: len,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readback = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("maze pixels readback"),
+            size: len,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("maze view") });
+        self.encode(&mut encoder, view, theme, &output);
+        encoder.copy_buffer_to_buffer(&output, 0, &readback, 0, len);
+        self.queue.submit(Some(encoder.finish()));
+
+        let slice = readback.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |result| result.unwrap()); // Safe to unwrap since the buffer was made for mapping and nothing else maps it
+        self.device.poll(wgpu::Maintain::Wait);
+        let pixels = slice.get_mapped_range().to_vec();
+        readback.unmap();
+
+        pixels
+    }
+
+    /// The shader's `View` uniform, see render.wgsl for the layout
+    fn view_bytes(&self, view: &View, theme: &Theme) -> [u8; VIEW_LEN] {
+        let fields = [
+            self.maze_size.0.to_le_bytes(),
+            self.maze_size.1.to_le_bytes(),
+            view.origin.0.to_le_bytes(),
+            view.origin.1.to_le_bytes(),
+            view.size.0.to_le_bytes(),
+            view.size.1.to_le_bytes(),
+            view.zoom.to_le_bytes(),
+            theme.wall,
+            theme.path,
+            theme.background,
+        ];
+
+        let mut bytes = [0; VIEW_LEN];
+        for (chunk, field) in bytes.chunks_mut(4).zip(fields.iter()) {
+            chunk.copy_from_slice(field);
+        }
+        bytes
+    }
+}
+
+/// Uploads the packed words of the maze as a storage buffer
+fn upload_cells(device: &wgpu::Device, maze: &Maze) -> Result<wgpu::Buffer, GpuError> {
+    let raw = maze.to_raw();
+    let words = &raw[RAW_HEADER_LEN..];
+
+    let limit = device.limits().max_storage_buffer_binding_size as u64;
+    if words.len() as u64 > limit {
+        return Err(GpuError::TooBig { bytes: words.len() as u64, limit });
+    }
+
+    Ok(device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("maze cells"),
+        contents: words,
+        usage: wgpu::BufferUsages::STORAGE,
+    }))
+}
@@ -0,0 +1,91 @@
+// Draws one view of a maze from its packed cells, one invocation per output pixel.
+// Maze pixels follow `Maze::build_image`: cell (x, y) is pixel (2x + 1, 2y + 1), with its walls on either side.
+
+struct View {
+    // Size of the maze in cells
+    maze_size: vec2<u32>,
+    // The maze pixel at the top left of the view, fractions allowed
+    origin: vec2<f32>,
+    // Size of the view in output pixels
+    output_size: vec2<u32>,
+    // Output pixels per maze pixel. Below 1 the view is zoomed out and each output pixel samples one maze pixel.
+    zoom: f32,
+    // Colors as RGBA bytes packed little endian, red in the lowest byte
+    wall: u32,
+    path: u32,
+    // Outside the maze
+    background: u32,
+}
+
+@group(0) @binding(0) var<uniform> view: View;
+// The words of `Maze::to_raw` after the header. Each u64 is two u32s, low half first, with 12 cells of 5 bits in its low 60 bits.
+@group(0) @binding(1) var<storage, read> words: array<u32>;
+@group(0) @binding(2) var<storage, read_write> output: array<u32>;
+
+const UP: u32 = 1u;
+const RIGHT: u32 = 2u;
+const DOWN: u32 = 4u;
+const LEFT: u32 = 8u;
+const CARVED: u32 = 16u;
+
+fn cell(x: u32, y: u32) -> u32 {
+    let i = y * view.maze_size.x + x;
+    let shift = 5u * (i % 12u);
+    let lo = words[(i / 12u) * 2u];
+    let hi = words[(i / 12u) * 2u + 1u];
+    var bits: u32;
+    if shift >= 32u {
+        bits = hi >> (shift - 32u);
+    } else if shift > 27u {
+        // The cell straddles the two halves
+        bits = (lo >> shift) | (hi << (32u - shift));
+    } else {
+        bits = lo >> shift;
+    }
+    return bits & 31u;
+}
+
+// A cell is only open towards a side if it's carved too
+fn open(bits: u32, side: u32) -> bool {
+    return (bits & CARVED) != 0u && (bits & side) != 0u;
+}
+
+fn is_path(px: u32, py: u32) -> bool {
+    let x = px / 2u;
+    let y = py / 2u;
+    let odd_x = px % 2u == 1u;
+    let odd_y = py % 2u == 1u;
+    if odd_x && odd_y {
+        return (cell(x, y) & CARVED) != 0u;
+    }
+    if odd_y {
+        // The wall between cells x - 1 and x
+        return (x < view.maze_size.x && open(cell(x, y), LEFT)) || (x > 0u && open(cell(x - 1u, y), RIGHT));
+    }
+    if odd_x {
+        // The wall between cells y - 1 and y
+        return (y < view.maze_size.y && open(cell(x, y), UP)) || (y > 0u && open(cell(x, y - 1u), DOWN));
+    }
+    // Corners between cells
+    return false;
+}
+
+@compute @workgroup_size(16, 16)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= view.output_size.x || id.y >= view.output_size.y {
+        return;
+    }
+
+    let maze_pixel = view.origin + vec2<f32>(id.xy) / view.zoom;
+    let pixel_size = view.maze_size * 2u + 1u;
+    var color = view.background;
+    if all(maze_pixel >= vec2<f32>(0.0)) && all(maze_pixel < vec2<f32>(pixel_size)) {
+        let pixel = vec2<u32>(maze_pixel);
+        if is_path(pixel.x, pixel.y) {
+            color = view.path;
+        } else {
+            color = view.wall;
+        }
+    }
+    output[id.y * view.output_size.x + id.x] = color;
+}
@@ -0,0 +1,98 @@
+//! Python bindings for maze-generation, built with PyO3
+//!
+//! ```python
+//! import maze_generation
+//!
+//! maze = maze_generation.Maze(50, 50, seed=7)
+//! entrance, exit = maze.open_entrances()
+//! path = maze.solve(entrance, exit)
+//! pixels = maze.to_numpy()  # uint8 array, 1 for paths and 0 for walls
+//! maze.save_png("maze.png")
+//! ```
+
+// PyO3 0.20's macros expand to impls inside functions, which newer compilers warn about
+#![allow(non_local_definitions)]
+
+use numpy::PyArray2;
+use pyo3::exceptions::{PyIOError, PyValueError};
+use pyo3::prelude::*;
+
+use maze_generation::{Maze, MazeError, CellType, EntrancePlacement};
+
+type Cell = (usize, usize);
+
+fn to_py_err(err: MazeError) -> PyErr {
+    match err {
+        MazeError::Io(err) => PyIOError::new_err(err.to_string()),
+        err => PyValueError::new_err(err.to_string()),
+    }
+}
+
+/// A maze generated with Wilson's algorithm. Coordinates are (x, y) cells, with (0, 0) at the top left.
+#[pyclass(name = "Maze")]
+struct PyMaze {
+    maze: Maze,
+}
+
+#[pymethods]
+impl PyMaze {
+    /// Generates a maze of the given size in cells. Pass a seed to get the same maze every time.
+    #[new]
+    #[pyo3(signature = (width, height, seed=None))]
+    fn new(width: usize, height: usize, seed: Option<u64>) -> PyResult<Self> {
+        let maze = match seed {
+            Some(seed) => Maze::from_seed(width, height, seed),
+            None => Maze::new(width, height),
+        };
+
+        maze.map(|maze| PyMaze { maze }).map_err(to_py_err)
+    }
+
+    /// (width, height) in cells
+    #[getter]
+    fn size(&self) -> Cell {
+        self.maze.size()
+    }
+
+    /// Opens an entrance and exit as far apart as possible and returns them as (entrance, exit)
+    fn open_entrances(&mut self) -> PyResult<(Cell, Cell)> {
+        self.maze.open_entrances(EntrancePlacement::Farthest).ok_or_else(|| to_py_err(MazeError::NoEntrances))
+    }
+
+    /// The shortest path from start to goal as a list of cells, or None if there isn't one
+    fn solve(&self, start: Cell, goal: Cell) -> Option<Vec<Cell>> {
+        self.maze.solve(start, goal)
+    }
+
+    /// The rendered maze as a (2 * height + 1, 2 * width + 1) uint8 array indexed [y, x], with 1 for paths and 0 for walls
+    fn to_numpy<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray2<u8>> {
+        // Safe to unwrap since a factor of 1 is always valid
+        let columns = self.maze.scale(1).unwrap();
+        let rows: Vec<Vec<u8>> = (0..columns[0].len())
+            .map(|y| columns.iter().map(|col| (col[y] == CellType::Path) as u8).collect())
+            .collect();
+
+        PyArray2::from_vec2(py, &rows).map_err(|err| PyValueError::new_err(err.to_string()))
+    }
+
+    /// Renders the maze and saves it as a PNG
+    fn save_png(&self, path: &str) -> PyResult<()> {
+        self.maze.save_image(path).map_err(to_py_err)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self.maze)
+    }
+
+    fn __str__(&self) -> String {
+        self.maze.to_string()
+    }
+}
+
+// Named apart from the maze_generation crate it wraps, which it would otherwise shadow
+#[pymodule]
+#[pyo3(name = "maze_generation")]
+fn maze_generation_py(_py: Python, module: &PyModule) -> PyResult<()> {
+    module.add_class::<PyMaze>()?;
+    Ok(())
+}
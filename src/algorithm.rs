@@ -0,0 +1,67 @@
+use alloc::string::ToString;
+
+use rand::Rng;
+
+use super::{Maze, MazeError};
+
+/// The generation algorithms a maze can be made with, for picking one at runtime
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Algorithm {
+    /// Wilson's algorithm, as used by `Maze::with_rng`. Every perfect maze is equally likely.
+    #[default]
+    Wilson,
+    /// Eller's algorithm, as used by `Maze::from_eller`. More short horizontal passages than Wilson's.
+    Eller,
+    /// Wilson's algorithm in square regions of `REGION_SIZE` cells joined into one maze, as made by `Maze::from_seed_regions`.
+    /// On all cores with the `parallel` feature, for big mazes. Paths cross region borders less often than with plain Wilson's.
+    Regions,
+}
+
+/// Cells across each region for `Algorithm::Regions`, big enough that each core gets real work and small enough to spread over many cores
+pub const REGION_SIZE: usize = 256;
+
+impl Algorithm {
+    /// Every algorithm, for comparing them
+    pub const ALL: [Algorithm; 3] = [Algorithm::Wilson, Algorithm::Eller, Algorithm::Regions];
+
+    /// The name `from_str` reads, like "wilson"
+    pub fn name(self) -> &'static str {
+        match self {
+            Algorithm::Wilson => "wilson",
+            Algorithm::Eller => "eller",
+            Algorithm::Regions => "regions",
+        }
+    }
+
+    /// Generates a maze of the given size with this algorithm, taking all its randomness from the given RNG
+    pub fn generate<R: Rng + ?Sized>(self, width: usize, height: usize, rng: &mut R) -> Result<Maze, MazeError> {
+        match self {
+            Algorithm::Wilson => Maze::with_rng(width, height, rng),
+            Algorithm::Eller => Maze::from_eller(width, height, rng),
+            // Both give the same maze for a seed, so the feature doesn't change the result
+            #[cfg(feature = "parallel")]
+            Algorithm::Regions => Maze::from_seed_parallel(width, height, rng.gen(), REGION_SIZE),
+            #[cfg(not(feature = "parallel"))]
+            Algorithm::Regions => Maze::from_seed_regions(width, height, rng.gen(), REGION_SIZE),
+        }
+    }
+}
+
+impl core::fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+impl core::str::FromStr for Algorithm {
+    type Err = MazeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "wilson" => Ok(Algorithm::Wilson),
+            "eller" => Ok(Algorithm::Eller),
+            "regions" => Ok(Algorithm::Regions),
+            _ => Err(MazeError::InvalidAlgorithm(s.to_string())),
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use alloc::vec::Vec;
+
+use super::{Maze, CellIndex};
+
+/// Steps along the passages from one cell to every path cell reachable from it, as measured by `Maze::distances_from`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DistanceMap {
+    /// The cell distances are measured from
+    pub origin: CellIndex,
+    /// Indexed as distances[x][y]. Steps from the origin, or None for walls and cells that can't be reached.
+    pub distances: Vec<Vec<Option<usize>>>,
+}
+
+impl DistanceMap {
+    /// Steps from the origin to the given cell, or None if it can't be reached or is outside the maze
+    pub fn get(&self, cell: CellIndex) -> Option<usize> {
+        self.distances.get(cell.0).and_then(|col| col.get(cell.1)).copied().flatten()
+    }
+
+    /// The reachable cell farthest from the origin, and how far it is. Ties go to whichever cell comes first in column order.
+    pub fn farthest(&self) -> (CellIndex, usize) {
+        let mut farthest = (self.origin, 0);
+        for (x, col) in self.distances.iter().enumerate() {
+            for (y, distance) in col.iter().enumerate() {
+                if let Some(distance) = *distance {
+                    if distance > farthest.1 {
+                        farthest = ((x, y), distance);
+                    }
+                }
+            }
+        }
+        farthest
+    }
+
+    /// Number of cells that can be reached from the origin, counting the origin itself
+    pub fn reachable(&self) -> usize {
+        self.distances.iter().flatten().filter(|distance| distance.is_some()).count()
+    }
+}
+
+impl Maze {
+    /// Breadth first search distances from a path cell to every cell reachable from it, through passages and portals
+    /// Every step costs the same, so this is Dijkstra's algorithm without the priority queue. The basis for heatmaps, the longest path
+    /// and difficulty scores. Returns None if the cell is a wall or outside the maze.
+    pub fn distances_from(&self, cell: CellIndex) -> Option<DistanceMap> {
+        if !self.is_path(cell) {
+            return None;
+        }
+
+        Some(DistanceMap { origin: cell, distances: self.bfs_distances(cell) })
+    }
+
+    /// The two path cells that are farthest apart, and the number of steps between them, as (one end, other end, steps)
+    /// Uses the double breadth first search trick: the cell farthest from anywhere is one end of the longest path,
+    /// and the cell farthest from that is the other end. This is exact for perfect mazes, and a good approximation for mazes with loops.
+    /// Only the region containing the first path cell is considered. Returns None if the maze has no path cells.
+    pub fn diameter(&self) -> Option<(CellIndex, CellIndex, usize)> {
+        let any_path_cell = self.first_path_cell()?;
+
+        let (one_end, _) = self.distances_from(any_path_cell).unwrap().farthest(); // Safe to unwrap since first_path_cell is a path cell
+        let (other_end, length) = self.distances_from(one_end).unwrap().farthest(); // Safe to unwrap since farthest only returns reachable cells
+
+        info!("Longest path runs from ({}, {}) to ({}, {}), {} steps", one_end.0, one_end.1, other_end.0, other_end.1, length);
+
+        Some((one_end, other_end, length))
+    }
+
+    /// Finds the longest shortest-path in the maze, i.e. the path between the two ends of `diameter`
+    /// Returns None if the maze has no path cells.
+    pub fn longest_path(&self) -> Option<Vec<CellIndex>> {
+        info!("Finding longest path");
+
+        let (one_end, other_end, _) = self.diameter()?;
+        self.solve(one_end, other_end)
+    }
+
+    /// Tags the ends of `diameter` "start" and "goal", so a puzzle can use the hardest start and goal the maze has, wherever they are
+    /// `EntrancePlacement::Farthest` is the same idea for openings, which have to be on the border.
+    /// Returns the (start, goal) cells, or None if the maze has no path cells.
+    pub fn tag_diameter(&mut self) -> Option<(CellIndex, CellIndex)> {
+        let (start, goal, _) = self.diameter()?;
+        self.add_tag(start, "start").unwrap(); // Safe to unwrap since diameter only returns cells inside the maze
+        self.add_tag(goal, "goal").unwrap(); // Same here
+        Some((start, goal))
+    }
+
+    /// The path between the maze's entrance and exit, as placed by `open_entrances`
+    /// If the maze hasn't been opened up, the longest path is used instead, since that's where you'd put them.
+    pub fn solution(&self) -> Option<Vec<CellIndex>> {
+        match self.entrances {
+            Some((entrance, exit)) => self.solve(entrance.cell, exit.cell),
+            None => self.longest_path(),
+        }
+    }
+
+    /// Total number of cells that have been carved into the maze
+    pub(super) fn path_cell_count(&self) -> usize {
+        self.path_cells().count()
+    }
+
+    /// The first path cell found scanning column by column
+    fn first_path_cell(&self) -> Option<CellIndex> {
+        self.path_cells().next()
+    }
+}
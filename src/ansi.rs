@@ -0,0 +1,88 @@
+use alloc::{string::String, vec, vec::Vec};
+use core::fmt::Write;
+
+use super::{Maze, CellIndex, Direction, DistanceMap};
+
+const WALL: [u8; 3] = [0, 0, 0];
+const PATH: [u8; 3] = [255, 255, 255];
+const ROUTE: [u8; 3] = [230, 40, 40];
+
+/// What `to_ansi` colors the paths with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnsiOverlay<'a> {
+    /// Plain white paths
+    Plain,
+    /// The cells along a route, like the one from `solution`, and the passages between them in red
+    Route(&'a [CellIndex]),
+    /// Every reachable cell shaded from blue near the origin to red for the farthest
+    Heatmap(&'a DistanceMap),
+}
+
+impl Maze {
+    /// Draws the maze for a terminal with 24-bit color, packing two pixel rows into each line with half block characters like `Display`
+    /// Walls are black and paths white, with the overlay's colors on top. Every line ends by resetting the colors.
+    pub fn to_ansi(&self, overlay: AnsiOverlay) -> String {
+        let (width, height) = self.pixel_size();
+        let pixels = self.to_pixels();
+
+        // Indexed as colors[x][y] like the pixels, for the path pixels the overlay colors
+        let mut colors: Vec<Vec<Option<[u8; 3]>>> = vec![vec![None; height]; width];
+        match overlay {
+            AnsiOverlay::Plain => {},
+            AnsiOverlay::Route(route) => {
+                for &(x, y) in route.iter().filter(|&&cell| self.is_path(cell)) {
+                    colors[2 * x + 1][2 * y + 1] = Some(ROUTE);
+                }
+                for pair in route.windows(2).filter(|pair| self.can_pass(pair[0], pair[1])) {
+                    colors[pair[0].0 + pair[1].0 + 1][pair[0].1 + pair[1].1 + 1] = Some(ROUTE);
+                }
+            },
+            AnsiOverlay::Heatmap(distances) => {
+                let farthest = distances.farthest().1.max(1);
+                let shade = |distance: usize| {
+                    let red = (distance * 255 / farthest) as u8;
+                    [red, 64, 255 - red]
+                };
+                for cell in self.path_cells() {
+                    let distance = match distances.get(cell) {
+                        Some(distance) => distance,
+                        None => continue,
+                    };
+                    let (px, py) = (2 * cell.0 + 1, 2 * cell.1 + 1);
+                    colors[px][py] = Some(shade(distance));
+                    // Passages take the shade of the farther of their cells
+                    for &(direction, (wx, wy)) in &[(Direction::Right, (px + 1, py)), (Direction::Down, (px, py + 1))] {
+                        if self.can_move(cell, direction) {
+                            let next = self.step(cell, direction).and_then(|next| distances.get(next)).unwrap_or(distance);
+                            colors[wx][wy] = Some(shade(distance.max(next)));
+                        }
+                    }
+                }
+            },
+        }
+        let color = |x: usize, y: usize| if pixels[x][y].is_wall() { WALL } else { colors[x][y].unwrap_or(PATH) };
+
+        // Colors only change when they have to, which keeps the output a fraction of the size of setting both on every character
+        let mut text = String::new();
+        for top in (0..height).step_by(2) {
+            let mut current: Option<([u8; 3], Option<[u8; 3]>)> = None;
+            for x in 0..width {
+                let upper = color(x, top);
+                let lower = (top + 1 < height).then(|| color(x, top + 1));
+                if current != Some((upper, lower)) {
+                    write!(text, "\x1b[38;2;{};{};{}m", upper[0], upper[1], upper[2]).unwrap(); // Safe to unwrap since writing to a String can't fail
+                    match lower {
+                        Some(lower) => write!(text, "\x1b[48;2;{};{};{}m", lower[0], lower[1], lower[2]).unwrap(), // Safe to unwrap since writing to a String can't fail
+                        // The last line of an odd number of pixel rows only has a top half
+                        None => text.push_str("\x1b[49m"),
+                    }
+                    current = Some((upper, lower));
+                }
+                text.push('▀');
+            }
+            text.push_str("\x1b[0m\n");
+        }
+
+        text
+    }
+}
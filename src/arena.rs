@@ -0,0 +1,45 @@
+use alloc::vec::Vec;
+
+use rand::{Rng, seq::SliceRandom};
+
+use super::{Maze, MazeError, Piece, Rotation};
+
+impl Maze {
+    /// Generates a capture the flag arena: a maze that looks the same rotated 180 degrees, with a base for each team on opposite sides
+    /// The left half is a random maze and the right half is the same maze turned around, joined by `lanes` passages across the middle,
+    /// so each team's routes from their base are exactly the other team's turned around. More lanes means more loops and more ways across.
+    /// The bases are the middle cells of the left and right edges, tagged "base1" and "base2".
+    /// Fails with OutOfRange if the width is odd, or if `lanes` is 0, more than the height, or odd when the height is even,
+    /// since an odd number of lanes needs one through the middle row.
+    pub fn ctf_arena<R: Rng + ?Sized>(width: usize, height: usize, lanes: usize, rng: &mut R) -> Result<Maze, MazeError> {
+        if width == 0 || !width.is_multiple_of(2) {
+            return Err(MazeError::OutOfRange { setting: "width", got: width as f64, min: 2.0, max: f64::INFINITY });
+        }
+        if lanes == 0 || lanes > height || (!lanes.is_multiple_of(2) && height.is_multiple_of(2)) {
+            return Err(MazeError::OutOfRange { setting: "lanes", got: lanes as f64, min: 1.0, max: height as f64 });
+        }
+
+        info!("Generating a {}x{} arena with {} lanes", width, height, lanes);
+
+        let half = Maze::with_rng(width / 2, height, rng)?;
+        let turned = half.rotated(Rotation::Half);
+
+        // Lanes come in pairs of rows that swap places when the arena is turned around, plus the middle row on its own
+        let mut pairs: Vec<usize> = (0..height / 2).collect();
+        pairs.shuffle(rng);
+        let mut rows: Vec<usize> = pairs[..lanes / 2].iter().flat_map(|&y| [y, height - 1 - y]).collect();
+        if !lanes.is_multiple_of(2) {
+            rows.push(height / 2);
+        }
+        let doorways: Vec<_> = rows.iter().map(|&y| ((width / 2 - 1, y), (width / 2, y))).collect();
+
+        let pieces = [Piece { offset: (0, 0), maze: &half }, Piece { offset: (width / 2, 0), maze: &turned }];
+        let mut arena = Maze::stitch((width, height), &pieces, &doorways)?;
+        arena.add_tag((0, (height - 1) / 2), "base1")?;
+        arena.add_tag((width - 1, height / 2), "base2")?;
+
+        debug!("Arena lanes cross at rows {:?}", rows);
+
+        Ok(arena)
+    }
+}
@@ -0,0 +1,236 @@
+use core::cmp::Ordering;
+use alloc::{collections::BinaryHeap, vec, vec::Vec};
+
+use super::{Maze, CellIndex, Direction};
+use super::solvers::reconstruct_path;
+
+/// Estimates the remaining cost of travelling between two cells, used to guide the A* solver
+/// The estimate must never be larger than the real cost, otherwise the returned path may not be the shortest one.
+/// Portals are taken care of by the solver, so estimates only need to hold for the passages.
+pub trait Heuristic {
+    /// Estimated cost of the cheapest path from `from` to `goal`
+    fn estimate(&self, from: CellIndex, goal: CellIndex) -> f64;
+}
+
+/// Sum of the horizontal and vertical distances. Exact for a maze with no walls, so it's the best choice in general.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Manhattan;
+
+impl Heuristic for Manhattan {
+    fn estimate(&self, from: CellIndex, goal: CellIndex) -> f64 {
+        (from.0.abs_diff(goal.0) + from.1.abs_diff(goal.1)) as f64
+    }
+}
+
+#[cfg(feature = "std")]
+/// Straight line distance. Always smaller than Manhattan, so the solver ends up expanding more cells.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Euclidean;
+
+#[cfg(feature = "std")]
+impl Heuristic for Euclidean {
+    fn estimate(&self, from: CellIndex, goal: CellIndex) -> f64 {
+        let dx = from.0.abs_diff(goal.0) as f64;
+        let dy = from.1.abs_diff(goal.1) as f64;
+        (dx * dx + dy * dy).sqrt()
+    }
+}
+
+/// Distance when diagonal steps are allowed, with diagonals costing sqrt(2). Use this instead of Manhattan with `Movement::Diagonal`,
+/// since Manhattan overestimates diagonal moves and can make A* miss the shortest path.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Octile;
+
+impl Heuristic for Octile {
+    fn estimate(&self, from: CellIndex, goal: CellIndex) -> f64 {
+        let dx = from.0.abs_diff(goal.0) as f64;
+        let dy = from.1.abs_diff(goal.1) as f64;
+        dx.max(dy) + (core::f64::consts::SQRT_2 - 1.0) * dx.min(dy)
+    }
+}
+
+/// Any closure taking (from, goal) can be used as a custom heuristic
+impl<F> Heuristic for F where F: Fn(CellIndex, CellIndex) -> f64 {
+    fn estimate(&self, from: CellIndex, goal: CellIndex) -> f64 {
+        self(from, goal)
+    }
+}
+
+/// Which moves the solver is allowed to make
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Movement {
+    /// Only up, down, left and right
+    Orthogonal,
+    /// Also diagonally, but only when both routes around the corner to the diagonal cell are open, so corners are never cut.
+    /// Diagonal steps cost sqrt(2).
+    Diagonal,
+}
+
+/// What an A* search found, from `Maze::solve_astar_with_stats`
+#[derive(Debug, Clone, PartialEq)]
+pub struct AStarSearch {
+    /// The shortest path, including both ends, or None if there isn't one
+    pub path: Option<Vec<CellIndex>>,
+    /// Cells taken off the open set and explored before reaching the goal. Fewer means the heuristic guided the search better.
+    pub cells_expanded: usize,
+    /// Most cells waiting in the open set at once, which is what the search's memory grows with
+    pub peak_open: usize,
+}
+
+/// An entry in the open set. Ordered so that BinaryHeap (a max-heap) pops the lowest estimated total cost first.
+/// A cell is pushed again whenever a cheaper way to it is found, so `cost` tells the stale entries apart from the current one.
+struct OpenCell {
+    estimated_cost: f64,
+    cost: f64,
+    cell: CellIndex,
+}
+
+impl PartialEq for OpenCell {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for OpenCell {}
+
+impl PartialOrd for OpenCell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OpenCell {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimated_cost.total_cmp(&self.estimated_cost)
+    }
+}
+
+/// Keeps a heuristic admissible on mazes with portals, which can reach cells far closer to the goal than the estimate says
+/// Every portal end gets a lower bound on the cost of finishing from it, through any chain of other portals. Each hop through
+/// a portal costs at least 1, so an estimate is the smaller of going straight to the goal and going to a portal end and through it.
+struct PortalBound<'a, H> {
+    heuristic: &'a H,
+    /// Each portal end, with a lower bound on the cost of stepping through it and finishing from the other end
+    via: Vec<(CellIndex, f64)>,
+}
+
+impl<'a, H: Heuristic> PortalBound<'a, H> {
+    fn new(heuristic: &'a H, portals: &[(CellIndex, CellIndex)], goal: CellIndex) -> Self {
+        let ends: Vec<(CellIndex, CellIndex)> = portals.iter().flat_map(|&(a, b)| [(a, b), (b, a)]).collect();
+        // Bellman-Ford over the portal ends: a chain of portals can't be longer than the number of ends
+        let mut finish: Vec<f64> = ends.iter().map(|&(_, out)| heuristic.estimate(out, goal)).collect();
+        for _ in 0..ends.len() {
+            let mut changed = false;
+            for i in 0..ends.len() {
+                let out = ends[i].1;
+                for (j, &(next, _)) in ends.iter().enumerate() {
+                    let through = heuristic.estimate(out, next) + 1.0 + finish[j];
+                    if through < finish[i] {
+                        finish[i] = through;
+                        changed = true;
+                    }
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+        let via = ends.iter().zip(finish).map(|(&(end, _), finish)| (end, 1.0 + finish)).collect();
+        PortalBound { heuristic, via }
+    }
+
+    fn estimate(&self, from: CellIndex, goal: CellIndex) -> f64 {
+        self.via.iter()
+            .map(|&(end, cost)| self.heuristic.estimate(from, end) + cost)
+            .fold(self.heuristic.estimate(from, goal), f64::min)
+    }
+}
+
+impl Maze {
+    /// Finds the shortest path between two cells using A*, guided by the given heuristic
+    /// The returned path includes both the start and the goal. Returns None if either cell is a wall or no path exists.
+    pub fn solve_astar<H: Heuristic>(&self, start: CellIndex, goal: CellIndex, heuristic: &H) -> Option<Vec<CellIndex>> {
+        self.solve_astar_with_movement(start, goal, heuristic, Movement::Orthogonal)
+    }
+
+    /// Same as `solve_astar`, but lets the solver move diagonally. See `Movement` for the rules.
+    /// This only affects solving; generated mazes are always made of orthogonal corridors.
+    pub fn solve_astar_with_movement<H: Heuristic>(&self, start: CellIndex, goal: CellIndex, heuristic: &H, movement: Movement) -> Option<Vec<CellIndex>> {
+        self.astar(start, goal, heuristic, movement, |_| 1.0).path
+    }
+
+    /// Same as `solve_astar_with_movement`, but also says how much work the search did, for comparing heuristics on big mazes
+    pub fn solve_astar_with_stats<H: Heuristic>(&self, start: CellIndex, goal: CellIndex, heuristic: &H, movement: Movement) -> AStarSearch {
+        self.astar(start, goal, heuristic, movement, |_| 1.0)
+    }
+
+    /// A* where entering a cell costs `cell_cost(cell)` times the length of the step. The heuristic has to account for the costs.
+    pub(super) fn astar<H: Heuristic, C: Fn(CellIndex) -> f64>(&self, start: CellIndex, goal: CellIndex, heuristic: &H, movement: Movement, cell_cost: C) -> AStarSearch {
+        info!("Solving maze with A* ({:?}) from ({}, {}) to ({}, {})", movement, start.0, start.1, goal.0, goal.1);
+
+        if !self.is_path(start) || !self.is_path(goal) {
+            return AStarSearch { path: None, cells_expanded: 0, peak_open: 0 };
+        }
+
+        let mut came_from: Vec<Vec<Option<CellIndex>>> = vec![vec![None; self.size.1]; self.size.0];
+        let mut cost_so_far: Vec<Vec<f64>> = vec![vec![f64::INFINITY; self.size.1]; self.size.0];
+        came_from[start.0][start.1] = Some(start);
+        cost_so_far[start.0][start.1] = 0.0;
+
+        let heuristic = PortalBound::new(heuristic, &self.portals, goal);
+        let mut open = BinaryHeap::new();
+        open.push(OpenCell { estimated_cost: heuristic.estimate(start, goal), cost: 0.0, cell: start });
+
+        let mut expanded: usize = 0;
+        let mut peak_open: usize = 1;
+        while let Some(OpenCell { cell: current, cost, .. }) = open.pop() {
+            if cost > cost_so_far[current.0][current.1] {
+                // A cheaper way to this cell was found after this entry was pushed, and that one has been expanded already
+                continue;
+            }
+            if current == goal {
+                let path = reconstruct_path(&came_from, start, goal);
+                info!("Solution found with length {} after expanding {} cells", path.len(), expanded);
+                return AStarSearch { path: Some(path), cells_expanded: expanded, peak_open };
+            }
+            expanded += 1;
+
+            let current_cost = cost_so_far[current.0][current.1];
+            for (neighbor, step_cost) in self.weighted_neighbors(current, movement) {
+                let new_cost = current_cost + step_cost * cell_cost(neighbor);
+                // Routes of the same length can add up their diagonal steps in a different order and differ in the last bit,
+                // which would count as an improvement and expand the cell again
+                if new_cost < cost_so_far[neighbor.0][neighbor.1] - 1e-9 {
+                    cost_so_far[neighbor.0][neighbor.1] = new_cost;
+                    came_from[neighbor.0][neighbor.1] = Some(current);
+                    open.push(OpenCell { estimated_cost: new_cost + heuristic.estimate(neighbor, goal), cost: new_cost, cell: neighbor });
+                }
+            }
+            peak_open = peak_open.max(open.len());
+        }
+
+        info!("No solution exists");
+
+        AStarSearch { path: None, cells_expanded: expanded, peak_open }
+    }
+
+    /// The path cells reachable in one move from the given cell, along with the cost of moving there
+    fn weighted_neighbors(&self, cell: CellIndex, movement: Movement) -> Vec<(CellIndex, f64)> {
+        let mut neighbors: Vec<(CellIndex, f64)> = self.neighbors(cell).into_iter().map(|neighbor| (neighbor, 1.0)).collect();
+
+        if movement == Movement::Diagonal {
+            for &first in Direction::ALL.iter() {
+                let second = first.turn_right();
+                // The diagonal is only reachable if both L-shaped routes to it are open
+                let route = |a: Direction, b: Direction| self.step(cell, a)
+                    .filter(|&side| self.can_pass(cell, side))
+                    .and_then(|side| self.step(side, b).filter(|&corner| self.can_pass(side, corner)));
+                if let (Some(diagonal), Some(_)) = (route(first, second), route(second, first)) {
+                    neighbors.push((diagonal, core::f64::consts::SQRT_2));
+                }
+            }
+        }
+
+        neighbors
+    }
+}
@@ -0,0 +1,91 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, atomic::{AtomicBool, Ordering}};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use super::{Maze, MazeError, GenerationProgress};
+
+/// State shared between a `GenerationHandle` and the thread doing the generating
+struct Shared {
+    progress: GenerationProgress,
+    result: Option<Result<Maze, MazeError>>,
+    waker: Option<Waker>,
+}
+
+/// A maze being generated on a background thread, see `Maze::generate_async`
+/// Await it for the finished maze. Dropping it cancels the generation.
+pub struct GenerationHandle {
+    shared: Arc<Mutex<Shared>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl GenerationHandle {
+    /// How far along the generation is, as of the last finished random walk
+    pub fn progress(&self) -> GenerationProgress {
+        self.shared.lock().unwrap().progress.clone() // Safe to unwrap since the generator thread never panics while holding the lock
+    }
+
+    /// Asks the generation to stop. Awaiting the handle afterwards gives `MazeError::Cancelled`, unless it had already finished.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Future for GenerationHandle {
+    type Output = Result<Maze, MazeError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut shared = self.shared.lock().unwrap(); // Safe to unwrap since the generator thread never panics while holding the lock
+        match shared.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                shared.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl Drop for GenerationHandle {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+impl Maze {
+    /// Generates a maze from a seed on a background thread, so async code doesn't block its runtime while a big maze is built
+    /// Gives the same maze as `from_seed`. Works with any executor, since the handle is woken from the generator thread.
+    /// Poll `GenerationHandle::progress` for updates.
+    pub fn generate_async(width: usize, height: usize, seed: u64) -> GenerationHandle {
+        let shared = Arc::new(Mutex::new(Shared {
+            progress: GenerationProgress { cells_carved: 0, total_cells: width * height, walks_completed: 0 },
+            result: None,
+            waker: None,
+        }));
+        let cancelled = Arc::new(AtomicBool::new(false));
+
+        let thread_shared = Arc::clone(&shared);
+        let thread_cancelled = Arc::clone(&cancelled);
+        thread::spawn(move || {
+            let result = Maze::generate(width, height, &mut ChaCha8Rng::seed_from_u64(seed), |progress| {
+                thread_shared.lock().unwrap().progress = progress.clone(); // Safe to unwrap since the handle never panics while holding the lock
+                !thread_cancelled.load(Ordering::Relaxed)
+            }, || thread_cancelled.load(Ordering::Relaxed));
+
+            let mut shared = thread_shared.lock().unwrap(); // Safe to unwrap since the handle never panics while holding the lock
+            if let Ok(maze) = &result {
+                shared.progress.cells_carved = maze.size.0 * maze.size.1;
+            }
+            shared.result = Some(result);
+            if let Some(waker) = shared.waker.take() {
+                waker.wake();
+            }
+        });
+
+        GenerationHandle { shared, cancelled }
+    }
+}
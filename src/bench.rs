@@ -0,0 +1,95 @@
+//! The `bench` subcommand: times every algorithm at a few sizes, to help pick one for the size of maze you need
+//! Peak grid memory is only counted with the `memory-stats` feature, and shows as - without it.
+
+use std::time::Instant;
+
+use maze_generation::{Algorithm, MazeError};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/// How one algorithm did at one size, over every run
+struct BenchResult {
+    algorithm: Algorithm,
+    size: (usize, usize),
+    mean_millis: f64,
+    fastest_millis: f64,
+    /// Most grid memory any run took, if it was counted
+    peak_grid_bytes: Option<usize>,
+    dead_ends: f64,
+    river_factor: f64,
+    solution_length: f64,
+}
+
+/// Parses sizes written like 50x50,200x100
+pub fn parse_sizes(value: &str) -> Result<Vec<(usize, usize)>, String> {
+    value.split(',').map(|size| {
+        let (width, height) = size.trim().split_once('x').ok_or(format!("Invalid size: {}", size))?;
+        match (width.parse(), height.parse()) {
+            (Ok(width), Ok(height)) => Ok((width, height)),
+            _ => Err(format!("Invalid size: {}", size)),
+        }
+    }).collect()
+}
+
+/// Generates `runs` mazes of the size with the algorithm, seeded `seed`, `seed + 1` and so on so every algorithm gets the same seeds
+fn bench_one(algorithm: Algorithm, size: (usize, usize), runs: usize, seed: u64) -> Result<BenchResult, MazeError> {
+    let mut millis = Vec::with_capacity(runs);
+    let (mut dead_ends, mut river_factor, mut solution_length) = (0.0, 0.0, 0.0);
+    #[allow(unused_mut)]
+    let mut peak_grid_bytes = None;
+
+    for run in 0..runs {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed.wrapping_add(run as u64));
+        #[cfg(feature = "memory-stats")]
+        maze_generation::reset_memory_stats();
+
+        let start_time = Instant::now();
+        let maze = algorithm.generate(size.0, size.1, &mut rng)?;
+        millis.push(start_time.elapsed().as_secs_f64() * 1000.0);
+
+        #[cfg(feature = "memory-stats")]
+        {
+            peak_grid_bytes = peak_grid_bytes.max(Some(maze_generation::memory_stats().peak_grid_bytes));
+        }
+
+        let stats = maze.stats();
+        dead_ends += stats.dead_ends as f64;
+        river_factor += stats.river_factor;
+        solution_length += stats.solution_length.unwrap_or(0) as f64;
+    }
+
+    let runs = runs as f64;
+    Ok(BenchResult {
+        algorithm,
+        size,
+        mean_millis: millis.iter().sum::<f64>() / runs,
+        fastest_millis: millis.iter().cloned().fold(f64::INFINITY, f64::min),
+        peak_grid_bytes,
+        dead_ends: dead_ends / runs,
+        river_factor: river_factor / runs,
+        solution_length: solution_length / runs,
+    })
+}
+
+/// Benchmarks every algorithm at every size and prints a table of the results, averaged over the runs
+pub fn bench(sizes: &[(usize, usize)], runs: usize, seed: u64) -> Result<(), MazeError> {
+    println!("{:<10} {:>11} {:>10} {:>10} {:>12} {:>10} {:>7} {:>9}", "algorithm", "size", "mean ms", "best ms", "grid memory", "dead ends", "river", "solution");
+    for &size in sizes {
+        for &algorithm in &Algorithm::ALL {
+            let result = bench_one(algorithm, size, runs, seed)?;
+            let memory = result.peak_grid_bytes.map_or_else(|| String::from("-"), |bytes| format!("{} B", bytes));
+            println!(
+                "{:<10} {:>11} {:>10.2} {:>10.2} {:>12} {:>10.1} {:>7.3} {:>9.1}",
+                result.algorithm,
+                format!("{}x{}", result.size.0, result.size.1),
+                result.mean_millis,
+                result.fastest_millis,
+                memory,
+                result.dead_ends,
+                result.river_factor,
+                result.solution_length,
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use alloc::vec::Vec;
+
+use rand::{Rng, seq::SliceRandom};
+
+use super::{Maze, MazeError, CellIndex, Direction};
+
+impl Maze {
+    /// Knocks through a wall at `ratio` of the maze's dead ends, turning a perfect maze into a braided one with loops
+    /// Dead ends are picked at random, and each one is joined to a neighboring dead end if it has one, which removes both at once,
+    /// and to any other neighboring path cell otherwise. 0 leaves the maze alone and 1 removes every dead end that has a wall to knock through.
+    /// Returns how many dead ends were removed, which can be one more than asked for if the last one was joined to another. Fails with OutOfRange if the ratio isn't between 0 and 1.
+    pub fn braid<R: Rng + ?Sized>(&mut self, ratio: f64, rng: &mut R) -> Result<usize, MazeError> {
+        if !(0.0..=1.0).contains(&ratio) {
+            return Err(MazeError::OutOfRange { setting: "ratio", got: ratio, min: 0.0, max: 1.0 });
+        }
+
+        let mut dead_ends: Vec<CellIndex> = self.path_cells().filter(|&cell| self.is_dead_end(cell)).collect();
+        let target = (ratio * dead_ends.len() as f64 + 0.5) as usize; // Rounded to nearest, since f64::round needs std
+        dead_ends.shuffle(rng);
+
+        info!("Braiding {} of {} dead ends", target, dead_ends.len());
+
+        let mut removed = 0;
+        for cell in dead_ends {
+            if removed >= target {
+                break;
+            }
+            // An earlier knock through may have already joined this one to a neighboring dead end
+            if !self.is_dead_end(cell) {
+                continue;
+            }
+
+            let walls: Vec<CellIndex> = Direction::ALL.iter()
+                .filter(|&&direction| !self.is_open(cell, direction))
+                .filter_map(|&direction| self.step(cell, direction))
+                .filter(|&neighbor| self.is_path(neighbor))
+                .collect();
+            let dead_end_walls: Vec<CellIndex> = walls.iter().copied().filter(|&neighbor| self.is_dead_end(neighbor)).collect();
+            let neighbor = match dead_end_walls.choose(rng).or_else(|| walls.choose(rng)) {
+                Some(&neighbor) => neighbor,
+                None => continue,
+            };
+
+            removed += if self.is_dead_end(neighbor) { 2 } else { 1 };
+            self.open_passage(cell, neighbor);
+        }
+
+        debug!("Removed {} dead ends", removed);
+
+        Ok(removed)
+    }
+
+    /// Opens `count` walls between neighboring path cells, picked at random, so there's more than one route between most pairs of cells
+    /// Each wall knocked through adds one loop. Returns how many were opened, which is fewer than `count` if the maze ran out of walls.
+    pub fn add_loops<R: Rng + ?Sized>(&mut self, count: usize, rng: &mut R) -> usize {
+        // Only walls to the right and below, so each one is listed once
+        let mut walls: Vec<(CellIndex, CellIndex)> = self.path_cells()
+            .flat_map(|cell| [Direction::Right, Direction::Down].iter()
+                .filter(|&&direction| !self.is_open(cell, direction))
+                .filter_map(|&direction| self.step(cell, direction))
+                .filter(|&neighbor| self.is_path(neighbor))
+                .map(|neighbor| (cell, neighbor))
+                .collect::<Vec<_>>())
+            .collect();
+        let (picked, _) = walls.partial_shuffle(rng, count);
+
+        info!("Adding {} loops", picked.len());
+
+        for &(from, to) in picked.iter() {
+            self.open_passage(from, to);
+        }
+        picked.len()
+    }
+
+    /// Returns true if the cell is a path cell open on exactly one side, the same dead ends `stats` counts
+    fn is_dead_end(&self, cell: CellIndex) -> bool {
+        self.open_directions(cell).len() == 1
+    }
+}
@@ -0,0 +1,25 @@
+use super::{Maze, CellIndex, Cell};
+
+impl Maze {
+    /// Every cell in the maze along with its coordinates, column by column
+    pub fn cells(&self) -> impl Iterator<Item = (CellIndex, Cell)> + '_ {
+        (0..self.size.0).flat_map(move |x| (0..self.size.1).map(move |y| ((x, y), self[(x, y)])))
+    }
+
+    /// The coordinates of every cell that has been carved into the maze, column by column
+    pub fn path_cells(&self) -> impl Iterator<Item = CellIndex> + '_ {
+        self.cells().filter(|(_, cell)| cell.is_carved()).map(|(index, _)| index)
+    }
+
+    /// The coordinates of every cell that hasn't been carved, column by column
+    /// Always empty for a freshly generated maze, since generation visits every cell.
+    pub fn wall_cells(&self) -> impl Iterator<Item = CellIndex> + '_ {
+        self.cells().filter(|(_, cell)| !cell.is_carved()).map(|(index, _)| index)
+    }
+
+    /// The maze one row at a time, top to bottom, with each row running left to right
+    /// Handy for printing or exporting, since `cells` goes column by column.
+    pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = (CellIndex, Cell)> + '_> + '_ {
+        (0..self.size.1).map(move |y| (0..self.size.0).map(move |x| ((x, y), self[(x, y)])))
+    }
+}
@@ -0,0 +1,92 @@
+//! Config files for the binary, describing one or more maze jobs in TOML
+//!
+//! Keys are the binary's flags with underscores for dashes, e.g. `cell_size = 4` for `--cell-size 4` and `parallel = true` for `--parallel`,
+//! plus `analyze = true` for the `analyze` subcommand. Keys outside a `[[maze]]` table apply to every job, and each `[[maze]]` table is one job.
+//! Values are strings, integers, floats or booleans. Anything else, like arrays or nested tables, is an error naming the key.
+
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs;
+
+use serde::Deserialize;
+use toml::Value;
+
+/// One job from a config file, as the command line arguments it stands for
+pub type Job = Vec<String>;
+
+/// The whole file: shared keys at the top level and one table per job
+#[derive(Deserialize)]
+struct ConfigFile {
+    #[serde(default)]
+    maze: Vec<BTreeMap<String, Value>>,
+    #[serde(flatten)]
+    shared: BTreeMap<String, Value>,
+}
+
+/// Reads a config file into one job per `[[maze]]` table, or a single job if there are none
+pub fn read_jobs(path: &str) -> Result<Vec<Job>, String> {
+    let text = fs::read_to_string(path).map_err(|err| format!("Can't read {}: {}", path, err))?;
+    parse_jobs(&text).map_err(|err| format!("{}: {}", path, err))
+}
+
+/// Parses config text into jobs, each starting with the keys shared by every job
+/// TOML syntax errors come back with the line and column they're on.
+fn parse_jobs(text: &str) -> Result<Vec<Job>, String> {
+    let mut file: ConfigFile = toml::from_str(text).map_err(|err| err.to_string())?;
+
+    if file.maze.is_empty() {
+        file.maze.push(BTreeMap::new());
+    }
+    file.maze.iter().enumerate().map(|(i, table)| {
+        // A job's own keys override the shared ones
+        let mut settings = file.shared.clone();
+        settings.extend(table.iter().map(|(key, value)| (key.clone(), value.clone())));
+        to_args(&settings).map_err(|err| format!("job {}: {}", i + 1, err))
+    }).collect()
+}
+
+/// Checks nothing but `--config` and `--quiet` was given on the command line along with a config file
+/// Other flags would have to be merged into every job, so they're refused rather than quietly ignored.
+pub fn check_command_line(args: &[OsString]) -> Result<(), String> {
+    let mut rest = args.iter().skip(1);
+    while let Some(arg) = rest.next() {
+        match arg.to_str() {
+            Some("--config") => {
+                rest.next();
+            },
+            Some(arg) if arg.starts_with("--config=") => {},
+            Some("--quiet") | Some("-q") => {},
+            _ => return Err(format!("{} can't be given with --config, set it in the config file instead", arg.to_string_lossy())),
+        }
+    }
+    Ok(())
+}
+
+/// Turns keys and values into command line arguments
+fn to_args(settings: &BTreeMap<String, Value>) -> Result<Job, String> {
+    let mut args = vec![String::from("maze-generation")];
+    let mut analyze = false;
+    for (key, value) in settings {
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(format!("invalid key {}", key));
+        }
+        if key == "config" {
+            return Err(String::from("config files can't include other config files"));
+        }
+        let flag = format!("--{}", key.replace('_', "-"));
+        match value {
+            Value::Boolean(set) if key == "analyze" => analyze = *set,
+            // Booleans are flags without a value, given or left out. A quoted "true" is a string, and goes to the flag as its value.
+            Value::Boolean(true) => args.push(flag),
+            Value::Boolean(false) => {},
+            Value::String(text) => args.extend([flag, text.clone()]),
+            Value::Integer(number) => args.extend([flag, number.to_string()]),
+            Value::Float(number) => args.extend([flag, number.to_string()]),
+            _ => return Err(format!("{} must be a string, number or boolean, not {}", key, value.type_str())),
+        }
+    }
+    if analyze {
+        args.push(String::from("analyze"));
+    }
+    Ok(args)
+}
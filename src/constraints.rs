@@ -0,0 +1,129 @@
+use alloc::string::ToString;
+
+use rand::Rng;
+#[cfg(feature = "std")]
+use rand::SeedableRng;
+#[cfg(feature = "std")]
+use rand_chacha::ChaCha8Rng;
+
+use super::{Maze, MazeError, EntrancePlacement, Algorithm};
+
+/// Rough difficulty buckets, based on the score from `Maze::difficulty`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DifficultyTarget {
+    /// Score below 29
+    Easy,
+    /// Score from 29 up to 32
+    Medium,
+    /// Score of 32 or more. Large mazes rarely get here, since their solutions are a small fraction of the whole maze.
+    Hard,
+}
+
+impl DifficultyTarget {
+    /// Returns true if the difficulty score falls in this bucket
+    pub fn contains(self, score: f64) -> bool {
+        match self {
+            DifficultyTarget::Easy => score < 29.0,
+            DifficultyTarget::Medium => (29.0..32.0).contains(&score),
+            DifficultyTarget::Hard => score >= 32.0,
+        }
+    }
+}
+
+impl core::str::FromStr for DifficultyTarget {
+    type Err = MazeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "easy" => Ok(DifficultyTarget::Easy),
+            "medium" => Ok(DifficultyTarget::Medium),
+            "hard" => Ok(DifficultyTarget::Hard),
+            _ => Err(MazeError::InvalidDifficulty(s.to_string())),
+        }
+    }
+}
+
+/// Requirements for `Maze::generate_until`. Every requirement that's set has to be met.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Constraints {
+    /// Entrances to open in each attempt before measuring it. The solution is measured between them, or along the longest path if unset.
+    pub entrances: Option<EntrancePlacement>,
+    /// Minimum number of cells on the solution, including both ends
+    pub min_solution_length: Option<usize>,
+    /// Minimum fraction (0 to 1) of all path cells that have to be on the solution, so big mazes can't have trivially short solutions
+    pub min_solution_fraction: Option<f64>,
+    /// Difficulty bucket the maze has to land in
+    pub difficulty: Option<DifficultyTarget>,
+}
+
+impl Constraints {
+    /// Checks the constraints can be met by a maze of the given size at all, so `generate_until` can fail straight away instead of after every attempt
+    /// Fails with InvalidDimensions for an empty maze, or OutOfRange naming the setting that can never be met.
+    pub fn validate(&self, width: usize, height: usize) -> Result<(), MazeError> {
+        if width == 0 || height == 0 {
+            return Err(MazeError::InvalidDimensions { got: (width, height) });
+        }
+
+        let cells = (width * height) as f64;
+        if let Some(min) = self.min_solution_length {
+            if min as f64 > cells {
+                return Err(MazeError::OutOfRange { setting: "min_solution_length", got: min as f64, min: 0.0, max: cells });
+            }
+        }
+        if let Some(min) = self.min_solution_fraction {
+            // Written so NaN fails too
+            if !(0.0..=1.0).contains(&min) {
+                return Err(MazeError::OutOfRange { setting: "min_solution_fraction", got: min, min: 0.0, max: 1.0 });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns true if the maze meets every requirement
+    pub fn is_met_by(&self, maze: &Maze) -> bool {
+        let difficulty = match maze.difficulty() {
+            Some(difficulty) => difficulty,
+            None => return false,
+        };
+
+        self.min_solution_length.is_none_or(|min| difficulty.solution_length >= min)
+            && self.min_solution_fraction.is_none_or(|min| difficulty.solution_length as f64 >= min * maze.path_cell_count() as f64)
+            && self.difficulty.is_none_or(|target| target.contains(difficulty.score))
+    }
+}
+
+impl Maze {
+    /// Keeps generating mazes until one meets the constraints, giving up after `max_attempts`
+    /// Returns the maze along with the number of attempts it took. The constraints are checked with `Constraints::validate` first.
+    #[cfg(feature = "std")]
+    pub fn generate_until(width: usize, height: usize, constraints: &Constraints, max_attempts: usize) -> Result<(Self, usize), MazeError> {
+        Maze::generate_until_with_rng(width, height, Algorithm::Wilson, constraints, max_attempts, &mut ChaCha8Rng::from_entropy())
+    }
+
+    /// Same as `generate_until`, with the given algorithm and all the randomness taken from the given RNG, so a seed always gives the same result
+    pub fn generate_until_with_rng<R: Rng + ?Sized>(width: usize, height: usize, algorithm: Algorithm, constraints: &Constraints, max_attempts: usize, rng: &mut R) -> Result<(Self, usize), MazeError> {
+        constraints.validate(width, height)?;
+        if max_attempts == 0 {
+            return Err(MazeError::OutOfRange { setting: "max_attempts", got: 0.0, min: 1.0, max: f64::INFINITY });
+        }
+
+        for attempt in 1..=max_attempts {
+            info!("Generation attempt {} of {}", attempt, max_attempts);
+
+            let mut maze = algorithm.generate(width, height, rng)?;
+            if let Some(placement) = constraints.entrances {
+                if maze.open_entrances(placement).is_none() {
+                    return Err(MazeError::NoEntrances);
+                }
+            }
+
+            if constraints.is_met_by(&maze) {
+                info!("Constraints met after {} attempts", attempt);
+                return Ok((maze, attempt));
+            }
+        }
+
+        Err(MazeError::ConstraintsNotMet { attempts: max_attempts })
+    }
+}
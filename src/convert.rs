@@ -0,0 +1,33 @@
+use core::convert::TryFrom;
+
+use image::{Rgb, RgbImage};
+
+use super::{Maze, MazeError};
+
+/// Pixels brighter than this count as paths when reading an image
+const PATH_THRESHOLD: u32 = 128;
+
+fn is_path_pixel(pixel: &Rgb<u8>) -> bool {
+    let [r, g, b] = pixel.0;
+    (r as u32 + g as u32 + b as u32) / 3 >= PATH_THRESHOLD
+}
+
+impl From<&Maze> for RgbImage {
+    /// Same as `Maze::build_image`
+    fn from(maze: &Maze) -> Self {
+        maze.build_image()
+    }
+}
+
+impl TryFrom<&RgbImage> for Maze {
+    type Error = MazeError;
+
+    /// Reads a maze back from an image laid out like `Maze::build_image`: light pixels are paths, dark pixels are walls
+    /// Cells, passages and entrances are read the same way as `Maze::from_tiles`.
+    fn try_from(img: &RgbImage) -> Result<Self, MazeError> {
+        let (pixel_width, pixel_height) = (img.width() as usize, img.height() as usize);
+        info!("Reading maze from {}x{} image", pixel_width, pixel_height);
+
+        Maze::from_path_pixels((pixel_width, pixel_height), |x, y| is_path_pixel(img.get_pixel(x as u32, y as u32)))
+    }
+}
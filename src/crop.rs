@@ -0,0 +1,69 @@
+use alloc::vec::Vec;
+
+use super::{Maze, MazeError, CellIndex, Direction, Opening, PackedCells, grid_offset};
+
+/// A rectangular piece cut out of a maze, see `Maze::crop`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Crop {
+    /// The cut out region as a maze of its own, with every passage that crossed the cut sealed
+    pub maze: Maze,
+    /// The passages that crossed the edge of the region and were sealed, in the cropped maze's coordinates
+    /// Reopening these (with `Maze::carve` after stitching, or as extra openings) reconnects the piece to its surroundings.
+    pub sealed: Vec<Opening>,
+}
+
+impl Maze {
+    /// Cuts out the cells from (x0, y0) up to but not including (x1, y1) as a new maze
+    /// Passages leading out of the region are sealed and reported in `Crop::sealed`. Openings in the original outer wall are kept,
+    /// the entrances are kept if both of them are inside the region, labeled openings are kept if they are, and portals and one-way passages if both ends are. Tags on cells inside the region are kept. The piece may not be connected, see `Maze::regions`.
+    pub fn crop(&self, x0: usize, y0: usize, x1: usize, y1: usize) -> Result<Crop, MazeError> {
+        if x0 >= x1 || y0 >= y1 || x1 > self.size.0 || y1 > self.size.1 {
+            return Err(MazeError::InvalidRegion { from: (x0, y0), to: (x1, y1) });
+        }
+
+        info!("Cropping ({}, {}) to ({}, {})", x0, y0, x1, y1);
+
+        let size = (x1 - x0, y1 - y0);
+        let inside = |cell: CellIndex| cell.0 >= x0 && cell.0 < x1 && cell.1 >= y0 && cell.1 < y1;
+
+        let mut cells = PackedCells::grid(size);
+        let mut sealed = Vec::new();
+        for x in x0..x1 {
+            for y in y0..y1 {
+                let mut cell = self[(x, y)];
+                for &direction in Direction::ALL.iter() {
+                    if cell.is_open(direction) && self.step((x, y), direction).is_some_and(|neighbor| !inside(neighbor)) {
+                        cell.close(direction);
+                        sealed.push(Opening { cell: (x - x0, y - y0), side: direction });
+                    }
+                }
+                cells.set(grid_offset(size, (x - x0, y - y0)), cell);
+            }
+        }
+
+        let shift = |opening: Opening| Opening { cell: (opening.cell.0 - x0, opening.cell.1 - y0), ..opening };
+        let entrances = self.entrances
+            .filter(|(entrance, exit)| inside(entrance.cell) && inside(exit.cell))
+            .map(|(entrance, exit)| (shift(entrance), shift(exit)));
+        let openings = self.openings.iter()
+            .filter(|(_, opening)| inside(opening.cell))
+            .map(|(label, opening)| (label.clone(), shift(*opening)))
+            .collect();
+        let portals = self.portals.iter()
+            .filter(|&&(a, b)| inside(a) && inside(b))
+            .map(|&(a, b)| ((a.0 - x0, a.1 - y0), (b.0 - x0, b.1 - y0)))
+            .collect();
+        let one_way = self.one_way.iter()
+            .filter(|&&(from, to)| inside(from) && inside(to))
+            .map(|&(from, to)| ((from.0 - x0, from.1 - y0), (to.0 - x0, to.1 - y0)))
+            .collect();
+        let tags = self.tags.iter()
+            .filter(|&(&cell, _)| inside(cell))
+            .map(|(&cell, tags)| ((cell.0 - x0, cell.1 - y0), tags.clone()))
+            .collect();
+
+        info!("Sealed {} passages along the cut", sealed.len());
+
+        Ok(Crop { maze: Maze { cells, size, entrances, openings, portals, one_way, tags }, sealed })
+    }
+}
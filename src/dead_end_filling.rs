@@ -0,0 +1,63 @@
+use alloc::{collections::VecDeque, vec, vec::Vec};
+
+use super::{Maze, CellIndex};
+
+/// The result of solving a maze by dead end filling
+#[derive(Debug, Clone)]
+pub struct DeadEndFill {
+    /// The path from start to goal through the cells left unfilled
+    pub path: Vec<CellIndex>,
+    /// Indexed as filled[x][y]. True for every path cell that was filled in as part of a dead end.
+    pub filled: Vec<Vec<bool>>,
+}
+
+impl Maze {
+    /// Solves the maze by repeatedly filling in dead ends until only the corridors connecting start and goal remain
+    /// On a perfect maze the cells left over are exactly the solution. On a maze with loops, the shortest route through the remaining cells is returned.
+    /// Returns None if either cell is a wall or no path exists.
+    pub fn solve_dead_end_filling(&self, start: CellIndex, goal: CellIndex) -> Option<DeadEndFill> {
+        info!("Solving maze with dead end filling from ({}, {}) to ({}, {})", start.0, start.1, goal.0, goal.1);
+
+        if !self.is_path(start) || !self.is_path(goal) {
+            return None;
+        }
+
+        let mut filled = vec![vec![false; self.size.1]; self.size.0];
+        let open_neighbor_count = |filled: &[Vec<bool>], cell: CellIndex| {
+            self.neighbors(cell).iter().filter(|neighbor| !filled[neighbor.0][neighbor.1]).count()
+        };
+
+        // Seed the fill with every dead end. Start and goal are never filled, even if they're dead ends themselves.
+        let mut dead_ends = VecDeque::new();
+        for x in 0..self.size.0 {
+            for y in 0..self.size.1 {
+                let cell = (x, y);
+                if self.is_path(cell) && cell != start && cell != goal && open_neighbor_count(&filled, cell) <= 1 {
+                    dead_ends.push_back(cell);
+                }
+            }
+        }
+
+        // Filling a dead end may turn its one open neighbor into a new dead end
+        let mut filled_count: usize = 0;
+        while let Some(cell) = dead_ends.pop_front() {
+            if filled[cell.0][cell.1] {
+                continue;
+            }
+            filled[cell.0][cell.1] = true;
+            filled_count += 1;
+
+            for neighbor in self.neighbors(cell) {
+                if !filled[neighbor.0][neighbor.1] && neighbor != start && neighbor != goal && open_neighbor_count(&filled, neighbor) <= 1 {
+                    dead_ends.push_back(neighbor);
+                }
+            }
+        }
+
+        info!("Filled {} dead end cells", filled_count);
+
+        let path = self.breadth_first_search(start, goal, |_, cell| filled[cell.0][cell.1])?;
+
+        Some(DeadEndFill { path, filled })
+    }
+}
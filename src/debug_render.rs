@@ -0,0 +1,152 @@
+use alloc::{string::ToString, vec::Vec};
+
+use image::{Rgb, RgbImage};
+
+use super::{Maze, MazeError, CellIndex, GenerationEvent, ImageStyle};
+
+/// Digits 0 to 9 in a 3x5 pixel font, a row of 3 bits per line from the top, leftmost pixel in the highest bit
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b010, 0b010, 0b010],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+/// Pixels each digit takes along a label, counting the gap after it
+const DIGIT_ADVANCE: usize = 4;
+/// Pixels between the labels and the maze, and around the outside of the labels
+const LABEL_MARGIN: usize = 2;
+
+const GRID_COLOR: Rgb<u8> = Rgb([120, 160, 255]);
+const SEED_COLOR: Rgb<u8> = Rgb([0, 110, 255]);
+const WALK_START_COLOR: Rgb<u8> = Rgb([255, 170, 0]);
+const LABEL_COLOR: Rgb<u8> = Rgb([90, 90, 90]);
+
+/// What `build_debug_image` draws over a maze, for working on generators and tracking down connectivity bugs
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DebugOverlay {
+    /// Pixels across every cell and wall. Keep it at 4 or more so the gridlines don't cover the cells.
+    pub scale: usize,
+    /// Label every this many columns and rows with its coordinate, or 0 for no labels. Labels can overlap if they're too close for the scale.
+    pub label_every: usize,
+    /// The cell generation grew out from, filled blue
+    pub seed_cell: Option<CellIndex>,
+    /// Cells random walks started from, filled orange
+    pub walk_starts: Vec<CellIndex>,
+}
+
+impl DebugOverlay {
+    /// An overlay highlighting the seed cell and walk starts from the events of a `Maze::generation_trace`
+    pub fn from_events(events: &[GenerationEvent]) -> Self {
+        let mut overlay = DebugOverlay::default();
+        for event in events {
+            match *event {
+                GenerationEvent::InitialCell(cell) => overlay.seed_cell = Some(cell),
+                GenerationEvent::WalkStarted(cell) => overlay.walk_starts.push(cell),
+                _ => {},
+            }
+        }
+        overlay
+    }
+}
+
+/// 8 pixels a cell, labels every 5 cells, nothing highlighted
+impl Default for DebugOverlay {
+    fn default() -> Self {
+        DebugOverlay { scale: 8, label_every: 5, seed_cell: None, walk_starts: Vec::new() }
+    }
+}
+
+impl Maze {
+    /// Renders the maze with faint gridlines between the cells, the overlay's cells highlighted, and coordinates along the top and left edges
+    /// The walk starts are drawn before the seed cell, so the seed shows even if a walk started there too.
+    /// Fails with InvalidScale for a scale of 0, or OutOfBounds for a highlighted cell outside the maze.
+    pub fn build_debug_image(&self, overlay: &DebugOverlay) -> Result<RgbImage, MazeError> {
+        let scale = overlay.scale;
+        let style = ImageStyle { corridor_width: scale, wall_thickness: scale, ..ImageStyle::default() };
+        let maze = self.build_image_with_style(&style)?;
+
+        let highlights = overlay.walk_starts.iter().map(|&cell| (cell, WALK_START_COLOR)).chain(overlay.seed_cell.map(|cell| (cell, SEED_COLOR)));
+        let highlights: Vec<(CellIndex, Rgb<u8>)> = highlights.collect();
+        if let Some(&(cell, _)) = highlights.iter().find(|&&(cell, _)| cell.0 >= self.size.0 || cell.1 >= self.size.1) {
+            return Err(MazeError::OutOfBounds { cell });
+        }
+
+        // Room for the widest row label on the left, and a line of digits along the top
+        let (width, height) = self.size;
+        let (left, top) = if overlay.label_every == 0 {
+            (0, 0)
+        } else {
+            let widest = (height - 1).to_string().len();
+            (widest * DIGIT_ADVANCE + 2 * LABEL_MARGIN, DIGITS[0].len() + 2 * LABEL_MARGIN)
+        };
+        let mut img = RgbImage::from_pixel(maze.width() + left as u32, maze.height() + top as u32, Rgb([255, 255, 255]));
+        image::imageops::replace(&mut img, &maze, left as u32, top as u32);
+
+        // A cell's pixels start one wall in, at 2x + 1 pixels of the rendered maze
+        let cell_start = |i: usize| (2 * i + 1) * scale;
+        for (cell, color) in highlights {
+            for y in cell_start(cell.1)..cell_start(cell.1) + scale {
+                for x in cell_start(cell.0)..cell_start(cell.0) + scale {
+                    img.put_pixel((left + x) as u32, (top + y) as u32, color);
+                }
+            }
+        }
+
+        // Gridlines down the middle of every line of walls, blended in so the walls and passages still show through
+        let lines = |count: usize| (0..=count).map(move |i| 2 * i * scale + scale / 2);
+        for x in lines(width) {
+            for y in 0..maze.height() as usize {
+                blend(img.get_pixel_mut((left + x) as u32, (top + y) as u32));
+            }
+        }
+        for y in lines(height) {
+            for x in 0..maze.width() as usize {
+                blend(img.get_pixel_mut((left + x) as u32, (top + y) as u32));
+            }
+        }
+
+        if overlay.label_every > 0 {
+            for x in (0..width).step_by(overlay.label_every) {
+                let text = x.to_string();
+                let centered = (left + cell_start(x) + scale / 2).saturating_sub(text.len() * DIGIT_ADVANCE / 2);
+                draw_label(&mut img, &text, centered, LABEL_MARGIN);
+            }
+            for y in (0..height).step_by(overlay.label_every) {
+                let text = y.to_string();
+                let right_aligned = left - LABEL_MARGIN - text.len() * DIGIT_ADVANCE;
+                let centered = (top + cell_start(y) + scale / 2).saturating_sub(DIGITS[0].len() / 2);
+                draw_label(&mut img, &text, right_aligned, centered);
+            }
+        }
+
+        Ok(img)
+    }
+}
+
+/// Tints a pixel a third of the way towards the grid color
+fn blend(pixel: &mut Rgb<u8>) {
+    for (channel, &grid) in pixel.0.iter_mut().zip(&GRID_COLOR.0) {
+        *channel = ((*channel as u16 * 2 + grid as u16) / 3) as u8;
+    }
+}
+
+/// Draws a number with its top left corner at (x, y), skipping any pixels that fall outside the image
+fn draw_label(img: &mut RgbImage, text: &str, x: usize, y: usize) {
+    for (i, digit) in text.bytes().enumerate() {
+        let glyph = DIGITS[(digit - b'0') as usize];
+        for (row, bits) in glyph.iter().enumerate() {
+            for column in 0..3 {
+                let (px, py) = ((x + i * DIGIT_ADVANCE + column) as u32, (y + row) as u32);
+                if bits & (0b100 >> column) != 0 && px < img.width() && py < img.height() {
+                    img.put_pixel(px, py, LABEL_COLOR);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use alloc::vec::Vec;
+
+#[cfg(feature = "image")]
+use image::{Rgb, RgbImage};
+
+use super::{Maze, MazeError, CellIndex, CellType};
+
+/// The pixels that differ between two mazes of the same size when rendered, see `Maze::diff`
+/// Pixel coordinates are used so that opened passages show up as well as carved cells.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MazeDiff {
+    /// Pixels that are walls in the original maze but paths in the other one
+    pub added: Vec<CellIndex>,
+    /// Pixels that are paths in the original maze but walls in the other one
+    pub removed: Vec<CellIndex>,
+}
+
+impl MazeDiff {
+    /// Returns true if the two mazes were identical
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl Maze {
+    /// Compares this maze to another of the same size, listing every pixel that was opened or closed going from this one to the other
+    pub fn diff(&self, other: &Maze) -> Result<MazeDiff, MazeError> {
+        if self.size != other.size {
+            return Err(MazeError::SizeMismatch { expected: self.size, got: other.size });
+        }
+
+        let mut diff = MazeDiff::default();
+        for (x, (col, other_col)) in self.to_pixels().iter().zip(other.to_pixels().iter()).enumerate() {
+            for (y, (cell, other_cell)) in col.iter().zip(other_col.iter()).enumerate() {
+                match (cell, other_cell) {
+                    (CellType::Wall, CellType::Path) => diff.added.push((x, y)),
+                    (CellType::Path, CellType::Wall) => diff.removed.push((x, y)),
+                    _ => {},
+                }
+            }
+        }
+
+        info!("Diff found {} added and {} removed pixels", diff.added.len(), diff.removed.len());
+
+        Ok(diff)
+    }
+
+    /// Renders the differences between this maze and another like `build_image`, except
+    /// pixels that are only paths in the other maze are green and pixels that are only paths in this maze are red
+    #[cfg(feature = "image")]
+    pub fn build_diff_image(&self, other: &Maze) -> Result<RgbImage, MazeError> {
+        let diff = self.diff(other)?;
+
+        let mut img = self.build_image();
+        for &(x, y) in &diff.added {
+            img.put_pixel(x as u32, y as u32, Rgb([0, 255, 0]));
+        }
+        for &(x, y) in &diff.removed {
+            img.put_pixel(x as u32, y as u32, Rgb([255, 0, 0]));
+        }
+
+        Ok(img)
+    }
+}
@@ -0,0 +1,93 @@
+use alloc::vec::Vec;
+
+use super::Maze;
+
+/// How hard a maze is to solve, along with the measurements the score is built from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Difficulty {
+    /// Weighted combination of the measurements below, from 0 (trivial) to 100
+    pub score: f64,
+    /// Number of cells on the solution, including both ends
+    pub solution_length: usize,
+    /// Junctions passed through along the solution, where a wrong turn could be taken
+    pub branches: usize,
+    /// Fraction of maze cells that are dead ends
+    pub dead_end_density: f64,
+    /// Average steps from a dead end back to the nearest junction, i.e. how long a wrong turn takes to discover
+    pub mean_dead_end_depth: f64,
+    /// Average number of wrong options offered at each junction along the solution
+    pub mean_fan_out: f64,
+}
+
+// Weights for each component of the score. They add up to 1, and every component is scaled to 0..1 before weighting.
+const SOLUTION_LENGTH_WEIGHT: f64 = 0.4;
+const BRANCH_WEIGHT: f64 = 0.3;
+const DEAD_END_WEIGHT: f64 = 0.1;
+const DEAD_END_DEPTH_WEIGHT: f64 = 0.1;
+const FAN_OUT_WEIGHT: f64 = 0.1;
+
+/// Dead end depths are scaled to 0..1 as depth / (depth + this). At 4, the typical depth of 1.75 or so scales to about the same as
+/// a typical dead end density, so adding depth to the score didn't move mazes between `DifficultyTarget` buckets.
+const DEAD_END_DEPTH_SCALE: f64 = 4.0;
+
+impl Maze {
+    /// Scores how hard the maze is to solve, using the maze's solution (see `solution`)
+    /// The score is 100 times a weighted sum of:
+    /// - 40%: solution length as a fraction of all path cells
+    /// - 30%: fraction of maze cells along the solution that are junctions
+    /// - 10%: fraction of maze cells that are dead ends
+    /// - 10%: average depth of the dead ends, scaled with `depth / (depth + 4)`
+    /// - 10%: average wrong options per junction on the solution, out of the maximum of 2
+    ///
+    /// Returns None if the maze has no solution.
+    pub fn difficulty(&self) -> Option<Difficulty> {
+        info!("Scoring maze difficulty");
+
+        let solution = self.solution()?;
+
+        let path_cell_count = self.path_cell_count();
+        let dead_ends = self.stats().dead_ends;
+        let depths = self.dead_end_depths();
+        let total_depth: usize = depths.iter().enumerate().map(|(depth, &count)| depth * count).sum();
+
+        let solution_cells: Vec<usize> = solution.iter()
+            .map(|&cell| self.neighbors(cell).len())
+            .collect();
+        let branches = solution_cells.iter().filter(|&&neighbors| neighbors >= 3).count();
+        let wrong_options: usize = solution_cells.iter().filter(|&&neighbors| neighbors >= 3).map(|&neighbors| neighbors - 2).sum();
+
+        let solution_length_ratio = solution.len() as f64 / path_cell_count as f64;
+        let branch_ratio = ratio(branches, solution_cells.len());
+        let dead_end_density = ratio(dead_ends, path_cell_count);
+        let mean_fan_out = ratio(wrong_options, branches);
+        let mean_dead_end_depth = ratio(total_depth, depths.iter().sum());
+
+        let score = 100.0 * (
+            SOLUTION_LENGTH_WEIGHT * solution_length_ratio
+            + BRANCH_WEIGHT * branch_ratio
+            + DEAD_END_WEIGHT * dead_end_density
+            + DEAD_END_DEPTH_WEIGHT * mean_dead_end_depth / (mean_dead_end_depth + DEAD_END_DEPTH_SCALE)
+            + FAN_OUT_WEIGHT * mean_fan_out / 2.0
+        );
+
+        info!("Difficulty score: {:.2}", score);
+
+        Some(Difficulty {
+            score,
+            solution_length: solution.len(),
+            branches,
+            dead_end_density,
+            mean_dead_end_depth,
+            mean_fan_out,
+        })
+    }
+}
+
+/// Divides two counts, treating 0/0 as 0
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    if denominator == 0 {
+        0.0
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
@@ -0,0 +1,28 @@
+use core::fmt;
+
+use super::Maze;
+
+/// Draws the maze as text, packing two pixel rows into each line with half block characters so it keeps roughly the right shape
+impl fmt::Display for Maze {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pixels = self.to_pixels();
+        let (width, height) = self.pixel_size();
+
+        for top in (0..height).step_by(2) {
+            for column in pixels.iter().take(width) {
+                let upper = column[top].is_wall();
+                let lower = top + 1 < height && column[top + 1].is_wall();
+                let block = match (upper, lower) {
+                    (true, true) => '█',
+                    (true, false) => '▀',
+                    (false, true) => '▄',
+                    (false, false) => ' ',
+                };
+                write!(f, "{}", block)?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+use alloc::{collections::VecDeque, format, vec, vec::Vec};
+use std::io::{self, Write};
+
+use rand::{Rng, SeedableRng, prelude::SliceRandom};
+use rand_chacha::ChaCha8Rng;
+
+use super::{Maze, CellIndex};
+
+/// Shortest path distances between pairs of maze cells, counted in cell-to-cell steps
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DistanceMatrix {
+    /// The cells each row and column refers to, in order
+    pub cells: Vec<CellIndex>,
+    /// Row major, so the distance from cells[row] to cells[col] is at row * cells.len() + col. None where unreachable.
+    pub distances: Vec<Option<usize>>,
+}
+
+impl DistanceMatrix {
+    /// The distance from cells[row] to cells[col]
+    pub fn get(&self, row: usize, col: usize) -> Option<usize> {
+        self.distances[row * self.cells.len() + col]
+    }
+
+    /// Writes the matrix as CSV. The first row and column label each entry with its cell as "x:y"; unreachable pairs are left empty.
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write!(writer, "cell")?;
+        for cell in &self.cells {
+            write!(writer, ",{}:{}", cell.0, cell.1)?;
+        }
+        writeln!(writer)?;
+
+        for (row, cell) in self.cells.iter().enumerate() {
+            write!(writer, "{}:{}", cell.0, cell.1)?;
+            for col in 0..self.cells.len() {
+                match self.get(row, col) {
+                    Some(distance) => write!(writer, ",{}", distance)?,
+                    None => write!(writer, ",")?,
+                }
+            }
+            writeln!(writer)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the matrix as a NumPy .npy file (format version 1.0) holding an n x n array of little endian int64, with -1 where unreachable
+    /// The cell labels aren't included; use `cells` to write them separately if needed.
+    pub fn write_npy<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let n = self.cells.len();
+        let mut header = format!("{{'descr': '<i8', 'fortran_order': False, 'shape': ({}, {}), }}", n, n);
+
+        // Magic string (6 bytes), version (2 bytes) and header length (2 bytes) come first.
+        // The header is space padded and newline terminated so the data starts on a 64 byte boundary.
+        let unpadded_length = 10 + header.len() + 1;
+        header.push_str(&" ".repeat((64 - unpadded_length % 64) % 64));
+        header.push('\n');
+
+        writer.write_all(b"\x93NUMPY\x01\x00")?;
+        writer.write_all(&(header.len() as u16).to_le_bytes())?;
+        writer.write_all(header.as_bytes())?;
+
+        for distance in &self.distances {
+            let value = distance.map_or(-1, |distance| distance as i64);
+            writer.write_all(&value.to_le_bytes())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Maze {
+    /// Computes the distance between every pair of maze cells with one breadth first search per cell
+    /// That's quadratic in the number of cells, so for big mazes pass `sample` to only use that many randomly chosen cells.
+    pub fn distance_matrix(&self, sample: Option<usize>) -> DistanceMatrix {
+        self.distance_matrix_with_rng(sample, &mut ChaCha8Rng::from_entropy())
+    }
+
+    /// Same as `distance_matrix`, but samples cells with the given RNG so the result can be reproduced
+    pub fn distance_matrix_with_rng<R: Rng + ?Sized>(&self, sample: Option<usize>, rng: &mut R) -> DistanceMatrix {
+        let mut cells: Vec<CellIndex> = self.path_cells().collect();
+        if let Some(sample_size) = sample {
+            if sample_size < cells.len() {
+                cells = cells.choose_multiple(rng, sample_size).copied().collect();
+                cells.sort_unstable();
+            }
+        }
+
+        info!("Computing distance matrix for {} cells", cells.len());
+
+        let mut distances = Vec::with_capacity(cells.len() * cells.len());
+        let mut grid: Vec<Vec<Option<usize>>> = vec![vec![None; self.size.1]; self.size.0];
+        for &source in &cells {
+            self.bfs_distances_into(source, &mut grid);
+            distances.extend(cells.iter().map(|target| grid[target.0][target.1]));
+        }
+
+        info!("Distance matrix complete");
+
+        DistanceMatrix { cells, distances }
+    }
+
+    /// Breadth first search distances over the maze cells, reusing the given grid. Overwrites `distances`.
+    fn bfs_distances_into(&self, start: CellIndex, distances: &mut [Vec<Option<usize>>]) {
+        for col in distances.iter_mut() {
+            col.iter_mut().for_each(|distance| *distance = None);
+        }
+        distances[start.0][start.1] = Some(0);
+
+        let mut frontier = VecDeque::new();
+        frontier.push_back(start);
+
+        while let Some(current) = frontier.pop_front() {
+            let distance = distances[current.0][current.1].unwrap(); // Safe to unwrap since cells get a distance before being queued
+            for neighbor in self.neighbors(current) {
+                if distances[neighbor.0][neighbor.1].is_none() {
+                    distances[neighbor.0][neighbor.1] = Some(distance + 1);
+                    frontier.push_back(neighbor);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use alloc::vec::Vec;
+#[cfg(feature = "image")]
+use alloc::format;
+
+#[cfg(feature = "std")]
+use std::io::{self, Write};
+#[cfg(feature = "image")]
+use std::{fs::{self, File}, io::BufWriter, path::Path};
+
+#[cfg(feature = "image")]
+use image::{Rgb, RgbImage};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use super::{Maze, MazeError, CellIndex};
+
+/// A stack of floors of the same size, each its own maze, joined by stairs
+/// Floor 0 is the bottom. Every stair sits on the same cell on two neighboring floors, and no cell has more than one stair on it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Dungeon {
+    floors: Vec<Maze>,
+    stairs: Vec<Stair>,
+}
+
+/// A stair between a floor and the one above it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Stair {
+    /// The lower floor. The stair leads up to `floor + 1`.
+    pub floor: usize,
+    /// The cell it's on, on both floors
+    pub cell: CellIndex,
+}
+
+impl Dungeon {
+    /// Generates `floors` floors of `width` x `height` cells with `stairs` stairs between each pair of neighboring floors
+    /// A ChaCha8 RNG seeded with `seed` draws one u64 per floor from the bottom up, and each floor is made with `Maze::from_seed` using its own u64.
+    /// The same RNG then picks the stair cells, bottom pair of floors first. Fails with OutOfRange if there are no floors or the stairs
+    /// don't fit, since a floor in the middle of the stack needs a free cell for every stair up and every stair down.
+    pub fn from_seed(width: usize, height: usize, floors: usize, stairs: usize, seed: u64) -> Result<Dungeon, MazeError> {
+        if floors == 0 {
+            return Err(MazeError::OutOfRange { setting: "floors", got: 0.0, min: 1.0, max: f64::INFINITY });
+        }
+        let cells = width * height;
+        let max_stairs = if floors > 2 { cells / 2 } else { cells };
+        if floors > 1 && (stairs == 0 || stairs > max_stairs) {
+            return Err(MazeError::OutOfRange { setting: "stairs", got: stairs as f64, min: 1.0, max: max_stairs as f64 });
+        }
+
+        info!("Generating a dungeon of {} floors of {}x{} cells", floors, width, height);
+
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let seeds: Vec<u64> = (0..floors).map(|_| rng.gen()).collect();
+        let floors = seeds.into_iter()
+            .map(|seed| Maze::from_seed(width, height, seed))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut placed: Vec<Stair> = Vec::new();
+        for floor in 0..floors.len() - 1 {
+            // Cells already taken by the stairs coming up from the floor below
+            let mut taken: Vec<CellIndex> = placed.iter().filter(|stair| stair.floor + 1 == floor).map(|stair| stair.cell).collect();
+            for _ in 0..stairs {
+                let cell = loop {
+                    let cell = (rng.gen_range(0, width), rng.gen_range(0, height));
+                    if !taken.contains(&cell) {
+                        break cell;
+                    }
+                };
+                taken.push(cell);
+                placed.push(Stair { floor, cell });
+            }
+        }
+
+        debug!("Placed {} stairs", placed.len());
+
+        Ok(Dungeon { floors, stairs: placed })
+    }
+
+    /// Every floor, from the bottom up
+    pub fn floors(&self) -> &[Maze] {
+        &self.floors
+    }
+
+    /// Every stair, bottom floor first
+    pub fn stairs(&self) -> &[Stair] {
+        &self.stairs
+    }
+
+    /// The stairs leading up from a floor
+    pub fn stairs_up(&self, floor: usize) -> impl Iterator<Item = CellIndex> + '_ {
+        self.stairs.iter().filter(move |stair| stair.floor == floor).map(|stair| stair.cell)
+    }
+
+    /// The stairs leading down from a floor
+    pub fn stairs_down(&self, floor: usize) -> impl Iterator<Item = CellIndex> + '_ {
+        self.stairs.iter().filter(move |stair| stair.floor + 1 == floor).map(|stair| stair.cell)
+    }
+
+    /// Renders a floor like `build_marked_image`, with stairs up drawn blue and stairs down orange. Panics if there's no such floor.
+    #[cfg(feature = "image")]
+    pub fn build_floor_image(&self, floor: usize) -> RgbImage {
+        let mut img = self.floors[floor].build_marked_image();
+        let mut mark = |cell: CellIndex, color: Rgb<u8>| img.put_pixel(2 * cell.0 as u32 + 1, 2 * cell.1 as u32 + 1, color);
+        self.stairs_up(floor).for_each(|cell| mark(cell, Rgb([0, 0, 255])));
+        self.stairs_down(floor).for_each(|cell| mark(cell, Rgb([255, 128, 0])));
+        img
+    }
+
+    /// Saves every floor with `build_floor_image` as `floor_<n>.png` in `dir`, along with `dungeon.json` from `write_json`
+    /// The directory is created if needed.
+    #[cfg(feature = "image")]
+    pub fn save<P: AsRef<Path>>(&self, dir: P) -> Result<(), MazeError> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+
+        for floor in 0..self.floors.len() {
+            self.build_floor_image(floor).save(dir.join(format!("floor_{}.png", floor)))?;
+        }
+        let mut json = BufWriter::new(File::create(dir.join("dungeon.json"))?);
+        self.write_json(&mut json)?;
+        json.flush()?;
+
+        info!("Saved {} floors", self.floors.len());
+
+        Ok(())
+    }
+
+    /// Writes a JSON description of the dungeon: its size, each floor's image name from `save` and fingerprint, and every stair
+    /// Fingerprints are written as hex strings, since JSON numbers can't hold every u64.
+    #[cfg(feature = "std")]
+    pub fn write_json<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let (width, height) = self.floors.first().unwrap().size(); // Safe to unwrap since from_seed always makes at least one floor
+        writeln!(writer, "{{")?;
+        writeln!(writer, "  \"width\": {},", width)?;
+        writeln!(writer, "  \"height\": {},", height)?;
+
+        writeln!(writer, "  \"floors\": [")?;
+        for (i, floor) in self.floors.iter().enumerate() {
+            let comma = if i + 1 < self.floors.len() { "," } else { "" };
+            writeln!(writer, "    {{ \"floor\": {}, \"image\": \"floor_{}.png\", \"fingerprint\": \"{:016x}\" }}{}", i, i, floor.fingerprint(), comma)?;
+        }
+        writeln!(writer, "  ],")?;
+
+        writeln!(writer, "  \"stairs\": [")?;
+        for (i, stair) in self.stairs.iter().enumerate() {
+            let comma = if i + 1 < self.stairs.len() { "," } else { "" };
+            writeln!(writer, "    {{ \"from\": {}, \"to\": {}, \"x\": {}, \"y\": {} }}{}", stair.floor, stair.floor + 1, stair.cell.0, stair.cell.1, comma)?;
+        }
+        writeln!(writer, "  ]")?;
+        writeln!(writer, "}}")?;
+
+        Ok(())
+    }
+}
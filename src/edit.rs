@@ -0,0 +1,67 @@
+use super::{Maze, MazeError, CellIndex, Direction};
+
+impl Maze {
+    /// Opens the passage between two neighboring cells, carving either of them if they weren't already part of the maze
+    /// Both cells are updated together, so the passage is always open from both ends. Carving an already open passage does nothing.
+    pub fn carve(&mut self, from: CellIndex, to: CellIndex) -> Result<(), MazeError> {
+        self.passage_direction(from, to)?;
+
+        info!("Carving passage between ({}, {}) and ({}, {})", from.0, from.1, to.0, to.1);
+        self.open_passage(from, to);
+
+        Ok(())
+    }
+
+    /// Same as `carve`, but first checks the passage wouldn't open up a 2x2 room, so hand edits keep the maze made of corridors
+    /// Returns `MazeError::WouldOpenBlock` with the top left cell of the room and leaves the maze untouched if it would.
+    pub fn carve_without_rooms(&mut self, from: CellIndex, to: CellIndex) -> Result<(), MazeError> {
+        let direction = self.passage_direction(from, to)?;
+
+        // The top left corner of each 2x2 block on either side of the passage
+        let (x, y) = if direction == Direction::Right || direction == Direction::Down { from } else { to };
+        let horizontal = direction == Direction::Right || direction == Direction::Left;
+        let corners = if horizontal { [y.checked_sub(1).map(|y| (x, y)), Some((x, y))] } else { [x.checked_sub(1).map(|x| (x, y)), Some((x, y))] };
+        let open = |a: CellIndex, b: CellIndex| (a, b) == (from, to) || (a, b) == (to, from) || Direction::between(a, b).is_some_and(|direction| self.can_move(a, direction));
+        for corner in corners.iter().flatten().copied().filter(|&(x, y)| x + 1 < self.size.0 && y + 1 < self.size.1) {
+            let (right, below, diagonal) = ((corner.0 + 1, corner.1), (corner.0, corner.1 + 1), (corner.0 + 1, corner.1 + 1));
+            if open(corner, right) && open(corner, below) && open(right, diagonal) && open(below, diagonal) {
+                return Err(MazeError::WouldOpenBlock { corner });
+            }
+        }
+
+        self.carve(from, to)
+    }
+
+    /// Closes the passage between two neighboring cells. Both cells stay carved, even if this leaves one of them cut off.
+    /// Filling an already closed passage does nothing. Use `fill_connected` to refuse fills that would split the maze.
+    pub fn fill(&mut self, from: CellIndex, to: CellIndex) -> Result<(), MazeError> {
+        let direction = self.passage_direction(from, to)?;
+
+        info!("Filling passage between ({}, {}) and ({}, {})", from.0, from.1, to.0, to.1);
+        self.update_cell(from, |cell| cell.close(direction));
+        self.update_cell(to, |cell| cell.close(direction.reverse()));
+        self.make_two_way(from, to);
+
+        Ok(())
+    }
+
+    /// Same as `fill`, but first checks the two cells would still be connected some other way, so the maze stays in one piece
+    /// Returns `MazeError::WouldDisconnect` and leaves the maze untouched if not. On a perfect maze every fill disconnects it.
+    pub fn fill_connected(&mut self, from: CellIndex, to: CellIndex) -> Result<(), MazeError> {
+        self.passage_direction(from, to)?;
+
+        let is_passage = |a: CellIndex, b: CellIndex| (a, b) == (from, to) || (a, b) == (to, from);
+        if self.is_path(from) && self.breadth_first_search(from, to, is_passage).is_none() {
+            return Err(MazeError::WouldDisconnect { from, to });
+        }
+
+        self.fill(from, to)
+    }
+
+    /// The direction from one cell to the other, or an error if they aren't neighbors inside the maze
+    pub(super) fn passage_direction(&self, from: CellIndex, to: CellIndex) -> Result<Direction, MazeError> {
+        Direction::between(from, to)
+            .filter(|&direction| self.step(from, direction) == Some(to))
+            .ok_or(MazeError::NotAdjacent { from, to })
+    }
+}
@@ -0,0 +1,107 @@
+use alloc::{vec, vec::Vec};
+
+use rand::Rng;
+
+use super::{Maze, MazeError, Cell, Direction};
+
+/// Iterator over the rows of a maze generated with Eller's algorithm, top to bottom, see `Maze::eller_rows`
+/// Only the current row is kept, so memory grows with the width of the maze and not its height. Each row is yielded
+/// as soon as it's finished and is never changed afterwards, so it can be written straight out.
+#[derive(Debug, Clone)]
+pub struct EllerRows<R> {
+    rng: R,
+    width: usize,
+    rows_left: usize,
+    /// The cells of each set form a circular list in column order, given by each cell's previous and next member
+    previous: Vec<usize>,
+    next: Vec<usize>,
+    /// Which cells of the next row have a passage up into the row before
+    open_up: Vec<bool>,
+}
+
+impl<R: Rng> Iterator for EllerRows<R> {
+    type Item = Vec<Cell>;
+
+    fn next(&mut self) -> Option<Vec<Cell>> {
+        if self.rows_left == 0 {
+            return None;
+        }
+        self.rows_left -= 1;
+        let last_row = self.rows_left == 0;
+
+        let mut row = vec![Cell::default(); self.width];
+        for (cell, &open_up) in row.iter_mut().zip(&self.open_up) {
+            cell.carve();
+            if open_up {
+                cell.open(Direction::Up);
+            }
+        }
+
+        // Randomly join neighbors that aren't connected yet. The last row joins all of them, so the maze ends up connected.
+        for x in 0..self.width - 1 {
+            if self.next[x] != x + 1 && (last_row || self.rng.gen()) {
+                self.next[self.previous[x + 1]] = self.next[x];
+                self.previous[self.next[x]] = self.previous[x + 1];
+                self.next[x] = x + 1;
+                self.previous[x + 1] = x;
+
+                row[x].open(Direction::Right);
+                row[x + 1].open(Direction::Left);
+            }
+        }
+
+        // Randomly carve down, keeping at least one passage down out of each set. Cells that don't carve down leave their set.
+        if !last_row {
+            for (x, cell) in row.iter_mut().enumerate() {
+                if self.previous[x] != x && self.rng.gen() {
+                    self.next[self.previous[x]] = self.next[x];
+                    self.previous[self.next[x]] = self.previous[x];
+                    self.previous[x] = x;
+                    self.next[x] = x;
+                    self.open_up[x] = false;
+                } else {
+                    cell.open(Direction::Down);
+                    self.open_up[x] = true;
+                }
+            }
+        }
+
+        Some(row)
+    }
+}
+
+impl Maze {
+    /// Generates a perfect maze one row at a time with Eller's algorithm, for mazes too tall to hold in memory
+    /// The rows come out top to bottom, each one `width` cells, and are final as soon as they're yielded. Working memory only
+    /// depends on the width. The mazes aren't uniformly random like `with_rng`'s: they tend to have more short horizontal passages.
+    /// Sizes are in cells, and must be at least 1.
+    pub fn eller_rows<R: Rng>(width: usize, height: usize, rng: R) -> Result<EllerRows<R>, MazeError> {
+        if width == 0 || height == 0 {
+            return Err(MazeError::InvalidDimensions { got: (width, height) });
+        }
+
+        info!("Generating a {}x{} maze row by row", width, height);
+
+        Ok(EllerRows {
+            rng,
+            width,
+            rows_left: height,
+            previous: (0..width).collect(),
+            next: (0..width).collect(),
+            open_up: vec![false; width],
+        })
+    }
+
+    /// Generates a whole maze with Eller's algorithm, collecting the rows from `eller_rows` into one
+    /// For when the different texture is wanted rather than the low memory use: this holds the whole maze like `with_rng` does.
+    pub fn from_eller<R: Rng>(width: usize, height: usize, rng: R) -> Result<Maze, MazeError> {
+        let rows = Maze::eller_rows(width, height, rng)?;
+        let mut maze = Maze::empty(width, height)?;
+        for (y, row) in rows.enumerate() {
+            for (x, cell) in row.into_iter().enumerate() {
+                maze.update_cell((x, y), |old| *old = cell);
+            }
+        }
+        Ok(maze)
+    }
+}
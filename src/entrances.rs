@@ -0,0 +1,174 @@
+use alloc::{string::String, vec::Vec};
+
+use super::{Maze, MazeError, CellIndex, Direction};
+
+/// Where to open the entrance and exit of a maze
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntrancePlacement {
+    /// Anywhere on the border, at the two openings with the longest path between them
+    Farthest,
+    /// One opening on each of the given sides (Up is the top edge, etc.), placed as far apart as possible along the maze
+    Sides(Direction, Direction),
+    /// Exactly the given entrance and exit openings
+    At(Opening, Opening),
+}
+
+/// A hole in the outer wall: a cell on the border of the maze, and which of its sides is open to the outside
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Opening {
+    /// The cell just inside the opening
+    pub cell: CellIndex,
+    /// The side of the cell facing out of the maze
+    pub side: Direction,
+}
+
+impl Opening {
+    /// The pixel of the hole in the rendered maze, in the wall just outside the cell
+    pub fn pixel(self) -> CellIndex {
+        let (x, y) = (2 * self.cell.0 + 1, 2 * self.cell.1 + 1);
+        match self.side {
+            Direction::Up => (x, y - 1),
+            Direction::Right => (x + 1, y),
+            Direction::Down => (x, y + 1),
+            Direction::Left => (x - 1, y),
+        }
+    }
+}
+
+impl Maze {
+    /// Opens two border walls so the maze has an entrance and an exit, replacing any openings made before
+    /// Returns the (entrance, exit) cells whose outer walls were opened, or None if there aren't two path cells on the requested sides.
+    /// For `EntrancePlacement::At`, returns None if either opening isn't on the border of a path cell.
+    pub fn open_entrances(&mut self, placement: EntrancePlacement) -> Option<(CellIndex, CellIndex)> {
+        info!("Placing entrances: {:?}", placement);
+
+        let (entrance, exit) = match placement {
+            EntrancePlacement::Farthest => {
+                let all: Vec<Opening> = Direction::ALL.iter().flat_map(|&side| self.border_candidates(side)).collect();
+                self.farthest_pair(&all, &all)?
+            },
+            EntrancePlacement::Sides(entrance_side, exit_side) => self.farthest_pair(&self.border_candidates(entrance_side), &self.border_candidates(exit_side))?,
+            EntrancePlacement::At(entrance, exit) => {
+                if !self.is_valid_opening(entrance) || !self.is_valid_opening(exit) {
+                    return None;
+                }
+                (entrance, exit)
+            },
+        };
+
+        if let Some((old_entrance, old_exit)) = self.entrances.take() {
+            self.close_unused(old_entrance);
+            self.close_unused(old_exit);
+        }
+
+        self.update_cell(entrance.cell, |cell| cell.open(entrance.side));
+        self.update_cell(exit.cell, |cell| cell.open(exit.side));
+        self.entrances = Some((entrance, exit));
+
+        info!("Opened entrance at ({}, {}) and exit at ({}, {})", entrance.cell.0, entrance.cell.1, exit.cell.0, exit.cell.1);
+
+        Some((entrance.cell, exit.cell))
+    }
+
+    /// The (entrance, exit) openings made by `open_entrances`, if it's been called
+    pub fn entrances(&self) -> Option<(Opening, Opening)> {
+        self.entrances
+    }
+
+    /// Opens a hole in the outer wall under the given label, for mazes with more ways in than an entrance and an exit
+    /// Labeled openings are kept alongside the entrances and survive `open_entrances`. Fails with InvalidOpening if the opening
+    /// isn't on the border of a path cell, or DuplicateLabel if another opening already has the label.
+    pub fn add_opening<L: Into<String>>(&mut self, label: L, opening: Opening) -> Result<(), MazeError> {
+        let label = label.into();
+        if !self.is_valid_opening(opening) {
+            return Err(MazeError::InvalidOpening { opening });
+        }
+        if self.opening(&label).is_some() {
+            return Err(MazeError::DuplicateLabel(label));
+        }
+
+        info!("Opened {} at ({}, {})", label, opening.cell.0, opening.cell.1);
+
+        self.update_cell(opening.cell, |cell| cell.open(opening.side));
+        self.openings.push((label, opening));
+        Ok(())
+    }
+
+    /// Closes the labeled opening and returns where it was, or None if there's no opening with that label
+    pub fn remove_opening(&mut self, label: &str) -> Option<Opening> {
+        let index = self.openings.iter().position(|(existing, _)| existing == label)?;
+        let (_, opening) = self.openings.remove(index);
+        self.close_unused(opening);
+        Some(opening)
+    }
+
+    /// The opening with the given label
+    pub fn opening(&self, label: &str) -> Option<Opening> {
+        self.openings.iter().find(|(existing, _)| existing == label).map(|&(_, opening)| opening)
+    }
+
+    /// Every labeled opening, in the order they were added
+    pub fn openings(&self) -> impl Iterator<Item = (&str, Opening)> + '_ {
+        self.openings.iter().map(|(label, opening)| (label.as_str(), *opening))
+    }
+
+    /// The shortest path between two labeled openings, or None if either label doesn't exist
+    pub fn solve_openings(&self, from: &str, to: &str) -> Option<Vec<CellIndex>> {
+        self.solve(self.opening(from)?.cell, self.opening(to)?.cell)
+    }
+
+    /// Closes the hole in the outer wall, unless the entrances or a labeled opening still use it
+    fn close_unused(&mut self, opening: Opening) {
+        let in_entrances = self.entrances.is_some_and(|(entrance, exit)| entrance == opening || exit == opening);
+        if !in_entrances && !self.openings.iter().any(|&(_, existing)| existing == opening) {
+            self.update_cell(opening.cell, |cell| cell.close(opening.side));
+        }
+    }
+
+    /// Returns true if the opening is on the outer wall of a path cell
+    pub(super) fn is_valid_opening(&self, opening: Opening) -> bool {
+        self.is_path(opening.cell) && self.step(opening.cell, opening.side).is_none()
+    }
+
+    /// Every path cell along the given side of the border
+    fn border_candidates(&self, side: Direction) -> Vec<Opening> {
+        let (x_max, y_max) = (self.size.0 - 1, self.size.1 - 1);
+        let cells: Vec<CellIndex> = match side {
+            Direction::Up => (0..=x_max).map(|x| (x, 0)).collect(),
+            Direction::Down => (0..=x_max).map(|x| (x, y_max)).collect(),
+            Direction::Left => (0..=y_max).map(|y| (0, y)).collect(),
+            Direction::Right => (0..=y_max).map(|y| (x_max, y)).collect(),
+        };
+
+        cells.into_iter()
+            .filter(|&cell| self.is_path(cell))
+            .map(|cell| Opening { cell, side })
+            .collect()
+    }
+
+    /// An (entrance, exit) pair with a long path between them
+    /// Same double sweep as longest_path, but only the candidates are allowed as endpoints.
+    /// First find the exit farthest from an arbitrary entrance, then the entrance farthest from that exit.
+    fn farthest_pair(&self, entrance_candidates: &[Opening], exit_candidates: &[Opening]) -> Option<(Opening, Opening)> {
+        let exit = self.farthest_candidate(*entrance_candidates.first()?, exit_candidates)?;
+        let entrance = self.farthest_candidate(exit, entrance_candidates)?;
+
+        Some((entrance, exit))
+    }
+
+    /// The candidate with the longest path to `from`, never picking the same opening as `from`
+    fn farthest_candidate(&self, from: Opening, candidates: &[Opening]) -> Option<Opening> {
+        let distances = self.bfs_distances(from.cell);
+
+        let mut farthest: Option<(Opening, usize)> = None;
+        for &candidate in candidates.iter().filter(|&&candidate| candidate != from) {
+            if let Some(distance) = distances[candidate.cell.0][candidate.cell.1] {
+                if farthest.is_none_or(|(_, best)| distance > best) {
+                    farthest = Some((candidate, distance));
+                }
+            }
+        }
+
+        farthest.map(|(candidate, _)| candidate)
+    }
+}
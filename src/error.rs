@@ -0,0 +1,213 @@
+use core::fmt;
+use alloc::{boxed::Box, string::String};
+#[cfg(feature = "std")]
+use std::error::Error;
+#[cfg(feature = "std")]
+use std::io;
+
+#[cfg(feature = "image")]
+use image::ImageError;
+
+use super::{Maze, CellIndex, Opening};
+
+/// Everything that can go wrong when generating, analyzing or saving a maze
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum MazeError {
+    /// The requested maze size can't be generated. Mazes need at least one cell in each direction.
+    InvalidDimensions {
+        /// The (width, height) size that was asked for, in cells
+        got: (usize, usize),
+    },
+    /// Two mazes needed to be the same size, but weren't
+    SizeMismatch {
+        /// Size of the maze the operation was called on
+        expected: (usize, usize),
+        /// Size of the other maze
+        got: (usize, usize),
+    },
+    /// There weren't enough path cells along the requested sides to open an entrance and exit
+    NoEntrances,
+    /// `Maze::generate_until` gave up without finding a maze that met the constraints
+    ConstraintsNotMet {
+        /// Number of mazes generated before giving up
+        attempts: usize,
+    },
+    /// A passage was asked for between two cells that aren't next to each other inside the maze
+    NotAdjacent {
+        /// The cell the passage starts from
+        from: CellIndex,
+        /// The cell the passage leads to
+        to: CellIndex,
+    },
+    /// A region that's empty or doesn't fit inside the maze
+    InvalidRegion {
+        /// The (x, y) corner the region starts at, inclusive
+        from: CellIndex,
+        /// The (x, y) corner the region ends at, exclusive
+        to: CellIndex,
+    },
+    /// Two mazes being stitched together both cover the same cell
+    Overlap {
+        /// The canvas cell covered twice
+        cell: CellIndex,
+    },
+    /// The path cells form more than one connected region
+    Disconnected {
+        /// How many separate regions there are
+        regions: usize,
+    },
+    /// Filling the passage would split the maze in two
+    WouldDisconnect {
+        /// The cell the passage starts from
+        from: CellIndex,
+        /// The cell the passage leads to
+        to: CellIndex,
+    },
+    /// Carving the passage would open up a 2x2 room
+    WouldOpenBlock {
+        /// The top left cell of the room
+        corner: CellIndex,
+    },
+    /// An opening that isn't on the outer wall of a path cell
+    InvalidOpening {
+        /// The opening that was asked for
+        opening: Opening,
+    },
+    /// Another opening already has this label
+    DuplicateLabel(String),
+    /// A setting that's outside the range it can be met in, e.g. a solution longer than the whole maze
+    OutOfRange {
+        /// Name of the setting
+        setting: &'static str,
+        /// The value it was given
+        got: f64,
+        /// Smallest value that works, inclusive
+        min: f64,
+        /// Largest value that works, inclusive
+        max: f64,
+    },
+    /// A portal that can't be placed, because an end is a wall, both ends are the same cell, or an end already has a portal
+    InvalidPortal {
+        /// One end of the portal
+        from: CellIndex,
+        /// The other end
+        to: CellIndex,
+    },
+    /// Two neighboring cells that have a wall between them instead of a passage
+    NoPassage {
+        /// The cell on one side of the wall
+        from: CellIndex,
+        /// The cell on the other side
+        to: CellIndex,
+    },
+    /// A cell that's outside the maze
+    OutOfBounds {
+        /// The cell that was asked for
+        cell: CellIndex,
+    },
+    /// A scale factor, corridor width or wall thickness of zero, which would leave nothing to draw
+    InvalidScale,
+    /// A frame buffer to patch wasn't the size the maze renders to
+    FrameSizeMismatch {
+        /// Bytes a rendering of the maze takes
+        expected: usize,
+        /// Bytes in the frame
+        got: usize,
+    },
+    /// Fewer cells than asked for fit the rules for placing points of interest
+    NoRoom {
+        /// The label the points were to be tagged with
+        label: String,
+        /// How many fit before the cells ran out
+        placed: usize,
+        /// How many were asked for
+        wanted: usize,
+    },
+    /// Generation was cancelled before the maze was finished
+    Cancelled {
+        /// The maze as far as it got. Only some of its cells are carved.
+        partial: Box<Maze>,
+    },
+    /// A difficulty name that isn't easy, medium or hard
+    InvalidDifficulty(String),
+    /// An algorithm name that isn't wilson, eller or regions
+    InvalidAlgorithm(String),
+    /// An image or tile grid couldn't be read as a maze, with the reason why
+    InvalidImage(String),
+    /// Saved maze data that's truncated, corrupt or not a maze at all, with the reason why
+    InvalidFormat(String),
+    /// Saved maze data written by a newer version of the crate
+    UnsupportedVersion {
+        /// The format version found in the data
+        version: u16,
+    },
+    /// Reading or writing a file failed
+    #[cfg(feature = "std")]
+    Io(io::Error),
+    /// Encoding or saving an image failed
+    #[cfg(feature = "image")]
+    Render(ImageError),
+}
+
+impl fmt::Display for MazeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MazeError::InvalidDimensions { got } => write!(f, "Mazes must be at least 1x1 cells, got {}x{}", got.0, got.1),
+            MazeError::SizeMismatch { expected, got } => write!(f, "Mazes must be the same size, expected {}x{} but got {}x{}", expected.0, expected.1, got.0, got.1),
+            MazeError::NoEntrances => write!(f, "Couldn't place entrances on the requested sides"),
+            MazeError::ConstraintsNotMet { attempts } => write!(f, "No maze met the constraints within {} attempts", attempts),
+            MazeError::NotAdjacent { from, to } => write!(f, "Cells ({}, {}) and ({}, {}) aren't neighbors inside the maze", from.0, from.1, to.0, to.1),
+            MazeError::InvalidRegion { from, to } => write!(f, "Region ({}, {}) to ({}, {}) is empty or outside the maze", from.0, from.1, to.0, to.1),
+            MazeError::Overlap { cell } => write!(f, "More than one piece covers cell ({}, {})", cell.0, cell.1),
+            MazeError::Disconnected { regions } => write!(f, "Maze is split into {} disconnected regions", regions),
+            MazeError::WouldDisconnect { from, to } => write!(f, "Filling the passage between ({}, {}) and ({}, {}) would disconnect the maze", from.0, from.1, to.0, to.1),
+            MazeError::WouldOpenBlock { corner } => write!(f, "Carving the passage would open a 2x2 room at ({}, {})", corner.0, corner.1),
+            MazeError::Cancelled { partial } => write!(f, "Generation was cancelled with {} of {} cells carved", partial.path_cells().count(), partial.size.0 * partial.size.1),
+            MazeError::InvalidOpening { opening } => write!(f, "The {:?} side of cell ({}, {}) isn't on the outer wall of a path cell", opening.side, opening.cell.0, opening.cell.1),
+            MazeError::DuplicateLabel(label) => write!(f, "There's already an opening labeled {}", label),
+            MazeError::OutOfRange { setting, got, min, max } => write!(f, "{} must be between {} and {}, got {}", setting, min, max, got),
+            MazeError::InvalidPortal { from, to } => write!(f, "Can't link ({}, {}) and ({}, {}) with a portal", from.0, from.1, to.0, to.1),
+            MazeError::NoPassage { from, to } => write!(f, "There's no passage between ({}, {}) and ({}, {})", from.0, from.1, to.0, to.1),
+            MazeError::OutOfBounds { cell } => write!(f, "Cell ({}, {}) is outside the maze", cell.0, cell.1),
+            MazeError::InvalidScale => write!(f, "Scale factor, corridor width and wall thickness must be at least 1"),
+            MazeError::FrameSizeMismatch { expected, got } => write!(f, "Frame must be {} bytes to fit the maze, got {}", expected, got),
+            MazeError::NoRoom { label, placed, wanted } => write!(f, "Only {} of {} points labeled {} fit the placement rules", placed, wanted, label),
+            MazeError::InvalidDifficulty(name) => write!(f, "Difficulty must be one of easy, medium or hard, got {}", name),
+            MazeError::InvalidAlgorithm(name) => write!(f, "Algorithm must be wilson, eller or regions, got {}", name),
+            MazeError::InvalidImage(reason) => write!(f, "Image isn't a maze: {}", reason),
+            MazeError::InvalidFormat(reason) => write!(f, "Data isn't a saved maze: {}", reason),
+            MazeError::UnsupportedVersion { version } => write!(f, "Maze format version {} is newer than the supported version {}", version, Maze::FORMAT_VERSION),
+            #[cfg(feature = "std")]
+            MazeError::Io(err) => write!(f, "I/O error: {}", err),
+            #[cfg(feature = "image")]
+            MazeError::Render(err) => write!(f, "Image error: {}", err),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for MazeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            MazeError::Io(err) => Some(err),
+            #[cfg(feature = "image")]
+            MazeError::Render(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<io::Error> for MazeError {
+    fn from(err: io::Error) -> Self {
+        MazeError::Io(err)
+    }
+}
+
+#[cfg(feature = "image")]
+impl From<ImageError> for MazeError {
+    fn from(err: ImageError) -> Self {
+        MazeError::Render(err)
+    }
+}
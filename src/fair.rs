@@ -0,0 +1,98 @@
+use alloc::{format, vec::Vec};
+
+use rand::{Rng, seq::SliceRandom};
+
+use super::{Maze, MazeError, CellIndex, Direction, Opening};
+
+impl Maze {
+    /// Generates mazes until one has an opening for every player whose shortest path to the middle cell is within `tolerance` steps of all the others
+    /// Players take turns around the sides, the first on the top edge and the rest clockwise from there, so nobody starts next to anyone else
+    /// until there are more than four. The openings are labeled "player1", "player2" and so on, and the middle cell is tagged "goal".
+    /// Of all the ways to fit the players, the one with the longest paths is used. Returns the maze along with the number of attempts it took.
+    /// Fails with OutOfRange for fewer than two players, more players on a side than it has cells, or no attempts, and ConstraintsNotMet
+    /// if no maze balanced within `max_attempts`.
+    pub fn generate_fair<R: Rng + ?Sized>(width: usize, height: usize, players: usize, tolerance: usize, max_attempts: usize, rng: &mut R) -> Result<(Maze, usize), MazeError> {
+        if width == 0 || height == 0 {
+            return Err(MazeError::InvalidDimensions { got: (width, height) });
+        }
+        let sides = fair_sides(players);
+        let shortest_side = width.min(height);
+        // The top edge always gets the most players, since it's dealt to first
+        if players < 2 || sides[0].1 > shortest_side {
+            return Err(MazeError::OutOfRange { setting: "players", got: players as f64, min: 2.0, max: (4 * shortest_side) as f64 });
+        }
+        if max_attempts == 0 {
+            return Err(MazeError::OutOfRange { setting: "max_attempts", got: 0.0, min: 1.0, max: f64::INFINITY });
+        }
+
+        let goal = (width / 2, height / 2);
+        for attempt in 1..=max_attempts {
+            info!("Fair generation attempt {} of {}", attempt, max_attempts);
+
+            let mut maze = Maze::with_rng(width, height, rng)?;
+            if let Some(starts) = maze.fair_openings(goal, &sides, tolerance, rng) {
+                for (player, &opening) in starts.iter().enumerate() {
+                    maze.add_opening(format!("player{}", player + 1), opening)?;
+                }
+                maze.add_tag(goal, "goal")?;
+
+                info!("Paths balanced after {} attempts", attempt);
+                return Ok((maze, attempt));
+            }
+        }
+
+        Err(MazeError::ConstraintsNotMet { attempts: max_attempts })
+    }
+
+    /// Picks the openings for `generate_fair`, in player order, or None if the paths to the goal can't be balanced
+    fn fair_openings<R: Rng + ?Sized>(&self, goal: CellIndex, sides: &[(Direction, usize); 4], tolerance: usize, rng: &mut R) -> Option<Vec<Opening>> {
+        let distances = self.bfs_distances(goal);
+        let border = |side: Direction| -> Vec<(usize, Opening)> {
+            let cells: Vec<CellIndex> = match side {
+                Direction::Up => (0..self.size.0).map(|x| (x, 0)).collect(),
+                Direction::Right => (0..self.size.1).map(|y| (self.size.0 - 1, y)).collect(),
+                Direction::Down => (0..self.size.0).map(|x| (x, self.size.1 - 1)).collect(),
+                Direction::Left => (0..self.size.1).map(|y| (0, y)).collect(),
+            };
+            cells.into_iter()
+                .filter_map(|cell| distances[cell.0][cell.1].map(|distance| (distance, Opening { cell, side })))
+                .collect()
+        };
+        let candidates: Vec<Vec<(usize, Opening)>> = sides.iter().map(|&(side, _)| border(side)).collect();
+
+        // Try each distance as the shortest path, longest first, until every side has enough openings in range
+        let mut lows: Vec<usize> = candidates.iter().flatten().map(|&(distance, _)| distance).collect();
+        lows.sort_unstable_by(|a, b| b.cmp(a));
+        lows.dedup();
+        let low = lows.into_iter().find(|&low| sides.iter().zip(&candidates).all(|(&(_, needed), candidates)| {
+            candidates.iter().filter(|&&(distance, _)| (low..=low + tolerance).contains(&distance)).count() >= needed
+        }))?;
+
+        // Each side's openings in range, shuffled so players are dealt them in a random order
+        let mut in_range: Vec<Vec<Opening>> = candidates.iter()
+            .map(|candidates| candidates.iter()
+                .filter(|&&(distance, _)| (low..=low + tolerance).contains(&distance))
+                .map(|&(_, opening)| opening)
+                .collect())
+            .collect();
+        in_range.iter_mut().for_each(|openings| openings.shuffle(rng));
+
+        let players: usize = sides.iter().map(|side| side.1).sum();
+        let openings = (0..players).map(|player| {
+            in_range[player % 4].pop().unwrap() // Safe to unwrap since every side was checked to have enough openings
+        }).collect();
+
+        debug!("Balanced openings {:?} with shortest path {}", openings, low);
+
+        Some(openings)
+    }
+}
+
+/// Each side players start on in `generate_fair`, clockwise from the top, with how many of them start there
+fn fair_sides(players: usize) -> [(Direction, usize); 4] {
+    let mut sides = [(Direction::Up, 0), (Direction::Right, 0), (Direction::Down, 0), (Direction::Left, 0)];
+    for player in 0..players {
+        sides[player % 4].1 += 1;
+    }
+    sides
+}
@@ -0,0 +1,28 @@
+use super::Maze;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+impl Maze {
+    /// A 64 bit hash of the maze's size and cells, for deduplicating mazes and checking generation is reproducible
+    /// Uses FNV-1a over a fixed byte layout rather than std's Hasher, so the value is the same on every machine and Rust version.
+    /// Only the cells are hashed, so it isn't affected by how the maze is rendered.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hash = FNV_OFFSET_BASIS;
+        let mut feed = |byte: u8| {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(FNV_PRIME);
+        };
+
+        for dimension in [self.size.0 as u64, self.size.1 as u64] {
+            dimension.to_le_bytes().iter().for_each(|&byte| feed(byte));
+        }
+
+        // Column by column, one byte per cell holding its open sides and whether it's carved
+        for (_, cell) in self.cells() {
+            feed(cell.0);
+        }
+
+        hash
+    }
+}
@@ -0,0 +1,310 @@
+use alloc::{format, string::String, vec::Vec};
+#[cfg(feature = "std")]
+use std::{fs, path::Path};
+
+use super::{Maze, MazeError, Cell, CellIndex, Direction, Opening};
+
+/// Every saved maze starts with these bytes
+const MAGIC: &[u8; 4] = b"MAZE";
+
+/// Reads little endian numbers off the front of a byte slice, failing with InvalidFormat when it runs out
+pub(super) struct Reader<'a> {
+    pub(super) bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub(super) fn take(&mut self, len: usize, what: &str) -> Result<&'a [u8], MazeError> {
+        if self.bytes.len() < len {
+            return Err(MazeError::InvalidFormat(format!("data ends before the {}", what)));
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    pub(super) fn u8(&mut self, what: &str) -> Result<u8, MazeError> {
+        Ok(self.take(1, what)?[0])
+    }
+
+    pub(super) fn u16(&mut self, what: &str) -> Result<u16, MazeError> {
+        let bytes = self.take(2, what)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    pub(super) fn u32(&mut self, what: &str) -> Result<u32, MazeError> {
+        let bytes = self.take(4, what)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    pub(super) fn u64(&mut self, what: &str) -> Result<u64, MazeError> {
+        let bytes = self.take(8, what)?;
+        let mut word = [0; 8];
+        word.copy_from_slice(bytes);
+        Ok(u64::from_le_bytes(word))
+    }
+}
+
+fn side_to_byte(side: Direction) -> u8 {
+    match side {
+        Direction::Up => 0,
+        Direction::Right => 1,
+        Direction::Down => 2,
+        Direction::Left => 3,
+    }
+}
+
+fn side_from_byte(byte: u8) -> Result<Direction, MazeError> {
+    match byte {
+        0 => Ok(Direction::Up),
+        1 => Ok(Direction::Right),
+        2 => Ok(Direction::Down),
+        3 => Ok(Direction::Left),
+        _ => Err(MazeError::InvalidFormat(format!("{} isn't a side", byte))),
+    }
+}
+
+fn write_opening(bytes: &mut Vec<u8>, opening: Opening) {
+    bytes.extend_from_slice(&(opening.cell.0 as u32).to_le_bytes());
+    bytes.extend_from_slice(&(opening.cell.1 as u32).to_le_bytes());
+    bytes.push(side_to_byte(opening.side));
+}
+
+fn read_opening(reader: &mut Reader<'_>) -> Result<Opening, MazeError> {
+    let x = reader.u32("opening")? as usize;
+    let y = reader.u32("opening")? as usize;
+    let side = side_from_byte(reader.u8("opening")?)?;
+    Ok(Opening { cell: (x, y), side })
+}
+
+fn write_string(bytes: &mut Vec<u8>, string: &str) {
+    bytes.extend_from_slice(&(string.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(string.as_bytes());
+}
+
+fn read_string(reader: &mut Reader<'_>, what: &str) -> Result<String, MazeError> {
+    let len = reader.u32(what)? as usize;
+    String::from_utf8(reader.take(len, what)?.to_vec()).map_err(|_| MazeError::InvalidFormat(format!("{} isn't UTF-8", what)))
+}
+
+/// A u32 count followed by that many pairs of cells, each as four u32s
+fn read_pairs(reader: &mut Reader<'_>, what: &str) -> Result<Vec<(CellIndex, CellIndex)>, MazeError> {
+    let count = reader.u32(what)?;
+    let mut pairs = Vec::new();
+    for _ in 0..count {
+        let from = (reader.u32(what)? as usize, reader.u32(what)? as usize);
+        let to = (reader.u32(what)? as usize, reader.u32(what)? as usize);
+        pairs.push((from, to));
+    }
+    Ok(pairs)
+}
+
+impl Maze {
+    /// Version of the format written by `to_bytes`
+    /// Bumped whenever the layout changes. Older versions can still be read, and are migrated to the current model when loaded.
+    pub const FORMAT_VERSION: u16 = 5;
+
+    /// Serializes the maze in a stable binary format, always as the latest `FORMAT_VERSION`
+    ///
+    /// Version 5 is laid out as, with all numbers little endian:
+    /// - the magic bytes `MAZE`, then the version as a u16
+    /// - width and height in cells, as u32s
+    /// - one byte per cell, row by row from the top left. Bits 0 to 3 are open sides (up, right, down, left) and bit 4 is set for carved cells.
+    /// - a u8 that's 1 if the maze has entrances, followed by the entrance and exit openings. Each opening is its x and y as u32s
+    ///   and its side as a u8 (0 up, 1 right, 2 down, 3 left).
+    /// - the number of labeled openings as a u32, then for each one its label and the opening. Strings are written as their length in bytes as a u32, then the UTF-8 bytes.
+    /// - the number of portals as a u32, then the x and y of both ends of each one as u32s
+    /// - the number of one-way passages as a u32, then the x and y of the cell each one leads from and the cell it leads to, as u32s
+    /// - the number of tagged cells as a u32, then for each one its x and y as u32s, its number of tags as a u32, and each tag as a string
+    ///
+    /// Each older version leaves off the last section: version 4 has no tags, version 3 no one-way passages, version 2 no portals and version 1 no labeled openings.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let (width, height) = self.size;
+        let mut bytes = Vec::with_capacity(4 + 2 + 8 + width * height + 1 + 18 + 4);
+
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&Maze::FORMAT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(width as u32).to_le_bytes());
+        bytes.extend_from_slice(&(height as u32).to_le_bytes());
+        bytes.extend(self.cells.row_major(self.size).iter().map(|cell| cell.0));
+
+        match self.entrances {
+            Some((entrance, exit)) => {
+                bytes.push(1);
+                write_opening(&mut bytes, entrance);
+                write_opening(&mut bytes, exit);
+            },
+            None => bytes.push(0),
+        }
+
+        bytes.extend_from_slice(&(self.openings.len() as u32).to_le_bytes());
+        for (label, opening) in &self.openings {
+            write_string(&mut bytes, label);
+            write_opening(&mut bytes, *opening);
+        }
+
+        for pairs in &[&self.portals, &self.one_way] {
+            bytes.extend_from_slice(&(pairs.len() as u32).to_le_bytes());
+            for &(a, b) in pairs.iter() {
+                for &value in &[a.0, a.1, b.0, b.1] {
+                    bytes.extend_from_slice(&(value as u32).to_le_bytes());
+                }
+            }
+        }
+
+        bytes.extend_from_slice(&(self.tags.len() as u32).to_le_bytes());
+        for (cell, tags) in &self.tags {
+            bytes.extend_from_slice(&(cell.0 as u32).to_le_bytes());
+            bytes.extend_from_slice(&(cell.1 as u32).to_le_bytes());
+            bytes.extend_from_slice(&(tags.len() as u32).to_le_bytes());
+            for tag in tags {
+                write_string(&mut bytes, tag);
+            }
+        }
+
+        #[cfg(feature = "memory-stats")]
+        super::memory::record_export(bytes.len());
+
+        bytes
+    }
+
+    /// Reads a maze written by `to_bytes`, by this or any older version of the crate
+    /// Fails with InvalidFormat if the data is truncated or inconsistent, and UnsupportedVersion if it was written by a newer version.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Maze, MazeError> {
+        let mut reader = Reader { bytes };
+
+        if reader.take(MAGIC.len(), "header")? != MAGIC {
+            return Err(MazeError::InvalidFormat(format!("data doesn't start with {:?}", MAGIC)));
+        }
+
+        // Each version gets its own reader that builds the current model, so migrations live in one place
+        let version = reader.u16("version")?;
+        let maze = match version {
+            1 => Maze::read_v1(&mut reader)?,
+            2 => Maze::read_v2(&mut reader)?,
+            3 => Maze::read_v3(&mut reader)?,
+            4 => Maze::read_v4(&mut reader)?,
+            5 => Maze::read_v5(&mut reader)?,
+            _ => return Err(MazeError::UnsupportedVersion { version }),
+        };
+
+        if !reader.bytes.is_empty() {
+            return Err(MazeError::InvalidFormat(format!("{} unexpected bytes after the maze", reader.bytes.len())));
+        }
+        maze.check_loaded()?;
+
+        Ok(maze)
+    }
+
+    /// Saves the maze to a file in the format written by `to_bytes`
+    #[cfg(feature = "std")]
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), MazeError> {
+        fs::write(path, self.to_bytes())?;
+        Ok(())
+    }
+
+    /// Loads a maze saved with `save`
+    #[cfg(feature = "std")]
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Maze, MazeError> {
+        Maze::from_bytes(&fs::read(path)?)
+    }
+
+    fn read_v1(reader: &mut Reader<'_>) -> Result<Maze, MazeError> {
+        let width = reader.u32("width")? as usize;
+        let height = reader.u32("height")? as usize;
+        // Taken before allocating, so a corrupt size can't ask for more cells than there is data
+        let cells = reader.take(width.saturating_mul(height), "cells")?;
+        let mut maze = Maze::empty(width, height)?;
+        for (i, &bits) in cells.iter().enumerate() {
+            if bits & !0b1_1111 != 0 {
+                return Err(MazeError::InvalidFormat(format!("cell ({}, {}) has unknown bits set", i % width, i / width)));
+            }
+            maze.update_cell((i % width, i / width), |cell| *cell = Cell(bits));
+        }
+
+        maze.entrances = match reader.u8("entrances")? {
+            0 => None,
+            1 => Some((read_opening(reader)?, read_opening(reader)?)),
+            flag => return Err(MazeError::InvalidFormat(format!("entrance flag must be 0 or 1, got {}", flag))),
+        };
+
+        Ok(maze)
+    }
+
+    /// Version 2 added labeled openings after the version 1 data
+    fn read_v2(reader: &mut Reader<'_>) -> Result<Maze, MazeError> {
+        let mut maze = Maze::read_v1(reader)?;
+
+        let count = reader.u32("opening count")?;
+        for _ in 0..count {
+            let label = read_string(reader, "label")?;
+            let opening = read_opening(reader)?;
+            if maze.opening(&label).is_some() {
+                return Err(MazeError::InvalidFormat(format!("more than one opening is labeled {}", label)));
+            }
+            maze.openings.push((label, opening));
+        }
+
+        Ok(maze)
+    }
+
+    /// Version 3 added portals after the version 2 data
+    fn read_v3(reader: &mut Reader<'_>) -> Result<Maze, MazeError> {
+        let mut maze = Maze::read_v2(reader)?;
+
+        for (from, to) in read_pairs(reader, "portal")? {
+            maze.add_portal(from, to).map_err(|_| MazeError::InvalidFormat(format!("portal between ({}, {}) and ({}, {}) isn't valid", from.0, from.1, to.0, to.1)))?;
+        }
+
+        Ok(maze)
+    }
+
+    /// Version 4 added one-way passages after the version 3 data
+    fn read_v4(reader: &mut Reader<'_>) -> Result<Maze, MazeError> {
+        let mut maze = Maze::read_v3(reader)?;
+
+        for (from, to) in read_pairs(reader, "one-way passage")? {
+            maze.make_one_way(from, to).map_err(|_| MazeError::InvalidFormat(format!("one-way passage from ({}, {}) to ({}, {}) isn't valid", from.0, from.1, to.0, to.1)))?;
+        }
+
+        Ok(maze)
+    }
+
+    /// Version 5 added tags after the version 4 data
+    fn read_v5(reader: &mut Reader<'_>) -> Result<Maze, MazeError> {
+        let mut maze = Maze::read_v4(reader)?;
+
+        let count = reader.u32("tagged cell count")?;
+        for _ in 0..count {
+            let cell = (reader.u32("tagged cell")? as usize, reader.u32("tagged cell")? as usize);
+            let tag_count = reader.u32("tag count")?;
+            for _ in 0..tag_count {
+                let tag = read_string(reader, "tag")?;
+                maze.add_tag(cell, tag).map_err(|_| MazeError::InvalidFormat(format!("tagged cell ({}, {}) is outside the maze", cell.0, cell.1)))?;
+            }
+        }
+
+        Ok(maze)
+    }
+
+    /// Checks that passages are open on both sides, and that the entrances and labeled openings are holes in the outer wall
+    pub(super) fn check_loaded(&self) -> Result<(), MazeError> {
+        let entrances = self.entrances.iter().flat_map(|&(entrance, exit)| [entrance, exit]);
+        for opening in entrances.chain(self.openings.iter().map(|&(_, opening)| opening)) {
+            if !self.is_valid_opening(opening) || !self.is_open(opening.cell, opening.side) {
+                return Err(MazeError::InvalidFormat(format!("opening at ({}, {}) isn't open to the outside", opening.cell.0, opening.cell.1)));
+            }
+        }
+
+        for (cell, _) in self.cells() {
+            for direction in self.open_directions(cell) {
+                if let Some(neighbor) = self.step(cell, direction) {
+                    if !self.is_open(neighbor, direction.reverse()) {
+                        return Err(MazeError::InvalidFormat(format!("cell ({}, {}) has a one way passage {:?}", cell.0, cell.1, direction)));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
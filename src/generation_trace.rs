@@ -0,0 +1,138 @@
+use alloc::{collections::VecDeque, vec, vec::Vec};
+
+use rand::{Rng, prelude::SliceRandom};
+
+use super::{Maze, MazeError, CellIndex};
+use super::generator::NOT_ON_WALK;
+use super::memory::grow_walk_buffer;
+
+/// One step of Wilson's algorithm, see `Maze::generation_trace`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GenerationEvent {
+    /// The first cell was added to the maze, for the walks to grow towards. Always the first event.
+    InitialCell(CellIndex),
+    /// A random walk started from a cell that isn't part of the maze yet
+    WalkStarted(CellIndex),
+    /// The walk stepped onto a new cell
+    StepTaken(CellIndex),
+    /// The walk stepped back onto a cell it had already visited, so the loop since then was erased and the walk continues from that cell
+    LoopErased(CellIndex),
+    /// The walk reached the maze and was added to it. The last cell is the one that was already part of the maze.
+    CellsCommitted(Vec<CellIndex>),
+}
+
+/// Iterator over the events of generating a maze, produced lazily one walk step at a time
+/// The maze being built can be looked at between events with `maze`, which is handy for drawing animation frames.
+pub struct GenerationTrace<R: Rng> {
+    maze: Maze,
+    rng: R,
+    necessary_cells: Vec<CellIndex>,
+    next_start: usize,
+    walk: Vec<CellIndex>,
+    /// Indexed by x * height + y. Where each cell is in `walk`, or NOT_ON_WALK, so loops are found without searching the walk.
+    walk_index: Vec<usize>,
+    pending: VecDeque<GenerationEvent>,
+}
+
+impl<R: Rng> GenerationTrace<R> {
+    /// The maze as it stands after the events so far. Cells on the current walk haven't been carved yet.
+    pub fn maze(&self) -> &Maze {
+        &self.maze
+    }
+
+    /// The walk in progress, loops already erased. Empty between walks.
+    pub fn walk(&self) -> &[CellIndex] {
+        &self.walk
+    }
+
+    /// Runs the rest of the generation and returns the finished maze
+    pub fn into_maze(mut self) -> Maze {
+        while self.next().is_some() {}
+        self.maze
+    }
+}
+
+impl<R: Rng> Iterator for GenerationTrace<R> {
+    type Item = GenerationEvent;
+
+    fn next(&mut self) -> Option<GenerationEvent> {
+        if let Some(event) = self.pending.pop_front() {
+            return Some(event);
+        }
+
+        let height = self.maze.size.1;
+        let id = |cell: CellIndex| cell.0 * height + cell.1;
+
+        let current_pos = match self.walk.last() {
+            Some(&current_pos) => current_pos,
+            None => {
+                // Start the next walk from the first cell that still isn't part of the maze
+                while self.next_start < self.necessary_cells.len() {
+                    let start = self.necessary_cells[self.next_start];
+                    self.next_start += 1;
+                    if !self.maze[start].is_carved() {
+                        self.walk_index[id(start)] = 0;
+                        grow_walk_buffer(&mut self.walk, |walk| walk.push(start));
+                        return Some(GenerationEvent::WalkStarted(start));
+                    }
+                }
+                return None;
+            }
+        };
+
+        let (candidates, count) = self.maze.generate_candidate_cells(current_pos);
+        let step = *candidates[..count].choose(&mut self.rng).unwrap(); // Safe to unwrap since the maze is at least 1x2 whenever there's an uncarved cell
+
+        if self.maze[step].is_carved() {
+            for &cell in &self.walk {
+                self.walk_index[id(cell)] = NOT_ON_WALK;
+            }
+            grow_walk_buffer(&mut self.walk, |walk| walk.push(step));
+            for pair in self.walk.windows(2) {
+                self.maze.open_passage(pair[0], pair[1]);
+            }
+            #[cfg(feature = "check-invariants")]
+            self.maze.check_invariants();
+            return Some(GenerationEvent::CellsCommitted(core::mem::take(&mut self.walk)));
+        }
+
+        // Erasing loops as soon as they close gives the same walk as erasing them all at the end
+        match self.walk_index[id(step)] {
+            NOT_ON_WALK => {
+                self.walk_index[id(step)] = self.walk.len();
+                grow_walk_buffer(&mut self.walk, |walk| walk.push(step));
+                Some(GenerationEvent::StepTaken(step))
+            },
+            loop_start => {
+                for &erased in &self.walk[loop_start + 1..] {
+                    self.walk_index[id(erased)] = NOT_ON_WALK;
+                }
+                self.walk.truncate(loop_start + 1);
+                Some(GenerationEvent::LoopErased(step))
+            },
+        }
+    }
+}
+
+
+impl Maze {
+    /// Generates a maze the same way as `with_rng`, but exposes every step of Wilson's algorithm as an event so it can be animated or audited
+    /// Generation only advances as the iterator is consumed. Given the same RNG state, the finished maze is identical to `with_rng`'s.
+    pub fn generation_trace<R: Rng>(width: usize, height: usize, mut rng: R) -> Result<GenerationTrace<R>, MazeError> {
+        let mut maze = Maze::empty(width, height)?;
+        maze.update_cell((0, 0), |cell| cell.carve());
+
+        let mut necessary_cells: Vec<CellIndex> = Vec::new();
+        for i in 0..width {
+            for j in 0..height {
+                necessary_cells.push((i, j));
+            }
+        }
+        necessary_cells.shuffle(&mut rng);
+
+        let mut pending = VecDeque::new();
+        pending.push_back(GenerationEvent::InitialCell((0, 0)));
+
+        Ok(GenerationTrace { maze, rng, necessary_cells, next_start: 0, walk: Vec::new(), walk_index: vec![NOT_ON_WALK; width * height], pending })
+    }
+}
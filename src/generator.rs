@@ -0,0 +1,464 @@
+use alloc::{boxed::Box, vec::Vec};
+
+use rand::{Rng, SeedableRng, prelude::SliceRandom};
+use rand_chacha::ChaCha8Rng;
+#[cfg(feature = "image")]
+use image::{Rgb, RgbImage};
+
+use super::{Maze, MazeError, CellIndex, CellIndexExt, Direction, GenerationProgress};
+use super::memory::grow_walk_buffer;
+
+/// Marks a cell that isn't on the current walk
+pub(super) const NOT_ON_WALK: usize = usize::MAX;
+/// Steps a walk takes between checks for cancellation, so a long walk on a huge maze can still be stopped part way
+const CANCEL_CHECK_STEPS: u64 = 1024;
+
+/// Generates mazes with Wilson's algorithm, keeping its RNG and working buffers between mazes
+/// Generating thousands of mazes, e.g. for a dataset, with one generator avoids allocating fresh buffers for each of them.
+/// Pass the same maze to `generate_into` each time and the cells are reused too. A generator seeded with `from_seed`
+/// produces the same maze for its first generation as `Maze::from_seed` with the same seed.
+#[derive(Debug, Clone)]
+pub struct Generator<R = ChaCha8Rng> {
+    rng: R,
+    /// Every cell, shuffled into the order walks are started from
+    order: Vec<CellIndex>,
+    /// The walk in progress, loops already erased
+    walk: Vec<CellIndex>,
+    /// Indexed by x * height + y. Where each cell is in `walk`, or NOT_ON_WALK.
+    walk_index: Vec<usize>,
+    /// Steps a walk may take before it's restarted, see `with_step_budget`
+    step_budget: Option<u64>,
+    /// How likely walks are to step each way, see `with_direction_weights`. Uniform if None.
+    weights: Option<DirectionWeights>,
+    /// Fraction of the cells to carve before stopping, see `with_fill`. Every cell if None.
+    fill: Option<f64>,
+    pub(super) stats: WalkStats,
+}
+
+/// How likely a `Generator`'s random walks are to step in each direction, relative to each other
+/// Walks that favor left and right give mazes with long east-west corridors, and favoring one direction gives flowing, river-like mazes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DirectionWeights {
+    pub up: f64,
+    pub right: f64,
+    pub down: f64,
+    pub left: f64,
+}
+
+impl DirectionWeights {
+    /// Left and right steps `weight` times as likely as up and down ones
+    pub fn horizontal(weight: f64) -> Self {
+        DirectionWeights { up: 1.0, right: weight, down: 1.0, left: weight }
+    }
+
+    /// Up and down steps `weight` times as likely as left and right ones
+    pub fn vertical(weight: f64) -> Self {
+        DirectionWeights { up: weight, right: 1.0, down: weight, left: 1.0 }
+    }
+
+    /// The weight of steps in the given direction
+    pub fn weight(&self, direction: Direction) -> f64 {
+        match direction {
+            Direction::Up => self.up,
+            Direction::Right => self.right,
+            Direction::Down => self.down,
+            Direction::Left => self.left,
+        }
+    }
+}
+
+/// Every direction equally likely, like an unbiased generator
+impl Default for DirectionWeights {
+    fn default() -> Self {
+        DirectionWeights { up: 1.0, right: 1.0, down: 1.0, left: 1.0 }
+    }
+}
+
+/// Counters for the random walks a `Generator` has taken, for tuning `Generator::with_step_budget`
+/// They add up over every maze the generator makes, until `reset_walk_stats` is called.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct WalkStats {
+    /// Random walks that reached the maze and were added to it
+    pub walks: u64,
+    /// Steps taken by all walks, including ones that were restarted
+    pub steps: u64,
+    /// Walks that ran out of step budget and started over
+    pub restarts: u64,
+    /// Most steps any single attempt at a walk took
+    pub longest_walk: u64,
+    /// Cells cut off the end of walks when they looped back on themselves
+    pub cells_erased: u64,
+    /// Cells added to mazes by finished walks
+    pub cells_committed: u64,
+    /// Cells checked for whether a walk should start there. Each cell is checked at most once per maze, in one pass over the cells,
+    /// so this never passes the number of cells generated.
+    pub starts_checked: u64,
+}
+
+/// What Wilson's algorithm carves into: a maze's cells, or the pixels of an image for `Generator::generate_image`
+pub(super) trait Canvas {
+    /// Size in cells, as (width, height)
+    fn size(&self) -> (usize, usize);
+    fn is_carved(&self, cell: CellIndex) -> bool;
+    fn carve(&mut self, cell: CellIndex);
+    /// Carves both cells and opens the passage between them. The cells must be next to each other.
+    fn open_passage(&mut self, from: CellIndex, to: CellIndex);
+
+    /// The cells a walk can step to from the given cell, in the same order as `Maze::generate_candidate_cells`
+    fn candidate_cells(&self, cell: CellIndex) -> ([CellIndex; 4], usize) {
+        let (width, height) = self.size();
+        let mut candidates = [(0, 0); 4];
+        let mut count = 0;
+        for &direction in Direction::ALL.iter() {
+            if let Some(next) = cell.offset(direction).filter(|next| next.0 < width && next.1 < height) {
+                candidates[count] = next;
+                count += 1;
+            }
+        }
+        (candidates, count)
+    }
+
+    /// Checked after every walk with the check-invariants feature
+    #[cfg(feature = "check-invariants")]
+    fn check_invariants(&self) {}
+}
+
+impl Canvas for Maze {
+    fn size(&self) -> (usize, usize) {
+        self.size
+    }
+
+    fn is_carved(&self, cell: CellIndex) -> bool {
+        self[cell].is_carved()
+    }
+
+    fn carve(&mut self, cell: CellIndex) {
+        self.update_cell(cell, |cell| cell.carve());
+    }
+
+    fn open_passage(&mut self, from: CellIndex, to: CellIndex) {
+        Maze::open_passage(self, from, to);
+    }
+
+    #[cfg(feature = "check-invariants")]
+    fn check_invariants(&self) {
+        Maze::check_invariants(self);
+    }
+}
+
+/// An image laid out like `Maze::build_image`, where a cell is carved once its pixel is white
+#[cfg(feature = "image")]
+struct ImageCanvas<'a> {
+    img: &'a mut RgbImage,
+    size: (usize, usize),
+}
+
+#[cfg(feature = "image")]
+impl Canvas for ImageCanvas<'_> {
+    fn size(&self) -> (usize, usize) {
+        self.size
+    }
+
+    fn is_carved(&self, cell: CellIndex) -> bool {
+        self.img.get_pixel(2 * cell.0 as u32 + 1, 2 * cell.1 as u32 + 1).0[0] == 255
+    }
+
+    fn carve(&mut self, cell: CellIndex) {
+        self.img.put_pixel(2 * cell.0 as u32 + 1, 2 * cell.1 as u32 + 1, Rgb([255, 255, 255]));
+    }
+
+    fn open_passage(&mut self, from: CellIndex, to: CellIndex) {
+        self.carve(from);
+        self.carve(to);
+        // The wall pixel between two cells is the one between their pixels
+        self.img.put_pixel((from.0 + to.0 + 1) as u32, (from.1 + to.1 + 1) as u32, Rgb([255, 255, 255]));
+    }
+}
+
+impl Generator<ChaCha8Rng> {
+    /// A generator seeded like `Maze::from_seed`
+    pub fn from_seed(seed: u64) -> Self {
+        Generator::new(ChaCha8Rng::seed_from_u64(seed))
+    }
+}
+
+impl<R: Rng> Generator<R> {
+    /// A generator that takes all its randomness from the given RNG
+    pub fn new(rng: R) -> Self {
+        Generator { rng, order: Vec::new(), walk: Vec::new(), walk_index: Vec::new(), step_budget: None, weights: None, fill: None, stats: WalkStats::default() }
+    }
+
+    /// Restarts any random walk that takes more than `steps` steps without reaching the maze, from the same cell
+    /// On huge empty grids the first few walks can wander for millions of steps before finding the maze, and this
+    /// cuts them short. Each restart of the same walk doubles its budget, so every walk still finishes eventually.
+    /// The mazes are still perfect, but no longer exactly uniform, and won't match `Maze::from_seed` for the same seed.
+    /// A budget of 0 is treated as 1.
+    pub fn with_step_budget(mut self, steps: u64) -> Self {
+        self.step_budget = Some(steps.max(1));
+        self
+    }
+
+    /// Biases the random walks towards some directions, e.g. `DirectionWeights::horizontal(4.0)` for long east-west corridors
+    /// Loop erasure keeps the mazes perfect, but they're no longer uniform, and won't match `Maze::from_seed` for the same seed.
+    /// Fails with OutOfRange unless every weight is above 0 and finite, since a walk in a corner must always have somewhere to go.
+    pub fn with_direction_weights(mut self, weights: DirectionWeights) -> Result<Self, MazeError> {
+        for &direction in Direction::ALL.iter() {
+            let weight = weights.weight(direction);
+            if weight <= 0.0 || !weight.is_finite() {
+                return Err(MazeError::OutOfRange { setting: "direction weight", got: weight, min: 0.0, max: f64::INFINITY });
+            }
+        }
+        self.weights = Some(weights);
+        Ok(self)
+    }
+
+    /// Stops generating once at least `fraction` of the cells are carved, leaving the rest as solid rock, for cave-like sparse mazes
+    /// Every carved cell is still connected to every other by exactly one route, but `validate` reports the uncarved cells, and the
+    /// corners may be solid, so place entrances with `EntrancePlacement` rather than assuming them. The last walk usually carves a few
+    /// cells past the target. Use `Maze::trim_dead_ends` afterwards to cut back the stubs. Fails with OutOfRange unless the fraction is above 0 and at most 1.
+    pub fn with_fill(mut self, fraction: f64) -> Result<Self, MazeError> {
+        if !(fraction > 0.0 && fraction <= 1.0) {
+            return Err(MazeError::OutOfRange { setting: "fill", got: fraction, min: 0.0, max: 1.0 });
+        }
+        self.fill = Some(fraction);
+        Ok(self)
+    }
+
+    /// Counters for the walks taken so far
+    pub fn walk_stats(&self) -> WalkStats {
+        self.stats
+    }
+
+    /// Sets the walk counters back to zero
+    pub fn reset_walk_stats(&mut self) {
+        self.stats = WalkStats::default();
+    }
+
+    /// Generates a new maze. Sizes are in cells, and must be at least 1.
+    pub fn generate(&mut self, width: usize, height: usize) -> Result<Maze, MazeError> {
+        let mut maze = Maze::empty(width, height)?;
+        self.run(&mut maze, |_| true, || false);
+        Ok(maze)
+    }
+
+    /// Same as `generate`, but gives up as soon as `is_cancelled` returns true, like `Maze::with_cancellation`
+    /// It's checked between walks and every so often during them. On cancellation, returns `MazeError::Cancelled` holding the partly
+    /// generated maze, and the generator is ready for the next maze.
+    pub fn generate_with_cancellation<C: Fn() -> bool>(&mut self, width: usize, height: usize, is_cancelled: C) -> Result<Maze, MazeError> {
+        let mut maze = Maze::empty(width, height)?;
+        if is_cancelled() || !self.run(&mut maze, |_| !is_cancelled(), &is_cancelled) {
+            return Err(MazeError::Cancelled { partial: Box::new(maze) });
+        }
+        Ok(maze)
+    }
+
+    /// Replaces the contents of `maze` with a newly generated maze of the given size, reusing its cells when the size allows
+    /// On error the maze is left as it was.
+    pub fn generate_into(&mut self, maze: &mut Maze, width: usize, height: usize) -> Result<(), MazeError> {
+        if width == 0 || height == 0 {
+            return Err(MazeError::InvalidDimensions { got: (width, height) });
+        }
+
+        maze.cells.reset(super::packed::grid_len((width, height)));
+        maze.size = (width, height);
+        maze.entrances = None;
+        maze.openings.clear();
+        maze.portals.clear();
+        maze.one_way.clear();
+        maze.tags.clear();
+
+        self.run(maze, |_| true, || false);
+        Ok(())
+    }
+
+    /// Generates a maze straight into an image, without keeping its cells, for when only the picture is wanted
+    /// The image ends up exactly like `build_image` of the maze `generate` would have made: one pixel per cell and per wall, black walls
+    /// and white paths, with no entrances. It's resized to fit, reusing its pixel buffer when that's big enough. Wilson's algorithm
+    /// reads back which cells are carved from the pixels, so the grid of cells is never built. Its walk buffers take about 8 bytes per cell,
+    /// against 12 for the image. Fails with InvalidDimensions if either size is 0.
+    #[cfg(feature = "image")]
+    pub fn generate_image(&mut self, width: usize, height: usize, img: &mut RgbImage) -> Result<(), MazeError> {
+        if width == 0 || height == 0 {
+            return Err(MazeError::InvalidDimensions { got: (width, height) });
+        }
+
+        let (pixel_width, pixel_height) = (2 * width as u32 + 1, 2 * height as u32 + 1);
+        let mut bytes = core::mem::replace(img, RgbImage::new(0, 0)).into_raw();
+        bytes.clear();
+        bytes.resize(pixel_width as usize * pixel_height as usize * 3, 0);
+        *img = RgbImage::from_raw(pixel_width, pixel_height, bytes).unwrap(); // Safe to unwrap since the buffer was sized to fit
+
+        self.run(&mut ImageCanvas { img, size: (width, height) }, |_| true, || false);
+        Ok(())
+    }
+
+    /// The RNG, for reseeding between mazes or drawing other random numbers from the same stream
+    pub fn rng_mut(&mut self) -> &mut R {
+        &mut self.rng
+    }
+
+    /// Gives back the RNG
+    pub fn into_rng(self) -> R {
+        self.rng
+    }
+
+    /// Wilson's algorithm itself, on an empty maze. `keep_going` is called after every random walk is added,
+    /// and generation stops if it returns false. `is_cancelled` is checked during walks, and stops generation without adding the walk
+    /// if it returns true. Returns true if the maze was finished.
+    pub(super) fn run<M: Canvas, F: FnMut(&GenerationProgress) -> bool, C: Fn() -> bool>(&mut self, maze: &mut M, mut keep_going: F, is_cancelled: C) -> bool {
+        info!("Starting maze generation");
+
+        let (width, height) = maze.size();
+
+        // This is arbitrary. All that matters is that we pick one cell to be the "seed"
+        maze.carve((0, 0));
+        info!("Initial cell: (0, 0)");
+
+        // Cells that must be included in the maze eventually
+        // These are used both for checking if the maze is done, and for picking starting points for the random walk
+        grow_walk_buffer(&mut self.order, |order| {
+            order.clear();
+            for i in 0..width {
+                for j in 0..height {
+                    order.push((i, j));
+                }
+            }
+        });
+
+        // This shuffle is totally unnecessary, and probably makes the algorithm slower.
+        // But it *looks cool*.
+        self.order.shuffle(&mut self.rng);
+
+        grow_walk_buffer(&mut self.walk_index, |walk_index| {
+            walk_index.clear();
+            walk_index.resize(width * height, NOT_ON_WALK);
+        });
+
+        let total_cells = match self.fill {
+            // Rounded up, so at least the fraction asked for is carved
+            Some(fraction) => {
+                let exact = fraction * (width * height) as f64;
+                (exact as usize + (exact > (exact as usize) as f64) as usize).max(1)
+            },
+            None => width * height,
+        };
+        let mut progress = GenerationProgress { cells_carved: 1, total_cells, walks_completed: 0 };
+        for i in 0..self.order.len() {
+            // Only reachable before the last cell with a fill target
+            if progress.is_done() {
+                info!("Reached the fill target with {} of {} cells carved", progress.cells_carved, width * height);
+                break;
+            }
+            let walk_start_point = self.order[i];
+            self.stats.starts_checked += 1;
+            if !maze.is_carved(walk_start_point) {
+                if !self.loop_erased_random_walk(maze, walk_start_point, &is_cancelled) {
+                    info!("Maze generation cancelled during a walk after {} of {} cells", progress.cells_carved, progress.total_cells);
+                    return false;
+                }
+                for pair in self.walk.windows(2) {
+                    maze.open_passage(pair[0], pair[1]);
+                }
+
+                // Every cell of the walk is new except the last one, which was already part of the maze
+                progress.cells_carved += self.walk.len() - 1;
+                progress.walks_completed += 1;
+                #[cfg(feature = "check-invariants")]
+                maze.check_invariants();
+                if !keep_going(&progress) && !progress.is_done() {
+                    info!("Maze generation cancelled after {} of {} cells", progress.cells_carved, progress.total_cells);
+                    return false;
+                }
+            }
+        }
+
+        info!("Maze generation complete");
+
+        true
+    }
+
+    /// Walks randomly from the starting point until it reaches a carved cell, leaving the walk in `self.walk`
+    /// Loops are erased as they're made: stepping back onto a cell already on the walk cuts the walk back to that cell.
+    /// Returns false if `is_cancelled` stopped the walk before it got there.
+    fn loop_erased_random_walk<M: Canvas, C: Fn() -> bool>(&mut self, maze: &M, starting_point: CellIndex, is_cancelled: C) -> bool {
+        info!("Starting random walk at: ({}, {})", starting_point.0, starting_point.1);
+
+        let height = maze.size().1;
+        let id = |cell: CellIndex| cell.0 * height + cell.1;
+
+        self.walk.clear();
+        grow_walk_buffer(&mut self.walk, |walk| walk.push(starting_point));
+        self.walk_index[id(starting_point)] = 0;
+        let mut current_pos = starting_point;
+        let mut budget = self.step_budget;
+        let mut steps = 0;
+
+        // Take random steps until we reach a piece of existing maze
+        while !maze.is_carved(current_pos) {
+            if budget == Some(steps) {
+                debug!("Random walk from ({}, {}) ran out of {} steps, restarting", starting_point.0, starting_point.1, steps);
+                for &cell in &self.walk[1..] {
+                    self.walk_index[id(cell)] = NOT_ON_WALK;
+                }
+                self.walk.truncate(1);
+                current_pos = starting_point;
+                budget = budget.map(|budget| budget.saturating_mul(2));
+                steps = 0;
+                self.stats.restarts += 1;
+            }
+            steps += 1;
+            self.stats.steps += 1;
+            if self.stats.steps.is_multiple_of(CANCEL_CHECK_STEPS) && is_cancelled() {
+                debug!("Random walk from ({}, {}) cancelled after {} steps", starting_point.0, starting_point.1, steps);
+                self.clear_walk_marks(height);
+                return false;
+            }
+            self.stats.longest_walk = self.stats.longest_walk.max(steps);
+
+            let (candidates, count) = maze.candidate_cells(current_pos);
+            let candidates = &candidates[..count];
+            let step = match self.weights {
+                None => *candidates.choose(&mut self.rng).unwrap(), // Safe to unwrap since the maze is at least 1x2 whenever there's an uncarved cell
+                Some(weights) => {
+                    let weight = |cell: CellIndex| weights.weight(Direction::between(current_pos, cell).unwrap()); // Safe to unwrap since candidates are neighbors
+                    let mut pick = self.rng.gen::<f64>() * candidates.iter().map(|&cell| weight(cell)).sum::<f64>();
+                    // Rounding can leave a sliver of `pick` after the last candidate, which counts as the last one
+                    *candidates.iter().find(|&&cell| {
+                        pick -= weight(cell);
+                        pick < 0.0
+                    }).unwrap_or(&candidates[count - 1])
+                },
+            };
+
+            match self.walk_index[id(step)] {
+                NOT_ON_WALK => {
+                    self.walk_index[id(step)] = self.walk.len();
+                    grow_walk_buffer(&mut self.walk, |walk| walk.push(step));
+                },
+                index => {
+                    for &erased in &self.walk[index + 1..] {
+                        self.walk_index[id(erased)] = NOT_ON_WALK;
+                    }
+                    self.stats.cells_erased += (self.walk.len() - index - 1) as u64;
+                    self.walk.truncate(index + 1);
+                },
+            }
+            current_pos = step;
+        }
+
+        self.stats.walks += 1;
+        self.stats.cells_committed += self.walk.len() as u64 - 1;
+        self.clear_walk_marks(height);
+
+        debug!("Random loop-erased walk generated: {:?}", self.walk);
+
+        true
+    }
+
+    /// Leaves the walk marks clean for the next walk
+    fn clear_walk_marks(&mut self, height: usize) {
+        for &cell in &self.walk {
+            self.walk_index[cell.0 * height + cell.1] = NOT_ON_WALK;
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use alloc::{vec, vec::Vec};
+
+use petgraph::graph::{NodeIndex, UnGraph};
+
+use super::{Maze, CellIndex, Direction};
+
+impl Maze {
+    /// Converts the maze to a petgraph graph of its junctions and dead ends, joined by edges weighted with the steps along the corridor between them
+    /// Every cell that isn't open to exactly two neighbors is a node, and so are the entrance, exit, labeled openings and both ends of every portal,
+    /// with each portal an edge of length 1. Node weights are the cells. A loop with no junctions on it gets a node at whichever of its cells `path_cells` reaches first.
+    /// Edges are undirected, so one-way passages count as two-way.
+    pub fn to_graph(&self) -> UnGraph<CellIndex, usize> {
+        info!("Building a graph of the maze's junctions and corridors");
+
+        let height = self.size.1;
+        let id = |cell: CellIndex| cell.0 * height + cell.1;
+        let linked = |cell: CellIndex| Direction::ALL.iter().copied().filter(move |&direction| self.can_move(cell, direction));
+
+        let mut graph = UnGraph::new_undirected();
+        let mut nodes: Vec<Option<NodeIndex>> = vec![None; self.size.0 * height];
+        let mut special: Vec<CellIndex> = self.entrances.iter().flat_map(|&(entrance, exit)| [entrance.cell, exit.cell]).collect();
+        special.extend(self.openings().map(|(_, opening)| opening.cell));
+        special.extend(self.portals.iter().flat_map(|&(a, b)| [a, b]));
+        for cell in self.path_cells() {
+            if linked(cell).count() != 2 || special.contains(&cell) {
+                nodes[id(cell)] = Some(graph.add_node(cell));
+            }
+        }
+
+        // Follows a corridor from a node until it reaches another one, returning that node, how many steps it took and which way it arrived
+        let walk = |nodes: &[Option<NodeIndex>], visited: &mut [bool], start: CellIndex, mut direction: Direction| {
+            let (mut current_pos, mut length) = (start, 0);
+            loop {
+                current_pos = self.step(current_pos, direction).unwrap(); // Safe to unwrap since can_move checked there's a cell there
+                length += 1;
+                if let Some(node) = nodes[id(current_pos)] {
+                    return (node, length, direction);
+                }
+                visited[id(current_pos)] = true;
+                // Safe to unwrap since cells that aren't nodes are linked to exactly two neighbors
+                direction = linked(current_pos).find(|&next| next != direction.reverse()).unwrap();
+            }
+        };
+
+        // Each corridor is walked from both ends, so it's only added from the end with the lower (node, direction) pair
+        let direction_index = |direction: Direction| Direction::ALL.iter().position(|&other| other == direction).unwrap(); // Safe to unwrap since ALL has every direction
+        let add_corridors = |graph: &mut UnGraph<CellIndex, usize>, nodes: &[Option<NodeIndex>], visited: &mut [bool], from: NodeIndex| {
+            let cell = graph[from];
+            for direction in linked(cell) {
+                let (to, length, arrival) = walk(nodes, visited, cell, direction);
+                if (from, direction_index(direction)) <= (to, direction_index(arrival.reverse())) {
+                    graph.add_edge(from, to, length);
+                }
+            }
+        };
+        let mut visited = vec![false; self.size.0 * height];
+        for node in graph.node_indices().collect::<Vec<NodeIndex>>() {
+            add_corridors(&mut graph, &nodes, &mut visited, node);
+        }
+        for cell in self.path_cells() {
+            if nodes[id(cell)].is_none() && !visited[id(cell)] {
+                let node = graph.add_node(cell);
+                nodes[id(cell)] = Some(node);
+                add_corridors(&mut graph, &nodes, &mut visited, node);
+            }
+        }
+
+        for &(a, b) in &self.portals {
+            // Safe to unwrap since both ends of every portal were made nodes
+            graph.add_edge(nodes[id(a)].unwrap(), nodes[id(b)].unwrap(), 1);
+        }
+
+        debug!("Built a graph of {} nodes and {} edges", graph.node_count(), graph.edge_count());
+
+        graph
+    }
+}
@@ -0,0 +1,57 @@
+use super::{Maze, MazeError, CellIndex, Direction, EntrancePlacement, Opening};
+
+impl Maze {
+    /// Lays a single corridor along a Hilbert curve through every cell of a square maze 2^order cells across, with no randomness involved
+    /// The entrance is on the left of the top left cell and the exit on the right of the top right cell, where the curve starts and ends,
+    /// so the solution always runs through all 4^order cells. Handy for tests that need a known maze, and for a long winding path with
+    /// no choices in it. Fails with OutOfRange if the maze would have more cells than fit in a usize.
+    pub fn hilbert(order: u32) -> Result<Maze, MazeError> {
+        let max_order = usize::BITS / 2 - 1;
+        if order > max_order {
+            return Err(MazeError::OutOfRange { setting: "order", got: order as f64, min: 0.0, max: max_order as f64 });
+        }
+        let side = 1usize << order;
+        info!("Laying a Hilbert curve through a {}x{} maze", side, side);
+
+        let mut maze = Maze::empty(side, side)?;
+        maze.update_cell((0, 0), |cell| cell.carve());
+        let mut previous = (0, 0);
+        for distance in 1..side * side {
+            let next = hilbert_cell(side, distance);
+            maze.open_passage(previous, next);
+            previous = next;
+        }
+
+        // Safe to unwrap since both openings are on the outer wall of carved cells
+        maze.open_entrances(EntrancePlacement::At(
+            Opening { cell: (0, 0), side: Direction::Left },
+            Opening { cell: (side - 1, 0), side: Direction::Right },
+        )).unwrap();
+
+        Ok(maze)
+    }
+}
+
+/// The cell `distance` steps along the Hilbert curve through a square grid `side` cells across, where side is a power of two
+/// The curve starts at (0, 0) and ends at (side - 1, 0). Each pass places the point within the next bigger quadrant, flipping it to match the quadrant's turn.
+fn hilbert_cell(side: usize, distance: usize) -> CellIndex {
+    let (mut x, mut y) = (0, 0);
+    let mut remaining = distance;
+    let mut size = 1;
+    while size < side {
+        let right = 1 & (remaining / 2);
+        let down = 1 & (remaining ^ right);
+        if down == 0 {
+            if right == 1 {
+                x = size - 1 - x;
+                y = size - 1 - y;
+            }
+            core::mem::swap(&mut x, &mut y);
+        }
+        x += size * right;
+        y += size * down;
+        remaining /= 4;
+        size *= 2;
+    }
+    (x, y)
+}
@@ -0,0 +1,148 @@
+use alloc::vec::Vec;
+
+use std::sync::{Arc, Mutex, atomic::{AtomicBool, Ordering}, mpsc::{self, Receiver, SyncSender, TrySendError}};
+use std::thread::{self, JoinHandle};
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use super::{Maze, MazeError, Generator};
+
+/// A maze for a `JobQueue` to generate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Job {
+    /// Handed back with the result, to match results to jobs since they finish out of order
+    pub id: u64,
+    /// Width of the maze in cells
+    pub width: usize,
+    /// Height of the maze in cells
+    pub height: usize,
+    /// Seed as for `Maze::from_seed`
+    pub seed: u64,
+}
+
+/// A finished job from a `JobQueue`
+#[derive(Debug)]
+pub struct JobResult {
+    /// The id the job was submitted with
+    pub id: u64,
+    /// The maze, the same as `Maze::from_seed` would give, or why it couldn't be made
+    pub result: Result<Maze, MazeError>,
+}
+
+/// A fixed pool of worker threads generating mazes from a bounded queue of jobs, for datasets and servers with thousands of requests
+/// At most `capacity` jobs wait in the queue, so `submit` blocks once it's full instead of letting requests pile up. Finished mazes wait until
+/// they're taken with `recv` or `results`, so take them as they come in to keep memory bounded too. Each worker reuses one `Generator`'s buffers
+/// for all its jobs. Dropping the queue skips any jobs still waiting and waits for the workers to finish the ones they're on.
+pub struct JobQueue {
+    jobs: Option<SyncSender<Job>>,
+    results: Receiver<JobResult>,
+    workers: Vec<JoinHandle<()>>,
+    stopping: Arc<AtomicBool>,
+    /// Jobs submitted whose results haven't been taken yet
+    outstanding: usize,
+}
+
+impl JobQueue {
+    /// Starts `workers` threads sharing a queue that holds up to `capacity` waiting jobs
+    /// Fails with OutOfRange if there are no workers. With a capacity of 0, `submit` waits for a worker to take the job.
+    pub fn new(workers: usize, capacity: usize) -> Result<JobQueue, MazeError> {
+        if workers == 0 {
+            return Err(MazeError::OutOfRange { setting: "workers", got: 0.0, min: 1.0, max: f64::INFINITY });
+        }
+
+        info!("Starting {} maze generation workers with room for {} waiting jobs", workers, capacity);
+
+        let (job_sender, job_receiver) = mpsc::sync_channel::<Job>(capacity);
+        let (result_sender, results) = mpsc::channel();
+        let job_receiver = Arc::new(Mutex::new(job_receiver));
+        let stopping = Arc::new(AtomicBool::new(false));
+
+        let workers = (0..workers).map(|_| {
+            let job_receiver = Arc::clone(&job_receiver);
+            let result_sender = result_sender.clone();
+            let stopping = Arc::clone(&stopping);
+            thread::spawn(move || {
+                let mut generator = Generator::from_seed(0);
+                loop {
+                    // The lock is only held while waiting for a job, so workers take turns at the queue
+                    let job = match job_receiver.lock().unwrap().recv() { // Safe to unwrap since workers never panic while holding the lock
+                        Ok(job) => job,
+                        Err(_) => break,
+                    };
+                    if stopping.load(Ordering::Relaxed) {
+                        break;
+                    }
+
+                    debug!("Generating job {}", job.id);
+                    *generator.rng_mut() = ChaCha8Rng::seed_from_u64(job.seed);
+                    let result = generator.generate(job.width, job.height);
+                    if result_sender.send(JobResult { id: job.id, result }).is_err() {
+                        break;
+                    }
+                }
+            })
+        }).collect();
+
+        Ok(JobQueue { jobs: Some(job_sender), results, workers, stopping, outstanding: 0 })
+    }
+
+    /// Adds a job to the queue, waiting for room if it's full
+    pub fn submit(&mut self, job: Job) {
+        self.sender().send(job).unwrap(); // Safe to unwrap since the workers only stop once the queue is dropped
+        self.outstanding += 1;
+    }
+
+    /// Adds a job to the queue if there's room, or gives it back if the queue is full
+    pub fn try_submit(&mut self, job: Job) -> Result<(), Job> {
+        match self.sender().try_send(job) {
+            Ok(()) => {
+                self.outstanding += 1;
+                Ok(())
+            },
+            Err(TrySendError::Full(job)) | Err(TrySendError::Disconnected(job)) => Err(job),
+        }
+    }
+
+    /// Waits for the next job to finish. Returns None straight away if every job submitted has already been taken.
+    pub fn recv(&mut self) -> Option<JobResult> {
+        if self.outstanding == 0 {
+            return None;
+        }
+
+        let result = self.results.recv().unwrap(); // Safe to unwrap since the workers outlive the queue and finish every job they take
+        self.outstanding -= 1;
+        Some(result)
+    }
+
+    /// The next finished job, if one is ready, without waiting
+    pub fn try_recv(&mut self) -> Option<JobResult> {
+        let result = self.results.try_recv().ok()?;
+        self.outstanding -= 1;
+        Some(result)
+    }
+
+    /// Waits for every job submitted so far, yielding each as it finishes
+    pub fn results(&mut self) -> impl Iterator<Item = JobResult> + '_ {
+        std::iter::from_fn(move || self.recv())
+    }
+
+    /// Jobs submitted whose results haven't been taken yet, whether they're waiting, being generated or finished
+    pub fn outstanding(&self) -> usize {
+        self.outstanding
+    }
+
+    fn sender(&self) -> &SyncSender<Job> {
+        self.jobs.as_ref().unwrap() // Safe to unwrap since the sender is only taken when the queue is dropped
+    }
+}
+
+impl Drop for JobQueue {
+    fn drop(&mut self) {
+        self.stopping.store(true, Ordering::Relaxed);
+        self.jobs = None;
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use alloc::{collections::BTreeSet, vec, vec::Vec};
+
+use super::{Maze, CellIndex};
+
+impl Maze {
+    /// Finds up to `k` shortest paths between two cells, shortest first, using Yen's algorithm
+    /// Paths never visit a cell twice. A perfect maze only ever has one, but braided mazes can offer alternate routes.
+    /// Returns an empty list if either cell is a wall or no path exists.
+    pub fn k_shortest_paths(&self, start: CellIndex, goal: CellIndex, k: usize) -> Vec<Vec<CellIndex>> {
+        info!("Finding {} shortest paths from ({}, {}) to ({}, {})", k, start.0, start.1, goal.0, goal.1);
+
+        let mut found: Vec<Vec<CellIndex>> = Vec::with_capacity(k);
+        if k == 0 {
+            return found;
+        }
+        match self.solve(start, goal) {
+            Some(path) => found.push(path),
+            None => return found,
+        }
+
+        let mut candidates: Vec<Vec<CellIndex>> = Vec::new();
+        while found.len() < k {
+            let previous = found.last().unwrap().clone(); // Safe to unwrap since found always has the first path in it
+
+            // Branch off the previous path at every cell, forbidding the steps earlier paths took from the same root
+            for spur_index in 0..previous.len() - 1 {
+                let root = &previous[..=spur_index];
+                let spur_cell = previous[spur_index];
+
+                let blocked_steps: BTreeSet<(CellIndex, CellIndex)> = found.iter()
+                    .filter(|path| path.len() > spur_index + 1 && &path[..=spur_index] == root)
+                    .map(|path| (path[spur_index], path[spur_index + 1]))
+                    .collect();
+                // The rest of the root can't be revisited, or the path would loop
+                let blocked_cells: BTreeSet<CellIndex> = root[..spur_index].iter().copied().collect();
+
+                let spur_path = self.breadth_first_search(spur_cell, goal, |from, to| {
+                    blocked_cells.contains(&to) || blocked_steps.contains(&(from, to))
+                });
+
+                if let Some(spur_path) = spur_path {
+                    let mut candidate = root[..spur_index].to_vec();
+                    candidate.extend(spur_path);
+                    if !candidates.contains(&candidate) && !found.contains(&candidate) {
+                        debug!("New candidate path of length {}", candidate.len());
+                        candidates.push(candidate);
+                    }
+                }
+            }
+
+            // Shortest candidate wins. Ties go to whichever was found first.
+            let shortest = candidates.iter().enumerate().min_by_key(|(_, path)| path.len()).map(|(i, _)| i);
+            match shortest {
+                Some(i) => found.push(candidates.remove(i)),
+                None => break,
+            }
+        }
+
+        info!("Found {} paths", found.len());
+
+        found
+    }
+
+    /// Counts the distinct paths between two cells that never visit a cell twice, of any length, stopping at `limit`
+    /// A perfect maze has exactly one, and every loop from `add_loops` or `braid` that the route can go around adds more.
+    /// The number of paths grows very quickly with the number of loops, so the search gives up once it's found `limit` of them.
+    /// Use `k_shortest_paths` to list the paths themselves. Returns 0 if either cell is a wall or no path exists.
+    pub fn count_paths(&self, start: CellIndex, goal: CellIndex, limit: usize) -> usize {
+        if !self.is_path(start) || !self.is_path(goal) || limit == 0 {
+            return 0;
+        }
+
+        if start == goal {
+            return 1;
+        }
+
+        let mut visited: BTreeSet<CellIndex> = BTreeSet::new();
+        visited.insert(start);
+        // Depth first, with each entry holding a cell on the current path and the neighbors of it still to try
+        let mut stack: Vec<(CellIndex, Vec<CellIndex>)> = vec![(start, self.neighbors(start))];
+        let mut count = 0;
+
+        while let Some((cell, untried)) = stack.last_mut() {
+            let cell = *cell;
+            match untried.pop() {
+                Some(next) if next == goal => {
+                    count += 1;
+                    if count == limit {
+                        break;
+                    }
+                },
+                Some(next) if !visited.contains(&next) => {
+                    visited.insert(next);
+                    stack.push((next, self.neighbors(next)));
+                },
+                Some(_) => {},
+                None => {
+                    visited.remove(&cell);
+                    stack.pop();
+                },
+            }
+        }
+
+        debug!("Counted {} paths from ({}, {}) to ({}, {})", count, start.0, start.1, goal.0, goal.1);
+
+        count
+    }
+}
@@ -0,0 +1,228 @@
+use alloc::{collections::{btree_map::Entry, BTreeMap, VecDeque}, string::String, vec, vec::Vec};
+
+#[cfg(feature = "image")]
+use image::{Rgb, RgbImage};
+use rand::{Rng, seq::SliceRandom};
+
+use super::{Maze, MazeError, CellIndex};
+
+/// Locked doors on passages and the keys that open them, kept alongside a maze like a `TerrainMap`
+/// Keys are identified by number. A key opens every door with the same number, isn't used up, and is picked up by walking onto its cell.
+/// Doors block their passage both ways until the key is held.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct KeyLayer {
+    /// (one side, other side, key) for every door
+    doors: Vec<(CellIndex, CellIndex, u32)>,
+    /// (cell, key) for every key
+    keys: Vec<(CellIndex, u32)>,
+}
+
+impl KeyLayer {
+    /// A layer with no doors or keys
+    pub fn new() -> Self {
+        KeyLayer::default()
+    }
+
+    /// Locks the passage between two neighboring cells with a door that needs the given key
+    pub fn add_door(&mut self, from: CellIndex, to: CellIndex, key: u32) {
+        self.doors.push((from, to, key));
+    }
+
+    /// Puts a key on a cell
+    pub fn add_key(&mut self, cell: CellIndex, key: u32) {
+        self.keys.push((cell, key));
+    }
+
+    /// Every door, as (one side, other side, key)
+    pub fn doors(&self) -> &[(CellIndex, CellIndex, u32)] {
+        &self.doors
+    }
+
+    /// Every key, as (cell, key)
+    pub fn keys(&self) -> &[(CellIndex, u32)] {
+        &self.keys
+    }
+
+    /// The key needed to get between two cells, if there's a door between them
+    fn door_between(&self, a: CellIndex, b: CellIndex) -> Option<u32> {
+        self.doors.iter()
+            .find(|&&(from, to, _)| (from, to) == (a, b) || (from, to) == (b, a))
+            .map(|&(_, _, key)| key)
+    }
+}
+
+/// The route found by `Maze::solve_with_keys`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyRoute {
+    /// Every cell walked through from start to goal, in order. Cells can appear more than once, when the route has to double back for a key.
+    pub path: Vec<CellIndex>,
+    /// The keys picked up along the way, in the order they're collected
+    pub keys_collected: Vec<u32>,
+}
+
+impl Maze {
+    /// Finds the shortest route from start to goal that picks up keys to get through the locked doors on the way
+    /// The search remembers which keys are held, so routes that have to fetch a key and come back are found too.
+    /// Returns None if the goal can't be reached. Fails with NotAdjacent or NoPassage for a door that isn't on a passage,
+    /// or OutOfRange if doors and keys use more than 64 different key numbers.
+    pub fn solve_with_keys(&self, layer: &KeyLayer, start: CellIndex, goal: CellIndex) -> Result<Option<KeyRoute>, MazeError> {
+        for &(from, to, _) in &layer.doors {
+            let direction = self.passage_direction(from, to)?;
+            if !self.can_move(from, direction) {
+                return Err(MazeError::NoPassage { from, to });
+            }
+        }
+
+        // Each distinct key number gets one bit of the held keys mask
+        let mut key_ids: Vec<u32> = layer.doors.iter().map(|&(_, _, key)| key).chain(layer.keys.iter().map(|&(_, key)| key)).collect();
+        key_ids.sort_unstable();
+        key_ids.dedup();
+        if key_ids.len() > 64 {
+            return Err(MazeError::OutOfRange { setting: "key count", got: key_ids.len() as f64, min: 0.0, max: 64.0 });
+        }
+        let bit = |key: u32| 1u64 << key_ids.binary_search(&key).unwrap(); // Safe to unwrap since every key used is in key_ids
+        let pick_up = |cell: CellIndex, held: u64| layer.keys.iter()
+            .filter(|&&(key_cell, _)| key_cell == cell)
+            .fold(held, |held, &(_, key)| held | bit(key));
+
+        info!("Solving maze with {} doors and {} keys from ({}, {}) to ({}, {})", layer.doors.len(), layer.keys.len(), start.0, start.1, goal.0, goal.1);
+
+        if !self.is_path(start) || !self.is_path(goal) {
+            return Ok(None);
+        }
+
+        // Breadth first search over (cell, keys held), so the same cell can be revisited once more keys are in hand
+        let start_state = (start, pick_up(start, 0));
+        let mut came_from: BTreeMap<(CellIndex, u64), (CellIndex, u64)> = BTreeMap::new();
+        came_from.insert(start_state, start_state);
+        let mut frontier = VecDeque::new();
+        frontier.push_back(start_state);
+
+        while let Some(state) = frontier.pop_front() {
+            let (current, held) = state;
+            if current == goal {
+                return Ok(Some(key_route(&came_from, start_state, state, &key_ids)));
+            }
+
+            for neighbor in self.neighbors(current) {
+                if layer.door_between(current, neighbor).is_some_and(|key| held & bit(key) == 0) {
+                    continue;
+                }
+
+                let next = (neighbor, pick_up(neighbor, held));
+                if let Entry::Vacant(entry) = came_from.entry(next) {
+                    entry.insert(state);
+                    frontier.push_back(next);
+                }
+            }
+        }
+
+        info!("No route collects the keys needed to reach the goal");
+
+        Ok(None)
+    }
+
+    /// Turns the maze into a multi-step puzzle: locks `count` doors along `solution`, numbered 0 up from the start, and hides each door's key
+    /// in a side branch off the stretch of the solution before it, so every key is reached with the keys before it and no others.
+    /// Meant for perfect mazes, where the side branches only join the solution at one cell. The layer is checked with `solve_with_keys` before
+    /// it's returned. Fails with NoRoom if the solution doesn't have `count` cells with side branches off it, or if the maze has no solution at all.
+    pub fn place_keys<R: Rng + ?Sized>(&self, count: usize, rng: &mut R) -> Result<KeyLayer, MazeError> {
+        let no_room = |placed: usize| MazeError::NoRoom { label: String::from("door"), placed, wanted: count };
+        let solution = self.solution().ok_or_else(|| no_room(0))?;
+        let (start, goal) = (solution[0], solution[solution.len() - 1]);
+
+        info!("Placing {} keys and doors along a solution of {} cells", count, solution.len());
+
+        // Which solution cell each side branch cell hangs off, by flood filling out from the whole solution at once
+        let height = self.size.1;
+        let id = |cell: CellIndex| cell.0 * height + cell.1;
+        let mut branch_of: Vec<Option<usize>> = vec![None; self.size.0 * height];
+        let mut frontier: VecDeque<CellIndex> = solution.iter().copied().collect();
+        for (i, &cell) in solution.iter().enumerate() {
+            branch_of[id(cell)] = Some(i);
+        }
+        let mut branches: Vec<Vec<CellIndex>> = vec![Vec::new(); solution.len()];
+        while let Some(current) = frontier.pop_front() {
+            let root = branch_of[id(current)].unwrap(); // Safe to unwrap since every cell in the frontier has been labeled
+            for neighbor in self.linked_cells(current) {
+                if branch_of[id(neighbor)].is_none() {
+                    branch_of[id(neighbor)] = Some(root);
+                    branches[root].push(neighbor);
+                    frontier.push_back(neighbor);
+                }
+            }
+        }
+
+        // Pick a solution cell with branches for each key, then put each door somewhere between its key's cell and the next key's,
+        // so the stretch of solution before every door has its key's branch on it. Doors can't go after the goal.
+        let mut rooted: Vec<usize> = (0..solution.len() - 1).filter(|&i| !branches[i].is_empty()).collect();
+        if rooted.len() < count {
+            return Err(no_room(rooted.len()));
+        }
+        rooted.partial_shuffle(rng, count);
+        rooted.truncate(count);
+        rooted.sort_unstable();
+
+        let mut layer = KeyLayer::new();
+        for (key, &root) in rooted.iter().enumerate() {
+            let last = rooted.get(key + 1).map_or(solution.len() - 2, |&next| next - 1);
+            let door = rng.gen_range(root, last + 1);
+            layer.add_door(solution[door], solution[door + 1], key as u32);
+            // Safe to unwrap since only solution cells with branches were picked
+            layer.add_key(*branches[root].choose(rng).unwrap(), key as u32);
+        }
+
+        if self.solve_with_keys(&layer, start, goal)?.is_none() {
+            return Err(no_room(0));
+        }
+
+        debug!("Placed doors {:?} and keys {:?}", layer.doors, layer.keys);
+
+        Ok(layer)
+    }
+
+    /// Like `build_image`, with each key's cell and the doors it opens drawn in the same color, keys bright and doors dark
+    /// Colors cycle through six hues by key number. Fails with NotAdjacent or NoPassage for a door that isn't on a passage.
+    #[cfg(feature = "image")]
+    pub fn build_keys_image(&self, layer: &KeyLayer) -> Result<RgbImage, MazeError> {
+        const KEY_COLORS: [Rgb<u8>; 6] = [
+            Rgb([255, 200, 0]), Rgb([0, 200, 255]), Rgb([255, 60, 200]),
+            Rgb([60, 220, 60]), Rgb([255, 120, 0]), Rgb([150, 90, 255]),
+        ];
+        let color = |key: u32| KEY_COLORS[key as usize % KEY_COLORS.len()];
+
+        let mut img = self.build_image();
+        for &(from, to, key) in &layer.doors {
+            let direction = self.passage_direction(from, to)?;
+            if !self.can_move(from, direction) {
+                return Err(MazeError::NoPassage { from, to });
+            }
+            let Rgb([r, g, b]) = color(key);
+            img.put_pixel((from.0 + to.0 + 1) as u32, (from.1 + to.1 + 1) as u32, Rgb([r / 2, g / 2, b / 2]));
+        }
+        for &((x, y), key) in &layer.keys {
+            img.put_pixel(2 * x as u32 + 1, 2 * y as u32 + 1, color(key));
+        }
+        Ok(img)
+    }
+}
+
+/// Walks back from the goal state to build the route, noting each time the held keys change
+fn key_route(came_from: &BTreeMap<(CellIndex, u64), (CellIndex, u64)>, start: (CellIndex, u64), goal: (CellIndex, u64), key_ids: &[u32]) -> KeyRoute {
+    let mut states = Vec::new();
+    let mut current = goal;
+    while current != start {
+        states.push(current);
+        current = came_from[&current];
+    }
+    states.push(start);
+    states.reverse();
+
+    let mut keys_collected: Vec<u32> = key_ids.iter().enumerate().filter(|&(i, _)| start.1 & (1 << i) != 0).map(|(_, &key)| key).collect();
+    for pair in states.windows(2) {
+        let new_keys = pair[1].1 & !pair[0].1;
+        keys_collected.extend(key_ids.iter().enumerate().filter(|&(i, _)| new_keys & (1 << i) != 0).map(|(_, &key)| key));
+    }
+
+    KeyRoute { path: states.into_iter().map(|(cell, _)| cell).collect(), keys_collected }
+}
@@ -0,0 +1,723 @@
+//! Maze generation using [Wilson's Algorithm](https://en.wikipedia.org/wiki/Maze_generation_algorithm#Wilson's_algorithm),
+//! along with solvers, analysis and rendering for the generated mazes.
+//!
+//! ```no_run
+//! use maze_generation::{Maze, EntrancePlacement};
+//!
+//! let mut maze = Maze::new(15, 15).unwrap();
+//! let (entrance, exit) = maze.open_entrances(EntrancePlacement::Farthest).unwrap();
+//! let solution = maze.solve(entrance, exit).unwrap();
+//! println!("Solution is {} cells long", solution.len());
+//!
+//! maze.save_image("maze.png").unwrap();
+//! ```
+//!
+//! Without the default features the crate is `no_std` and only needs `alloc`, so generation and solving work on embedded targets.
+//! The `std` feature adds `Maze::new`, Trémaux's algorithm, distance matrices, sweeps and background generation,
+//! `image` adds rendering to image files, `parallel` generates big mazes and renders images on all cores, and `log` logs progress through the log crate.
+
+#![no_std]
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+use core::fmt;
+use core::ops::Index;
+use packed::{PackedCells, grid_offset};
+use alloc::{boxed::Box, collections::BTreeMap, string::String, vec, vec::Vec};
+
+#[cfg(feature = "image")]
+use image::{Rgb, RgbImage};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+#[macro_use]
+mod logging;
+mod error;
+mod solvers;
+mod astar;
+mod wall_follower;
+mod dead_end_filling;
+#[cfg(feature = "std")]
+mod tremaux;
+mod analysis;
+mod entrances;
+mod difficulty;
+mod stats;
+mod validate;
+#[cfg(feature = "std")]
+mod distance_matrix;
+mod regions;
+mod trace;
+mod k_shortest;
+mod constraints;
+#[cfg(feature = "std")]
+mod sweep;
+mod diff;
+mod fingerprint;
+mod cells;
+mod packed;
+mod pixel_bits;
+mod memory;
+mod edit;
+mod display;
+mod ansi;
+mod patch;
+#[cfg(feature = "image")]
+mod convert;
+#[cfg(feature = "image")]
+mod streaming;
+#[cfg(feature = "image")]
+mod style;
+#[cfg(feature = "image")]
+mod debug_render;
+#[cfg(feature = "image")]
+mod solve_animation;
+#[cfg(feature = "mesh")]
+mod mesh;
+#[cfg(feature = "petgraph")]
+mod graph;
+mod transform;
+mod crop;
+mod stitch;
+mod scale;
+mod progress;
+mod generation_trace;
+mod provenance;
+mod svg;
+#[cfg(feature = "std")]
+mod background;
+#[cfg(feature = "std")]
+mod jobs;
+#[cfg(feature = "std")]
+mod report;
+#[cfg(feature = "std")]
+mod metadata;
+mod format;
+mod raw;
+mod generator;
+mod eller;
+mod rooms;
+mod symmetry;
+mod hilbert;
+mod parallel;
+mod terrain;
+mod portals;
+mod one_way;
+mod keys;
+mod tags;
+mod poi;
+mod fair;
+mod arena;
+mod secrets;
+mod dungeon;
+mod braid;
+mod sparse;
+mod algorithm;
+
+pub use error::MazeError;
+pub use solvers::{Solver, BreadthFirstSolver, AStarSolver, WallFollowerSolver, DeadEndFillingSolver};
+#[cfg(feature = "std")]
+pub use solvers::TremauxSolver;
+pub use astar::{Heuristic, Manhattan, Octile, Movement, AStarSearch};
+#[cfg(feature = "std")]
+pub use astar::Euclidean;
+pub use wall_follower::Hand;
+pub use dead_end_filling::DeadEndFill;
+#[cfg(feature = "std")]
+pub use tremaux::{Tremaux, Passage};
+pub use entrances::{EntrancePlacement, Opening};
+pub use difficulty::Difficulty;
+pub use stats::MazeStats;
+pub use validate::Violation;
+#[cfg(feature = "std")]
+pub use distance_matrix::DistanceMatrix;
+pub use analysis::DistanceMap;
+pub use ansi::AnsiOverlay;
+pub use regions::Regions;
+pub use trace::{SolveEvent, SolveTrace, TracedSolver};
+pub use constraints::{Constraints, DifficultyTarget};
+#[cfg(feature = "std")]
+pub use sweep::{sweep, write_sweep_csv, Summary, SweepResult};
+pub use diff::MazeDiff;
+pub use transform::{Rotation, Axis};
+pub use crop::Crop;
+pub use stitch::Piece;
+pub use symmetry::Symmetry;
+pub use progress::GenerationProgress;
+pub use generation_trace::{GenerationEvent, GenerationTrace};
+pub use provenance::{Provenance, ProvenanceColoring};
+pub use generator::{Generator, WalkStats, DirectionWeights};
+pub use eller::EllerRows;
+pub use pixel_bits::PixelBits;
+pub use algorithm::{Algorithm, REGION_SIZE};
+pub use terrain::{Terrain, TerrainMap, TerrainSolver};
+pub use keys::{KeyLayer, KeyRoute};
+pub use dungeon::{Dungeon, Stair};
+pub use poi::PlacementRules;
+pub use secrets::SecretLayer;
+#[cfg(feature = "memory-stats")]
+pub use memory::{MemoryStats, memory_stats, reset_memory_stats};
+#[cfg(feature = "std")]
+pub use background::GenerationHandle;
+#[cfg(feature = "std")]
+pub use jobs::{Job, JobQueue, JobResult};
+#[cfg(feature = "std")]
+pub use report::GenerationReport;
+#[cfg(feature = "std")]
+pub use metadata::MazeMetadata;
+#[cfg(feature = "image")]
+pub use style::ImageStyle;
+#[cfg(feature = "image")]
+pub use debug_render::DebugOverlay;
+#[cfg(feature = "image")]
+pub use solve_animation::SolveAnimation;
+#[cfg(feature = "mesh")]
+pub use mesh::{WallRect, WallMesh};
+
+/// A pixel of the rendered maze, or a tile of a scaled up one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CellType {
+    /// Solid wall, either between two cells or a cell that was never carved
+    Wall,
+    /// A carved cell or an open passage
+    Path
+}
+
+impl CellType {
+    /// Returns true for walls
+    pub fn is_wall(self) -> bool {
+        self == CellType::Wall
+    }
+
+    /// Returns true for paths
+    pub fn is_path(self) -> bool {
+        self == CellType::Path
+    }
+}
+
+/// Coordinates of a cell in the maze, as (x, y). (0, 0) is the top left cell.
+/// Pixel coordinates in a rendered maze use the same type. See `CellIndexExt` for helpers.
+pub type CellIndex = (usize, usize);
+
+/// Helpers for `CellIndex`, which is a plain tuple so it can't have methods of its own
+pub trait CellIndexExt: Sized {
+    /// The coordinates one step in the given direction, or None if that would go below zero
+    /// This doesn't know how big the maze is, so use `Maze::can_move` or `Maze::is_path` to check the result is inside it.
+    fn offset(self, direction: Direction) -> Option<Self>;
+}
+
+impl CellIndexExt for CellIndex {
+    fn offset(self, direction: Direction) -> Option<CellIndex> {
+        let (x, y) = self;
+        match direction {
+            Direction::Up => Some((x, y.checked_sub(1)?)),
+            Direction::Right => Some((x.checked_add(1)?, y)),
+            Direction::Down => Some((x, y.checked_add(1)?)),
+            Direction::Left => Some((x.checked_sub(1)?, y)),
+        }
+    }
+}
+
+/// The four directions you can move in. Up is towards y = 0, Left is towards x = 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left
+}
+
+impl Direction {
+    /// All directions, in clockwise order
+    pub const ALL: [Direction; 4] = [Direction::Up, Direction::Right, Direction::Down, Direction::Left];
+
+    /// The direction 90 degrees clockwise from this one
+    pub fn turn_right(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    /// The direction 90 degrees counterclockwise from this one
+    pub fn turn_left(self) -> Direction {
+        self.turn_right().reverse()
+    }
+
+    /// The opposite direction
+    pub fn reverse(self) -> Direction {
+        self.turn_right().turn_right()
+    }
+
+    /// The direction to step in to get from one cell to an adjacent one, or None if they aren't adjacent
+    pub fn between(from: CellIndex, to: CellIndex) -> Option<Direction> {
+        match (to.0 as isize - from.0 as isize, to.1 as isize - from.1 as isize) {
+            (0, -1) => Some(Direction::Up),
+            (1, 0) => Some(Direction::Right),
+            (0, 1) => Some(Direction::Down),
+            (-1, 0) => Some(Direction::Left),
+            _ => None,
+        }
+    }
+}
+
+/// What a single cell knows about itself: which of its four sides are open, and whether it's been carved into the maze yet
+/// Passages are stored on both cells they join, so each cell can answer questions about itself without looking at its neighbors.
+/// A passage on the outer edge of the maze with no cell on the other side is an opening in the border.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Cell(u8);
+
+impl Cell {
+    const CARVED: u8 = 1 << 4;
+
+    fn direction_bit(direction: Direction) -> u8 {
+        match direction {
+            Direction::Up => 1,
+            Direction::Right => 1 << 1,
+            Direction::Down => 1 << 2,
+            Direction::Left => 1 << 3,
+        }
+    }
+
+    /// Returns true if the cell has been carved into the maze
+    pub fn is_carved(self) -> bool {
+        self.0 & Cell::CARVED != 0
+    }
+
+    /// Returns true if the side of the cell in the given direction is open
+    pub fn is_open(self, direction: Direction) -> bool {
+        self.0 & Cell::direction_bit(direction) != 0
+    }
+
+    fn carve(&mut self) {
+        self.0 |= Cell::CARVED;
+    }
+
+    fn open(&mut self, direction: Direction) {
+        self.0 |= Cell::direction_bit(direction);
+    }
+
+    fn close(&mut self, direction: Direction) {
+        self.0 &= !Cell::direction_bit(direction);
+    }
+}
+
+/// A rectangular maze of logical cells, each storing which of its sides are open
+/// When rendered, every cell becomes a pixel with a one pixel wall (or passage) between it and each neighbor,
+/// so a maze of W x H cells is drawn as a (2W + 1) x (2H + 1) image.
+/// Two mazes are equal when they have the same size, passages, entrances, labeled openings, portals, one-way passages and tags.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Maze {
+    /// One row after another, so cell (x, y) is at y * width + x. See `update_cell` and the `Index` impl.
+    cells: PackedCells,
+    /// Size in cells, as (width, height)
+    size: (usize, usize),
+    /// The (entrance, exit) openings in the outer wall, once they've been placed
+    entrances: Option<(Opening, Opening)>,
+    /// Extra openings in the outer wall added with `add_opening`, in the order they were added
+    openings: Vec<(String, Opening)>,
+    /// Pairs of linked cells added with `add_portal`. Each cell is in at most one pair.
+    portals: Vec<(CellIndex, CellIndex)>,
+    /// Passages made one-way with `make_one_way`, as the (from, to) direction they can be walked in
+    one_way: Vec<(CellIndex, CellIndex)>,
+    /// Tags added with `add_tag`. Cells without tags aren't in the map.
+    tags: BTreeMap<CellIndex, Vec<String>>,
+}
+
+impl Maze {
+    /// Generates a new maze using Wilson's Algorithm
+    /// Sizes are in cells, and must be at least 1. The rendered image is (2 * width + 1) by (2 * height + 1) pixels.
+    /// The RNG is freshly seeded from the operating system, so there's no thread local state involved.
+    #[cfg(feature = "std")]
+    pub fn new(width: usize, height: usize) -> Result<Self, MazeError> {
+        Maze::with_rng(width, height, &mut ChaCha8Rng::from_entropy())
+    }
+
+    /// Generates a new maze from a seed. The same seed and size always give the same maze.
+    /// Seeds drive ChaCha8, which produces the same numbers on every platform, so a seed gives the same maze everywhere for a given crate version.
+    pub fn from_seed(width: usize, height: usize, seed: u64) -> Result<Self, MazeError> {
+        Maze::with_rng(width, height, &mut ChaCha8Rng::seed_from_u64(seed))
+    }
+
+    /// Generates a new maze using Wilson's Algorithm, taking all its randomness from the given RNG
+    /// Sizes are in cells, and must be at least 1
+    pub fn with_rng<R: Rng + ?Sized>(width: usize, height: usize, rng: &mut R) -> Result<Self, MazeError> {
+        Maze::generate(width, height, rng, |_| true, || false)
+    }
+
+    /// Same as `with_rng`, but calls `on_progress` after every random walk is added to the maze, so big generations can show how far along they are
+    pub fn with_progress<R: Rng + ?Sized, F: FnMut(&GenerationProgress)>(width: usize, height: usize, rng: &mut R, mut on_progress: F) -> Result<Self, MazeError> {
+        Maze::generate(width, height, rng, |progress| {
+            on_progress(progress);
+            true
+        }, || false)
+    }
+
+    /// Same as `with_rng`, but checks `is_cancelled` before every random walk and every so often during one, and gives up as soon as it returns true
+    /// A shared flag works well, e.g. `|| flag.load(Ordering::Relaxed)` with an `Arc<AtomicBool>`, set when the request for the maze goes away.
+    /// On cancellation, returns `MazeError::Cancelled` holding the partly generated maze, without the walk that was in progress.
+    pub fn with_cancellation<R: Rng + ?Sized, C: Fn() -> bool>(width: usize, height: usize, rng: &mut R, is_cancelled: C) -> Result<Self, MazeError> {
+        Generator::new(rng).generate_with_cancellation(width, height, is_cancelled)
+    }
+
+    /// Runs Wilson's algorithm with a throwaway `Generator`. `keep_going` is called after every random walk is added, and generation stops if it returns false.
+    /// `is_cancelled` is checked during walks too, see `Generator::run`.
+    fn generate<R: Rng + ?Sized, F: FnMut(&GenerationProgress) -> bool, C: Fn() -> bool>(width: usize, height: usize, rng: &mut R, keep_going: F, is_cancelled: C) -> Result<Self, MazeError> {
+        let mut maze = Maze::empty(width, height)?;
+        if !Generator::new(rng).run(&mut maze, keep_going, is_cancelled) {
+            return Err(MazeError::Cancelled { partial: Box::new(maze) });
+        }
+
+        Ok(maze)
+    }
+
+    /// A maze of the given size with no cells carved yet
+    fn empty(width: usize, height: usize) -> Result<Self, MazeError> {
+        if width == 0 || height == 0 {
+            return Err(MazeError::InvalidDimensions { got: (width, height) })
+        }
+
+        Ok(Maze::from_cells((width, height), PackedCells::grid((width, height))))
+    }
+
+    /// A maze with the given cells and nothing else, laid out like `PackedCells::grid`
+    fn from_cells(size: (usize, usize), cells: PackedCells) -> Self {
+        Maze {
+            cells,
+            size,
+            entrances: None,
+            openings: Vec::new(),
+            portals: Vec::new(),
+            one_way: Vec::new(),
+            tags: BTreeMap::new(),
+        }
+    }
+
+    /// Generates valid cells to step to during random walks, given a cell to be stepping from
+    /// Returns them in a fixed array along with how many there are, so walks don't allocate for every step.
+    fn generate_candidate_cells(&self, current_pos: CellIndex) -> ([CellIndex; 4], usize) {
+        let mut candidates = [(0, 0); 4];
+        let mut count = 0;
+        for &direction in Direction::ALL.iter() {
+            if let Some(cell) = self.step(current_pos, direction) {
+                candidates[count] = cell;
+                count += 1;
+            }
+        }
+        (candidates, count)
+    }
+
+    /// Carves both cells and opens the passage between them. The cells must be next to each other.
+    fn open_passage(&mut self, from: CellIndex, to: CellIndex) {
+        let direction = Direction::between(from, to).unwrap(); // Safe to unwrap since callers only pass adjacent cells
+        self.update_cell(from, |cell| {
+            cell.carve();
+            cell.open(direction);
+        });
+        self.update_cell(to, |cell| {
+            cell.carve();
+            cell.open(direction.reverse());
+        });
+    }
+
+    /// Where a cell is stored in `cells`. Panics if it's outside the maze.
+    fn cell_offset(&self, cell: CellIndex) -> usize {
+        assert!(cell.0 < self.size.0 && cell.1 < self.size.1, "cell ({}, {}) is outside the {}x{} maze", cell.0, cell.1, self.size.0, self.size.1);
+        grid_offset(self.size, cell)
+    }
+
+    /// Changes the cell at (x, y). Panics if it's outside the maze.
+    fn update_cell<F: FnOnce(&mut Cell)>(&mut self, cell: CellIndex, change: F) {
+        let offset = self.cell_offset(cell);
+        let mut updated = *self.cells.get(offset);
+        change(&mut updated);
+        self.cells.set(offset, updated);
+    }
+
+    /// Size of the maze in cells, as (width, height)
+    pub fn size(&self) -> (usize, usize) {
+        self.size
+    }
+
+    /// Returns true if the cell is inside the maze and has been carved
+    pub fn is_path(&self, cell: CellIndex) -> bool {
+        cell.0 < self.size.0 && cell.1 < self.size.1 && self[cell].is_carved()
+    }
+
+    /// Returns the cell one step in the given direction, or None if that would leave the maze
+    fn step(&self, cell: CellIndex, direction: Direction) -> Option<CellIndex> {
+        cell.offset(direction).filter(|next| next.0 < self.size.0 && next.1 < self.size.1)
+    }
+
+    /// Returns the cells you can move to from the given cell, through its open passages or a portal
+    /// Openings in the outer wall aren't included, since there's no cell on the other side. One-way passages are only included going the right way.
+    pub fn neighbors(&self, cell: CellIndex) -> Vec<CellIndex> {
+        let mut neighbors = self.linked_cells(cell);
+        if !self.one_way.is_empty() {
+            neighbors.retain(|&neighbor| !self.one_way.contains(&(neighbor, cell)));
+        }
+        neighbors
+    }
+
+    /// Same as `neighbors`, but ignoring which way one-way passages go
+    fn linked_cells(&self, cell: CellIndex) -> Vec<CellIndex> {
+        let mut neighbors: Vec<CellIndex> = self.open_directions(cell).into_iter()
+            .filter_map(|direction| self.step(cell, direction))
+            .collect();
+        neighbors.extend(self.portal_partner(cell));
+        neighbors
+    }
+
+    /// Returns the directions the given cell is open in, including openings in the outer wall
+    fn open_directions(&self, cell: CellIndex) -> Vec<Direction> {
+        if !self.is_path(cell) {
+            return Vec::new();
+        }
+
+        Direction::ALL.iter()
+            .copied()
+            .filter(|&direction| self.is_open(cell, direction))
+            .collect()
+    }
+
+    /// Returns true if the cell is carved and its side in the given direction is open
+    fn is_open(&self, cell: CellIndex, direction: Direction) -> bool {
+        self.is_path(cell) && self[cell].is_open(direction)
+    }
+
+    /// Returns true if you can walk from the given cell to the next cell in the given direction
+    /// Walking out through an opening in the outer wall doesn't count, since it leaves the maze.
+    pub fn can_move(&self, from: CellIndex, direction: Direction) -> bool {
+        self.is_open(from, direction) && self.step(from, direction).is_some()
+    }
+
+    /// Size of the maze when drawn with one pixel per cell and one pixel per wall, as (width, height)
+    fn pixel_size(&self) -> (usize, usize) {
+        (self.size.0 * 2 + 1, self.size.1 * 2 + 1)
+    }
+
+    /// Converts the maze into a grid of wall and path pixels, indexed as pixels[x][y]
+    /// Cell (x, y) becomes pixel (2x + 1, 2y + 1), the passages out of it are the pixels next to that, and the pixels at the corners between cells are always walls.
+    fn to_pixels(&self) -> Vec<Vec<CellType>> {
+        let (width, height) = self.pixel_size();
+        let mut pixels = vec![vec![CellType::Wall; height]; width];
+
+        for ((x, y), cell) in self.cells() {
+            if !cell.is_carved() {
+                continue;
+            }
+
+            let (px, py) = (x * 2 + 1, y * 2 + 1);
+            pixels[px][py] = CellType::Path;
+            for &direction in Direction::ALL.iter().filter(|&&direction| cell.is_open(direction)) {
+                let (wx, wy) = match direction {
+                    Direction::Up => (px, py - 1),
+                    Direction::Right => (px + 1, py),
+                    Direction::Down => (px, py + 1),
+                    Direction::Left => (px - 1, py),
+                };
+                pixels[wx][wy] = CellType::Path;
+            }
+        }
+
+        pixels
+    }
+
+    /// The rendered maze as raw RGBA bytes, row by row, for handing to canvases and GPU textures
+    /// The image is `2 * width + 1` pixels wide and `2 * height + 1` pixels tall, laid out like `build_image`.
+    pub fn to_rgba_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        self.to_rgba_bytes_into(&mut bytes);
+        bytes
+    }
+
+    /// Same as `to_rgba_bytes`, but renders into an existing buffer, resized to fit, so rendering maze after maze reuses one allocation
+    pub fn to_rgba_bytes_into(&self, bytes: &mut Vec<u8>) {
+        self.render_into(bytes, &[0, 0, 0, 255], &[255, 255, 255, 255], (1, 1));
+    }
+
+    /// Renders the maze row by row into raw bytes, with the given bytes for every wall and path pixel, drawn at the (corridor, wall) widths of `tile_span`
+    /// The buffer is resized to fit, and its allocation is reused when it's big enough. With the `parallel` feature the rows are rendered on all cores.
+    fn render_into(&self, bytes: &mut Vec<u8>, wall: &[u8], path: &[u8], widths: (usize, usize)) {
+        let (width, height) = self.pixel_size();
+        let channels = wall.len();
+        let row_len = tile_span(width - 1, widths).end * channels;
+
+        // Every row starts out as a copy of a solid wall row, which is one big memcpy, and only path pixels are written after that
+        let wall_row: Vec<u8> = wall.iter().copied().cycle().take(row_len).collect();
+        bytes.resize(row_len * tile_span(height - 1, widths).end, 0);
+
+        // Each block is the rows one row of pixels becomes. The first is drawn and the others are copies of it.
+        let mut blocks = Vec::with_capacity(height);
+        let mut rest = bytes.as_mut_slice();
+        for py in 0..height {
+            let (block, tail) = core::mem::take(&mut rest).split_at_mut(row_len * tile_span(py, widths).len());
+            blocks.push(block);
+            rest = tail;
+        }
+
+        #[cfg(feature = "parallel")]
+        let blocks = blocks.into_par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let blocks = blocks.into_iter();
+
+        blocks.enumerate().for_each(|(py, block)| {
+            let (row, copies) = block.split_at_mut(row_len);
+            row.copy_from_slice(&wall_row);
+            self.path_pixels_in_row(py, |px| {
+                let span = tile_span(px, widths);
+                for pixel in row[span.start * channels..span.end * channels].chunks_exact_mut(channels) {
+                    pixel.copy_from_slice(path);
+                }
+            });
+            copies.chunks_exact_mut(row_len).for_each(|copy| copy.copy_from_slice(row));
+        });
+    }
+
+    /// Calls `paint` with the x of every path pixel in row `py` of the rendered maze, see `to_pixels` for the layout
+    /// Works a cell at a time rather than a pixel at a time. Some pixels can be painted twice.
+    fn path_pixels_in_row<F: FnMut(usize)>(&self, py: usize, mut paint: F) {
+        let (width, height) = self.size;
+        let y = py / 2;
+        // Reads straight from storage, since every cell looked at is known to be inside the maze
+        let cell_at = |x: usize, y: usize| *self.cells.get(grid_offset(self.size, (x, y)));
+        let open = |cell: Cell, direction| cell.is_carved() && cell.is_open(direction);
+        if py % 2 == 1 {
+            // A row of cells, with the passages between them
+            for x in 0..width {
+                let cell = cell_at(x, y);
+                if !cell.is_carved() {
+                    continue;
+                }
+                paint(2 * x + 1);
+                if cell.is_open(Direction::Left) {
+                    paint(2 * x);
+                }
+                if cell.is_open(Direction::Right) {
+                    paint(2 * x + 2);
+                }
+            }
+        } else {
+            // The walls between row y - 1 and row y, which only have passages below cells
+            for x in 0..width {
+                let down_from_above = y > 0 && open(cell_at(x, y - 1), Direction::Down);
+                let up_from_below = y < height && open(cell_at(x, y), Direction::Up);
+                if down_from_above || up_from_below {
+                    paint(2 * x + 1);
+                }
+            }
+        }
+    }
+
+    /// Renders the maze with `build_image` and saves it, with the format picked from the file extension
+    #[cfg(feature = "image")]
+    pub fn save_image<P: AsRef<std::path::Path>>(&self, path: P) -> Result<(), MazeError> {
+        self.build_image().save(path)?;
+        Ok(())
+    }
+
+    /// Builds an image for the maze. Every cell and every wall in the maze is 1 pixel. Walls are black, paths are white.
+    #[cfg(feature = "image")]
+    pub fn build_image(&self) -> image::ImageBuffer<Rgb<u8>, Vec<u8>> {
+        info!("Starting image generation");
+
+        let (width, height) = self.pixel_size();
+        let mut bytes = Vec::new();
+        self.render_into(&mut bytes, &[0, 0, 0], &[255, 255, 255], (1, 1));
+        let img = RgbImage::from_raw(width as u32, height as u32, bytes).unwrap(); // Safe to unwrap since render_into makes exactly width * height pixels
+
+        info!("Image generation complete");
+
+        img
+    }
+
+    /// Like `build_image`, with every cell and wall `scale` pixels across and the given colors, for images meant to be looked at
+    /// Fails with InvalidScale for a scale of 0. See `build_image_with_style` to mark the entrance and exit too.
+    #[cfg(feature = "image")]
+    pub fn build_styled_image(&self, scale: usize, wall: Rgb<u8>, path: Rgb<u8>) -> Result<RgbImage, MazeError> {
+        self.build_image_with_style(&ImageStyle { corridor_width: scale, wall_thickness: scale, wall, path, ..ImageStyle::default() })
+    }
+
+    /// Same as `build_image`, but renders into an existing image, reusing its pixel buffer when it's big enough
+    /// Paired with `Generator::generate_into`, generating and rendering maze after maze allocates nothing once the buffers have grown.
+    #[cfg(feature = "image")]
+    pub fn build_image_into(&self, img: &mut RgbImage) {
+        let (width, height) = self.pixel_size();
+        let mut bytes = core::mem::replace(img, RgbImage::new(0, 0)).into_raw();
+        self.render_into(&mut bytes, &[0, 0, 0], &[255, 255, 255], (1, 1));
+        *img = RgbImage::from_raw(width as u32, height as u32, bytes).unwrap(); // Safe to unwrap since render_into makes exactly width * height pixels
+    }
+
+    /// Like `build_image`, but with every hole in the outer wall and every portal marked in color so it's clear where each way in is
+    /// The entrance is green and the exit red. Labeled openings cycle through blue, orange, purple, cyan, magenta and yellow in the order they were added,
+    /// and so do portals, with both ends of a portal the same color. One-way passages are grey, see `to_ascii` for which way they go.
+    #[cfg(feature = "image")]
+    pub fn build_marked_image(&self) -> image::ImageBuffer<Rgb<u8>, Vec<u8>> {
+        const PALETTE: [Rgb<u8>; 6] = [
+            Rgb([0, 0, 255]), Rgb([255, 128, 0]), Rgb([128, 0, 255]),
+            Rgb([0, 255, 255]), Rgb([255, 0, 255]), Rgb([255, 255, 0]),
+        ];
+
+        let mut img = self.build_image();
+        let mut mark = |opening: Opening, color: Rgb<u8>| {
+            let (x, y) = opening.pixel();
+            img.put_pixel(x as u32, y as u32, color);
+        };
+
+        if let Some((entrance, exit)) = self.entrances {
+            mark(entrance, Rgb([0, 255, 0]));
+            mark(exit, Rgb([255, 0, 0]));
+        }
+        for (i, &(_, opening)) in self.openings.iter().enumerate() {
+            mark(opening, PALETTE[i % PALETTE.len()]);
+        }
+        for &(from, to) in &self.one_way {
+            img.put_pixel((from.0 + to.0 + 1) as u32, (from.1 + to.1 + 1) as u32, Rgb([128, 128, 128]));
+        }
+        for (i, &(a, b)) in self.portals.iter().enumerate() {
+            for &(x, y) in &[a, b] {
+                img.put_pixel(2 * x as u32 + 1, 2 * y as u32 + 1, PALETTE[i % PALETTE.len()]);
+            }
+        }
+
+        img
+    }
+}
+
+impl Index<CellIndex> for Maze {
+    type Output = Cell;
+
+    /// The cell at (x, y). Panics if it's outside the maze.
+    fn index(&self, cell: CellIndex) -> &Cell {
+        self.cells.get(self.cell_offset(cell))
+    }
+}
+
+/// Only shows the size, entrances, openings, portals, one-way passages, how many cells are tagged and the fingerprint,
+/// since printing every cell of a big maze is never useful
+impl fmt::Debug for Maze {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Maze")
+            .field("size", &self.size)
+            .field("entrances", &self.entrances)
+            .field("openings", &self.openings)
+            .field("portals", &self.portals)
+            .field("one_way", &self.one_way)
+            .field("tagged_cells", &self.tags.len())
+            .field("fingerprint", &format_args!("{:016x}", self.fingerprint()))
+            .finish()
+    }
+}
+
+/// The image pixels a pixel of the rendered maze covers along one axis, when pixels of cells and passages are drawn `corridor` pixels across
+/// and wall pixels `wall` pixels across. Pixels at even coordinates lie along a line of walls, odd ones along a line of cells.
+fn tile_span(px: usize, (corridor, wall): (usize, usize)) -> core::ops::Range<usize> {
+    let start = px.div_ceil(2) * wall + px / 2 * corridor;
+    start..start + if px.is_multiple_of(2) { wall } else { corridor }
+}
@@ -0,0 +1,26 @@
+//! Logging macros that forward to the log crate when the `log` feature is on, and compile to nothing otherwise
+//! The arguments are still type checked when logging is off, so builds with and without the feature stay in sync.
+
+#[cfg(feature = "log")]
+macro_rules! info {
+    ($($arg:tt)*) => { log::info!($($arg)*) };
+}
+
+#[cfg(feature = "log")]
+macro_rules! debug {
+    ($($arg:tt)*) => { log::debug!($($arg)*) };
+}
+
+#[cfg(not(feature = "log"))]
+macro_rules! info {
+    ($($arg:tt)*) => {{
+        if false {
+            let _ = format_args!($($arg)*);
+        }
+    }};
+}
+
+#[cfg(not(feature = "log"))]
+macro_rules! debug {
+    ($($arg:tt)*) => { info!($($arg)*) };
+}
@@ -1,13 +1,522 @@
-mod mazes;
+mod bench;
+mod config;
+mod play;
 
-fn main() {
-    env_logger::init();
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::process;
+use std::str::FromStr;
+
+use clap::{App, Arg, ArgMatches, SubCommand};
+use image::{DynamicImage, ImageOutputFormat, Rgb, RgbImage};
+use maze_generation::{Algorithm, AnsiOverlay, CellIndex, Direction, EntrancePlacement, Hand, ImageStyle, Maze, MazeError, MazeMetadata, SolveAnimation, TracedSolver};
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+use rand_chacha::ChaCha8Rng;
+
+/// Everything that can be set from the command line
+struct Options {
+    width: usize,
+    height: usize,
+    algorithm: Algorithm,
+    /// Seed for the RNG, so the same flags always give the same maze. Freshly seeded from the operating system if unset.
+    seed: Option<u64>,
+    constraints: maze_generation::Constraints,
+    max_attempts: usize,
+    /// Cell size, colors and markers for saved images
+    style: ImageStyle,
+    /// A picture to show through the paths of saved images
+    background: Option<RgbImage>,
+    /// Where to save the maze. For batches, `{seed}` and `{index}` are replaced with each maze's seed and number.
+    output: String,
+    format: Format,
+    /// Also write a JSON file of the maze's size, algorithm, seed, entrances and solution length next to each saved maze
+    metadata: bool,
+    /// How many mazes to make, with seeds counting up from `seed`
+    count: usize,
+    /// Generate and save the mazes of a batch on all cores
+    parallel: bool,
+    /// Leave out progress messages and logs, e.g. when the maze is written to stdout
+    quiet: bool,
+    /// When set, generate this many mazes per algorithm and print a CSV report instead of saving an image
+    sweep: Option<usize>,
+    /// Print stats about the generated maze instead of saving an image
+    analyze: bool,
+    /// Play the maze in the terminal instead of saving an image
+    play: bool,
+    /// Print the maze in color in the terminal instead of saving an image, with this drawn over it
+    preview: Option<Preview>,
+    /// Save a GIF of a solver exploring the maze to the output instead of saving an image
+    animate: Option<SolveAnimation>,
+    /// Time every algorithm at these sizes instead of saving an image
+    bench: Option<Vec<(usize, usize)>>,
+    /// Mazes per algorithm and size when benchmarking
+    runs: usize,
+}
+
+/// What the `preview` subcommand colors the paths with
+#[derive(Clone, Copy, PartialEq)]
+enum Preview {
+    Plain,
+    /// The solution in red
+    Solution,
+    /// How far each cell is from the start
+    Heatmap,
+}
+
+impl FromStr for Preview {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Preview::Plain),
+            "solution" => Ok(Preview::Solution),
+            "heatmap" => Ok(Preview::Heatmap),
+            _ => Err(format!("Preview must show one of none, solution or heatmap, got {}", s)),
+        }
+    }
+}
+
+/// Parses a solver for the `animate` subcommand: bfs, left or right for the wall followers, or tremaux
+fn parse_solver(value: &str) -> Result<TracedSolver, String> {
+    match value {
+        "bfs" => Ok(TracedSolver::BreadthFirst),
+        "left" => Ok(TracedSolver::WallFollower(Hand::Left)),
+        "right" => Ok(TracedSolver::WallFollower(Hand::Right)),
+        "tremaux" => Ok(TracedSolver::Tremaux),
+        _ => Err(format!("Solver must be one of bfs, left, right or tremaux, got {}", value)),
+    }
+}
+
+/// How the maze gets saved
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    /// An image, in whichever format the file extension says
+    Image,
+    /// The half block drawing from `Display`
+    Text,
+    /// The crate's own binary format, from `Maze::save`
+    Maze,
+    /// The MZRW cell grid from `Maze::save_raw`: a 32 byte header, then 5 bit cells packed 12 to a little endian u64 word, row by row
+    Raw,
+}
+
+impl Format {
+    /// Where the maze is saved if there's no --output, or each maze of a batch
+    fn default_output(self, batch: bool) -> &'static str {
+        match (self, batch) {
+            (Format::Image, false) => "output.png",
+            (Format::Text, false) => "output.txt",
+            (Format::Maze, false) => "output.maze",
+            (Format::Raw, false) => "output.raw",
+            (Format::Image, true) => "maze_{seed}.png",
+            (Format::Text, true) => "maze_{seed}.txt",
+            (Format::Maze, true) => "maze_{seed}.maze",
+            (Format::Raw, true) => "maze_{seed}.raw",
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "image" => Ok(Format::Image),
+            "text" => Ok(Format::Text),
+            "maze" => Ok(Format::Maze),
+            "raw" => Ok(Format::Raw),
+            _ => Err(format!("Format must be one of image, text, maze or raw, got {}", s)),
+        }
+    }
+}
+
+/// Every flag the binary takes. They can go before or after the `analyze`, `play`, `preview`, `animate` or `bench` subcommand.
+fn app() -> App<'static, 'static> {
+    let flag = |name: &'static str, value: &'static str, help: &'static str| Arg::with_name(name).long(name).value_name(value).help(help).takes_value(true).global(true);
+
+    App::new("maze-generation")
+        .about("Generates perfect mazes with Wilson's or Eller's algorithm and saves them as images")
+        .arg(flag("width", "CELLS", "Width of the maze in cells [default: 150]"))
+        .arg(flag("height", "CELLS", "Height of the maze in cells [default: 150]"))
+        .arg(flag("algorithm", "NAME", "wilson, eller, or regions for big mazes on all cores [default: wilson]"))
+        .arg(flag("seed", "N", "Seed for the RNG, so the same flags always give the same maze"))
+        .arg(flag("entrances", "PLACEMENT", "farthest, none, or two sides like top-bottom [default: farthest]"))
+        .arg(flag("min-solution-length", "CELLS", "Keep generating until the solution has at least this many cells"))
+        .arg(flag("min-solution-fraction", "FRACTION", "Keep generating until this fraction of the cells are on the solution"))
+        .arg(flag("difficulty", "LEVEL", "Keep generating until the maze is easy, medium or hard"))
+        .arg(flag("max-attempts", "N", "Give up after this many mazes [default: 1000]"))
+        .arg(flag("cell-size", "PIXELS", "Pixels across each cell and wall in images [default: 1]"))
+        .arg(flag("corridor-width", "PIXELS", "Pixels across each corridor in images, overriding --cell-size"))
+        .arg(flag("wall-thickness", "PIXELS", "Pixels across each wall in images, overriding --cell-size"))
+        .arg(flag("wall-color", "RRGGBB", "Color of the walls in images [default: 000000]"))
+        .arg(flag("path-color", "RRGGBB", "Color of the paths in images [default: ffffff]"))
+        .arg(flag("background", "PATH", "Show this picture through the paths of images, stretched to fit, instead of --path-color"))
+        .arg(Arg::with_name("markers").long("markers").help("Color the entrance and exit in images, green and red unless --entrance-color or --exit-color say otherwise").global(true))
+        .arg(flag("entrance-color", "RRGGBB", "Color of the entrance in images, implies --markers [default: 00ff00]"))
+        .arg(flag("exit-color", "RRGGBB", "Color of the exit in images, implies --markers [default: ff0000]"))
+        .arg(flag("output", "PATH", "Where to save the maze, or - for stdout, with {seed} and {index} filled in for batches [default: output.png, or output.txt etc. for other formats, or maze_{seed}.png etc. for batches]"))
+        .arg(flag("format", "FORMAT", "image (format from the file extension), text, maze, or raw for the MZRW grid of 5 bit cells packed 12 to a u64 [default: image]"))
+        .arg(flag("sweep", "N", "Generate this many mazes per algorithm and print a CSV comparing them instead, seeded from --seed"))
+        .arg(flag("count", "N", "Generate this many mazes, with seeds counting up from --seed [default: 1]"))
+        .arg(Arg::with_name("quiet").long("quiet").short("q").help("Don't print progress messages or logs").global(true))
+        .arg(Arg::with_name("metadata").long("metadata").help("Also save a .json file next to each maze with its size, algorithm, seed, entrances and solution length").global(true))
+        .arg(Arg::with_name("parallel").long("parallel").help("Generate and save the mazes of a batch on all cores").global(true))
+        .arg(flag("config", "PATH", "Run every job in a config file instead, see the README for the format"))
+        .subcommand(SubCommand::with_name("analyze").about("Prints stats about the maze instead of saving it"))
+        .subcommand(SubCommand::with_name("play").about("Walk through the maze in the terminal instead of saving it, 20x12 cells unless --width and --height say otherwise"))
+        .subcommand(SubCommand::with_name("preview")
+            .about("Prints the maze in color in the terminal instead of saving it, 20x12 cells unless --width and --height say otherwise")
+            .arg(Arg::with_name("show").long("show").value_name("OVERLAY").takes_value(true).help("none, solution, or heatmap for the distance from the start [default: none]")))
+        .subcommand(SubCommand::with_name("animate")
+            .about("Saves a GIF of a solver exploring the maze to --output instead, solve.gif unless it says otherwise, at 4 pixels a cell unless --cell-size says otherwise")
+            .arg(Arg::with_name("solver").long("solver").value_name("NAME").takes_value(true).help("bfs, left or right to follow a wall, or tremaux [default: bfs]"))
+            .arg(Arg::with_name("frames").long("frames").value_name("N").takes_value(true).help("Frames of exploration before the last one showing the path [default: 60]")))
+        .subcommand(SubCommand::with_name("bench")
+            .about("Times every algorithm at a few sizes and prints a table comparing them, with grid memory when built with --features memory-stats")
+            .arg(Arg::with_name("sizes").long("sizes").value_name("SIZES").takes_value(true).help("Sizes to try, like 50x50,200x100 [default: 50x50,200x200,1000x1000]"))
+            .arg(Arg::with_name("runs").long("runs").value_name("N").takes_value(true).help("Mazes per algorithm and size [default: 3]")))
+}
+
+/// Parses a flag's value if it was given
+fn value<T: FromStr>(matches: &ArgMatches, name: &str, what: &str) -> Result<Option<T>, String> {
+    matches.value_of(name)
+        .map(|value| value.parse().map_err(|_| format!("Invalid {}: {}", what, value)))
+        .transpose()
+}
+
+/// Parses a color written as hex, with or without a leading #
+fn parse_color(value: &str) -> Result<Rgb<u8>, String> {
+    let hex = value.trim_start_matches('#');
+    let channel = |i: usize| hex.get(i..i + 2).and_then(|channel| u8::from_str_radix(channel, 16).ok());
+    match (hex.len(), channel(0), channel(2), channel(4)) {
+        (6, Some(r), Some(g), Some(b)) => Ok(Rgb([r, g, b])),
+        _ => Err(format!("Invalid color: {}", value)),
+    }
+}
+
+/// Parses where to open the entrances: farthest, none, or an entrance side and exit side like top-bottom
+fn parse_entrances(value: &str) -> Result<Option<EntrancePlacement>, String> {
+    let side = |name: &str| match name {
+        "top" => Ok(Direction::Up),
+        "right" => Ok(Direction::Right),
+        "bottom" => Ok(Direction::Down),
+        "left" => Ok(Direction::Left),
+        _ => Err(format!("Invalid side: {}", name)),
+    };
+    match value {
+        "farthest" => Ok(Some(EntrancePlacement::Farthest)),
+        "none" => Ok(None),
+        _ => {
+            let (entrance, exit) = value.split_once('-').ok_or(format!("Invalid entrances: {}", value))?;
+            Ok(Some(EntrancePlacement::Sides(side(entrance)?, side(exit)?)))
+        },
+    }
+}
 
-    // let maze = mazes::Maze::new(625, 345).unwrap();
-    let maze = mazes::Maze::new(301, 301).unwrap();
-    // let maze = mazes::Maze::new(11, 11).unwrap();
-    
-    let image = maze.build_image();
-    image.save("output.png").unwrap();
+/// The matches holding every flag. Global flags given after the subcommand only show up in its matches, which also get everything given before it.
+fn flags<'a>(top: &'a ArgMatches<'a>) -> &'a ArgMatches<'a> {
+    ["analyze", "play", "preview", "animate", "bench"].iter().find_map(|&name| top.subcommand_matches(name)).unwrap_or(top)
 }
 
+/// Reads the flags from the command line, or a job in a config file, after an optional `analyze`, `play`, `preview`, `animate` or `bench` subcommand
+fn parse_args<I: IntoIterator<Item = T>, T: Into<OsString> + Clone>(args: I) -> Result<Options, String> {
+    let top = app().get_matches_from(args);
+    let (analyze, play) = (top.subcommand_matches("analyze").is_some(), top.subcommand_matches("play").is_some());
+    let matches = flags(&top);
+    let preview = match top.subcommand_matches("preview") {
+        Some(_) => Some(value(matches, "show", "preview")?.unwrap_or(Preview::Plain)),
+        None => None,
+    };
+    // The usual 150x150 wouldn't fit in a terminal
+    let default_size = if play || preview.is_some() { (20, 12) } else { (150, 150) };
+
+    let format: Format = value(matches, "format", "format")?.unwrap_or(Format::Image);
+    let count = value(matches, "count", "maze count")?.unwrap_or(1);
+    if count == 0 {
+        return Err(String::from("--count must be at least 1"));
+    }
+    let animating = top.subcommand_matches("animate").is_some();
+    let default_output = if animating { "solve.gif" } else { format.default_output(count > 1) };
+    let output = matches.value_of("output").unwrap_or(default_output).to_string();
+    if count > 1 && !output.contains("{seed}") && !output.contains("{index}") {
+        return Err(String::from("--output needs {seed} or {index} in it for more than one maze, or every maze would overwrite the last"));
+    }
+    let metadata = matches.is_present("metadata");
+    if metadata && output == "-" {
+        return Err(String::from("--metadata needs a file to save next to, not stdout"));
+    }
+    let bench = match top.subcommand_matches("bench") {
+        Some(_) => Some(bench::parse_sizes(matches.value_of("sizes").unwrap_or("50x50,200x200,1000x1000"))?),
+        None => None,
+    };
+    let runs = value(matches, "runs", "run count")?.unwrap_or(3);
+    if runs == 0 {
+        return Err(String::from("--runs must be at least 1"));
+    }
+    // Either marker color turns on both markers, since a puzzle with only one end marked would be confusing
+    let markers = matches.is_present("markers") || matches.is_present("entrance-color") || matches.is_present("exit-color");
+    let marker = |name: &str, default: Rgb<u8>| -> Result<Option<Rgb<u8>>, String> {
+        Ok(if markers { Some(matches.value_of(name).map(parse_color).transpose()?.unwrap_or(default)) } else { None })
+    };
+    let cell_size = value(matches, "cell-size", "cell size")?.unwrap_or(1);
+    let style = ImageStyle {
+        corridor_width: value(matches, "corridor-width", "corridor width")?.unwrap_or(cell_size),
+        wall_thickness: value(matches, "wall-thickness", "wall thickness")?.unwrap_or(cell_size),
+        wall: matches.value_of("wall-color").map(parse_color).transpose()?.unwrap_or(Rgb([0, 0, 0])),
+        path: matches.value_of("path-color").map(parse_color).transpose()?.unwrap_or(Rgb([255, 255, 255])),
+        entrance: marker("entrance-color", ImageStyle::MARKERS.0)?,
+        exit: marker("exit-color", ImageStyle::MARKERS.1)?,
+    };
+    let animate = if animating {
+        let frames = value(matches, "frames", "frame count")?.unwrap_or(60);
+        if frames == 0 {
+            return Err(String::from("--frames must be at least 1"));
+        }
+        Some(SolveAnimation {
+            solver: matches.value_of("solver").map(parse_solver).transpose()?.unwrap_or(TracedSolver::BreadthFirst),
+            scale: value(matches, "cell-size", "cell size")?.unwrap_or(4),
+            frames,
+            ..SolveAnimation::default()
+        })
+    } else {
+        None
+    };
+    let entrances = match matches.value_of("entrances") {
+        Some(value) => parse_entrances(value)?,
+        None => Some(EntrancePlacement::Farthest),
+    };
+
+    Ok(Options {
+        width: value(matches, "width", "width")?.unwrap_or(default_size.0),
+        height: value(matches, "height", "height")?.unwrap_or(default_size.1),
+        algorithm: matches.value_of("algorithm").map(Algorithm::from_str).transpose().map_err(|err| err.to_string())?.unwrap_or_default(),
+        seed: value(matches, "seed", "seed")?,
+        constraints: maze_generation::Constraints {
+            entrances,
+            min_solution_length: value(matches, "min-solution-length", "length")?,
+            min_solution_fraction: value(matches, "min-solution-fraction", "fraction")?,
+            difficulty: matches.value_of("difficulty").map(str::parse).transpose().map_err(|err: MazeError| err.to_string())?,
+        },
+        max_attempts: value(matches, "max-attempts", "attempt count")?.unwrap_or(1000),
+        style,
+        background: matches.value_of("background")
+            .map(|path| image::open(path).map(|img| img.to_rgb8()).map_err(|err| format!("Can't read {}: {}", path, err)))
+            .transpose()?,
+        output,
+        format,
+        metadata,
+        count,
+        parallel: matches.is_present("parallel"),
+        quiet: matches.is_present("quiet"),
+        sweep: value(matches, "sweep", "maze count")?,
+        analyze,
+        play,
+        preview,
+        animate,
+        bench,
+        runs,
+    })
+}
+
+/// Saves the maze to the given path in the chosen format, creating its directory if needed, with a JSON sidecar if `--metadata` asks for one
+/// A path of - writes to stdout instead, with images as PNG since there's no extension to go by. PNGs are streamed a row at a time.
+fn save(maze: &Maze, options: &Options, path: &str, seed: u64) -> Result<(), MazeError> {
+    if path == "-" {
+        let stdout = io::stdout();
+        let mut stdout = stdout.lock();
+        match (options.format, &options.background) {
+            (Format::Image, Some(background)) => {
+                let img = maze.build_picture_image(&options.style, background)?;
+                DynamicImage::ImageRgb8(img).write_to(&mut stdout, ImageOutputFormat::Png)?;
+            },
+            (Format::Image, None) => maze.write_png_with_style(&mut stdout, &options.style)?,
+            (Format::Text, _) => stdout.write_all(maze.to_string().as_bytes())?,
+            (Format::Maze, _) => stdout.write_all(&maze.to_bytes())?,
+            (Format::Raw, _) => maze.write_raw(&mut stdout)?,
+        }
+        stdout.flush()?;
+        return Ok(());
+    }
+
+    if let Some(dir) = Path::new(path).parent() {
+        fs::create_dir_all(dir)?;
+    }
+    match (options.format, &options.background) {
+        (Format::Image, Some(background)) => maze.build_picture_image(&options.style, background)?.save(path)?,
+        // PNGs are streamed, so huge mazes don't need the whole image in memory. Other image formats have to be rendered first.
+        (Format::Image, None) if path.to_ascii_lowercase().ends_with(".png") => {
+            let mut file = BufWriter::new(File::create(path)?);
+            maze.write_png_with_style(&mut file, &options.style)?;
+            file.flush()?;
+        },
+        (Format::Image, None) => maze.build_image_with_style(&options.style)?.save(path)?,
+        (Format::Text, _) => fs::write(path, maze.to_string())?,
+        (Format::Maze, _) => maze.save(path)?,
+        (Format::Raw, _) => maze.save_raw(path)?,
+    }
+    if options.metadata {
+        let mut json = BufWriter::new(File::create(Path::new(path).with_extension("json"))?);
+        maze.write_metadata_json(&mut json, &MazeMetadata { algorithm: Some(options.algorithm), seed: Some(seed) })?;
+        json.flush()?;
+    }
+    Ok(())
+}
+
+/// Prints an error and exits
+fn fail<E: std::fmt::Display>(err: E) -> ! {
+    eprintln!("{}", err);
+    process::exit(1);
+}
+
+/// Prints the shape of the maze and, with the `memory-stats` feature, what generating and exporting it cost in memory
+fn analyze(maze: &Maze) {
+    let stats = maze.stats();
+    println!("Dead ends: {}", stats.dead_ends);
+    println!("Straightaways: {}", stats.straightaways);
+    println!("Turns: {}", stats.turns);
+    println!("T-junctions: {}", stats.t_junctions);
+    println!("Crossroads: {}", stats.crossroads);
+    println!("Junctions: {}", stats.junctions);
+    println!("Average corridor length: {:.2}", stats.average_corridor_length);
+    println!("Longest corridor: {}", stats.longest_corridor);
+    println!("River factor: {:.2}", stats.river_factor);
+    if let Some(length) = stats.solution_length {
+        println!("Solution length: {}", length);
+    }
+
+    #[cfg(feature = "memory-stats")]
+    {
+        let saved = maze.to_bytes().len();
+        maze.write_png(io::sink()).unwrap_or_else(|err| fail(err));
+        let memory = maze_generation::memory_stats();
+        println!("Peak grid memory: {} bytes", memory.peak_grid_bytes);
+        println!("Walk buffer allocations: {}", memory.walk_buffer_allocations);
+        println!("Saved maze: {} bytes", saved);
+        println!("Streamed PNG: {} bytes", memory.last_export_bytes);
+    }
+}
+
+/// Where to start and finish the maze when playing or previewing it: the entrance and exit, or without entrances the ends of its longest path
+fn ends(maze: &Maze) -> (CellIndex, CellIndex) {
+    match maze.entrances() {
+        Some((entrance, exit)) => (entrance.cell, exit.cell),
+        None => maze.diameter().map(|(start, exit, _)| (start, exit)).unwrap(), // Safe to unwrap since generated mazes are all path cells
+    }
+}
+
+/// Prints the maze in color with the overlay
+fn preview(maze: &Maze, show: Preview) {
+    let (start, exit) = ends(maze);
+    let (solution, distances) = match show {
+        Preview::Plain => (None, None),
+        Preview::Solution => (maze.solve(start, exit), None),
+        Preview::Heatmap => (None, maze.distances_from(start)),
+    };
+    let overlay = match (&solution, &distances) {
+        (Some(route), _) => AnsiOverlay::Route(route),
+        (_, Some(distances)) => AnsiOverlay::Heatmap(distances),
+        _ => AnsiOverlay::Plain,
+    };
+    print!("{}", maze.to_ansi(overlay));
+}
+
+/// Generates a maze until it meets the constraints, returning it with the number of attempts it took
+fn generate(options: &Options, seed: u64) -> (Maze, usize) {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    Maze::generate_until_with_rng(options.width, options.height, options.algorithm, &options.constraints, options.max_attempts, &mut rng)
+        .unwrap_or_else(|err| fail(err))
+}
+
+/// Generates the mazes with the options and saves or analyzes them, or runs a sweep or benchmark
+fn run(options: &Options) {
+    if let Some(sizes) = &options.bench {
+        let seed = options.seed.unwrap_or_else(|| ChaCha8Rng::from_entropy().gen());
+        bench::bench(sizes, options.runs, seed).unwrap_or_else(|err| fail(err));
+        return;
+    }
+
+    if let Some(count) = options.sweep {
+        let seed = options.seed.unwrap_or_else(|| ChaCha8Rng::from_entropy().gen());
+        let results = maze_generation::sweep(options.width, options.height, count, seed).unwrap_or_else(|err| fail(err));
+        maze_generation::write_sweep_csv(&results, io::stdout()).unwrap_or_else(|err| fail(err));
+        return;
+    }
+
+    let first_seed = options.seed.unwrap_or_else(|| ChaCha8Rng::from_entropy().gen());
+    if options.count == 1 {
+        let (maze, attempts) = generate(options, first_seed);
+        if !options.quiet {
+            eprintln!("Generated maze in {} attempts", attempts);
+        }
+        if options.analyze {
+            analyze(&maze);
+        } else if options.play {
+            let (start, exit) = ends(&maze);
+            play::play(maze, start, exit).unwrap_or_else(|err| fail(err));
+        } else if let Some(show) = options.preview {
+            preview(&maze, show);
+        } else if let Some(animation) = &options.animate {
+            let (start, exit) = ends(&maze);
+            let file = File::create(&options.output).map_err(MazeError::from).unwrap_or_else(|err| fail(err));
+            maze.write_solve_gif(BufWriter::new(file), animation, start, exit).unwrap_or_else(|err| fail(err));
+        } else {
+            save(&maze, options, &options.output, first_seed).unwrap_or_else(|err| fail(err));
+        }
+        return;
+    }
+
+    let make = |index: usize| {
+        let seed = first_seed.wrapping_add(index as u64);
+        let (maze, attempts) = generate(options, seed);
+        let path = options.output.replace("{seed}", &seed.to_string()).replace("{index}", &index.to_string());
+        save(&maze, options, &path, seed).unwrap_or_else(|err| fail(err));
+        if !options.quiet {
+            eprintln!("Saved {} after {} attempts", path, attempts);
+        }
+    };
+
+    // Analysis prints as it goes, so it always goes one maze at a time to keep the output in order
+    if options.analyze {
+        for index in 0..options.count {
+            let seed = first_seed.wrapping_add(index as u64);
+            println!("Maze {} (seed {})", index, seed);
+            analyze(&generate(options, seed).0);
+        }
+    } else if options.parallel {
+        (0..options.count).into_par_iter().for_each(make);
+    } else {
+        (0..options.count).for_each(make);
+    }
+}
+
+fn main() {
+    let args: Vec<OsString> = std::env::args_os().collect();
+    let top = app().get_matches_from(&args);
+    let config = flags(&top).value_of("config").map(String::from);
+    if !flags(&top).is_present("quiet") {
+        env_logger::init();
+    }
+
+    match config {
+        Some(path) => {
+            config::check_command_line(&args).unwrap_or_else(|err| fail(err));
+            // Every job is checked before any of them run, so a typo in the last one doesn't waste the time spent on the rest
+            let mut jobs = config::read_jobs(&path).unwrap_or_else(|err| fail(err));
+            // --quiet on the command line applies to every job
+            if flags(&top).is_present("quiet") {
+                jobs.iter_mut().filter(|job| !job.iter().any(|arg| arg == "--quiet")).for_each(|job| job.insert(1, String::from("--quiet")));
+            }
+            let jobs: Vec<Options> = jobs.into_iter().map(parse_args).collect::<Result<_, _>>().unwrap_or_else(|err| fail(err));
+            for (i, options) in jobs.iter().enumerate() {
+                if !options.quiet {
+                    eprintln!("Job {} of {}", i + 1, jobs.len());
+                }
+                run(options);
+            }
+        },
+        None => run(&parse_args(args).unwrap_or_else(|err| fail(err))),
+    }
+}
@@ -0,0 +1,115 @@
+#[cfg(feature = "memory-stats")]
+use core::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(all(feature = "memory-stats", feature = "std"))]
+use std::io::{self, Write};
+
+use alloc::vec::Vec;
+
+/// Where the memory went since the counters were last reset, see `memory_stats`
+/// The counters are shared by every maze and generator in the process, so run one job at a time for figures that mean something.
+#[cfg(feature = "memory-stats")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct MemoryStats {
+    /// Bytes taken by the biggest cell grid allocated, which sets the memory floor for a maze of that size
+    pub peak_grid_bytes: usize,
+    /// Times a generator's walk buffers had to grow, i.e. allocations made while generating
+    pub walk_buffer_allocations: usize,
+    /// Mazes exported by `to_bytes`, `to_raw`, `write_raw` or one of the PNG streaming methods
+    pub exports: usize,
+    /// Bytes written by all of those exports together
+    pub bytes_exported: usize,
+    /// Bytes written by the most recent export
+    pub last_export_bytes: usize,
+}
+
+#[cfg(feature = "memory-stats")]
+static PEAK_GRID_BYTES: AtomicUsize = AtomicUsize::new(0);
+#[cfg(feature = "memory-stats")]
+static WALK_BUFFER_ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+#[cfg(feature = "memory-stats")]
+static EXPORTS: AtomicUsize = AtomicUsize::new(0);
+#[cfg(feature = "memory-stats")]
+static BYTES_EXPORTED: AtomicUsize = AtomicUsize::new(0);
+#[cfg(feature = "memory-stats")]
+static LAST_EXPORT_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+/// The memory counters so far, for capacity planning at large sizes. Only kept with the `memory-stats` feature.
+#[cfg(feature = "memory-stats")]
+pub fn memory_stats() -> MemoryStats {
+    MemoryStats {
+        peak_grid_bytes: PEAK_GRID_BYTES.load(Ordering::Relaxed),
+        walk_buffer_allocations: WALK_BUFFER_ALLOCATIONS.load(Ordering::Relaxed),
+        exports: EXPORTS.load(Ordering::Relaxed),
+        bytes_exported: BYTES_EXPORTED.load(Ordering::Relaxed),
+        last_export_bytes: LAST_EXPORT_BYTES.load(Ordering::Relaxed),
+    }
+}
+
+/// Sets every memory counter back to zero
+#[cfg(feature = "memory-stats")]
+pub fn reset_memory_stats() {
+    for counter in [&PEAK_GRID_BYTES, &WALK_BUFFER_ALLOCATIONS, &EXPORTS, &BYTES_EXPORTED, &LAST_EXPORT_BYTES] {
+        counter.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Notes a newly allocated cell grid
+#[cfg(feature = "memory-stats")]
+pub(super) fn record_grid(bytes: usize) {
+    PEAK_GRID_BYTES.fetch_max(bytes, Ordering::Relaxed);
+}
+
+/// Notes a finished export
+#[cfg(feature = "memory-stats")]
+pub(super) fn record_export(bytes: usize) {
+    debug!("Exported {} bytes", bytes);
+    EXPORTS.fetch_add(1, Ordering::Relaxed);
+    BYTES_EXPORTED.fetch_add(bytes, Ordering::Relaxed);
+    LAST_EXPORT_BYTES.store(bytes, Ordering::Relaxed);
+}
+
+/// Changes a walk buffer, counting an allocation if it had to grow. Without the `memory-stats` feature this just calls `change`.
+#[inline]
+pub(super) fn grow_walk_buffer<T, F: FnOnce(&mut Vec<T>)>(buffer: &mut Vec<T>, change: F) {
+    #[cfg(feature = "memory-stats")]
+    let capacity = buffer.capacity();
+    change(buffer);
+    #[cfg(feature = "memory-stats")]
+    if buffer.capacity() != capacity {
+        WALK_BUFFER_ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Counts the bytes passing through to the writer an export was given, and records the export when it's dropped
+#[cfg(all(feature = "memory-stats", feature = "std"))]
+pub(super) struct CountingWriter<W: Write> {
+    inner: W,
+    written: usize,
+}
+
+#[cfg(all(feature = "memory-stats", feature = "std"))]
+impl<W: Write> CountingWriter<W> {
+    pub(super) fn new(inner: W) -> Self {
+        CountingWriter { inner, written: 0 }
+    }
+}
+
+#[cfg(all(feature = "memory-stats", feature = "std"))]
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.written += written;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(all(feature = "memory-stats", feature = "std"))]
+impl<W: Write> Drop for CountingWriter<W> {
+    fn drop(&mut self) {
+        record_export(self.written);
+    }
+}
@@ -0,0 +1,92 @@
+use alloc::{vec, vec::Vec};
+
+use super::Maze;
+
+/// A solid block of wall tiles, in the tile grid where cell (x, y) of the maze is tile (2x + 1, 2y + 1)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WallRect {
+    /// Column of the leftmost tile
+    pub x: usize,
+    /// Row of the top tile
+    pub y: usize,
+    /// Tiles across
+    pub width: usize,
+    /// Tiles down
+    pub height: usize,
+}
+
+/// Triangle mesh of a maze's walls, one box for each `WallRect`, for game engines to use as collision geometry
+/// The mesh is Y up: tile (x, y) covers x to x + 1 and z to z + 1 in tile units, so rows run along +z, and walls rise from y = 0.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WallMesh {
+    /// Corners of the boxes, 8 to a box
+    pub vertices: Vec<[f32; 3]>,
+    /// Three vertex indices to a triangle, wound counterclockwise seen from outside the box
+    pub indices: Vec<u32>,
+}
+
+/// Corners of each face of a box, counterclockwise seen from outside, numbered x + 2y + 4z with each axis 0 or 1
+const BOX_FACES: [[u32; 4]; 6] = [
+    [0, 4, 6, 2], [1, 3, 7, 5],
+    [0, 1, 5, 4], [2, 6, 7, 3],
+    [0, 2, 3, 1], [4, 5, 7, 6],
+];
+
+impl Maze {
+    /// Merges the wall tiles into as few rectangles as a greedy pass finds, so a game can spawn a handful of colliders instead of a cube per tile
+    /// Each rectangle grows right as far as it can from the first free wall tile in reading order, then down while the whole row below is wall.
+    /// The rectangles cover every wall tile exactly once. Openings in the outer wall are gaps like any other path tile.
+    pub fn wall_rects(&self) -> Vec<WallRect> {
+        let (width, height) = self.pixel_size();
+        info!("Merging the walls of a {}x{} tile grid", width, height);
+
+        // Indexed by y * width + x. True for wall tiles no rectangle covers yet.
+        let mut free = vec![true; width * height];
+        for y in 0..height {
+            self.path_pixels_in_row(y, |x| free[y * width + x] = false);
+        }
+
+        let mut rects = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                if !free[y * width + x] {
+                    continue;
+                }
+                let rect_width = (x..width).take_while(|&end| free[y * width + end]).count();
+                let rect_height = (y..height).take_while(|&row| free[row * width + x..row * width + x + rect_width].iter().all(|&tile| tile)).count();
+                for row in y..y + rect_height {
+                    free[row * width + x..row * width + x + rect_width].iter_mut().for_each(|tile| *tile = false);
+                }
+                rects.push(WallRect { x, y, width: rect_width, height: rect_height });
+            }
+        }
+
+        debug!("Merged the walls into {} rectangles", rects.len());
+
+        rects
+    }
+
+    /// Builds a box for every rectangle of `wall_rects`, `tile_size` across each tile and `wall_height` tall, see `WallMesh` for the layout
+    pub fn wall_mesh(&self, tile_size: f32, wall_height: f32) -> WallMesh {
+        let rects = self.wall_rects();
+        let mut vertices = Vec::with_capacity(rects.len() * 8);
+        let mut indices = Vec::with_capacity(rects.len() * BOX_FACES.len() * 6);
+
+        for rect in rects {
+            let first = vertices.len() as u32;
+            let (x0, x1) = (rect.x as f32 * tile_size, (rect.x + rect.width) as f32 * tile_size);
+            let (z0, z1) = (rect.y as f32 * tile_size, (rect.y + rect.height) as f32 * tile_size);
+            for corner in 0..8 {
+                let x = if corner & 1 == 0 { x0 } else { x1 };
+                let y = if corner & 2 == 0 { 0.0 } else { wall_height };
+                let z = if corner & 4 == 0 { z0 } else { z1 };
+                vertices.push([x, y, z]);
+            }
+            for face in &BOX_FACES {
+                indices.extend([face[0], face[1], face[2], face[0], face[2], face[3]].iter().map(|&corner| first + corner));
+            }
+        }
+
+        WallMesh { vertices, indices }
+    }
+}
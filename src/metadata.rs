@@ -0,0 +1,65 @@
+use alloc::{format, string::{String, ToString}};
+
+use std::io::{self, Write};
+#[cfg(feature = "image")]
+use std::{fs::File, io::BufWriter, path::Path};
+
+#[cfg(feature = "image")]
+use super::MazeError;
+use super::{Maze, Algorithm, Direction, Opening};
+
+/// How a maze was made, for `write_metadata_json` to record alongside it. The maze itself doesn't remember either.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct MazeMetadata {
+    pub algorithm: Option<Algorithm>,
+    pub seed: Option<u64>,
+}
+
+impl Maze {
+    /// Writes a JSON description of the maze for tools that pick up its image: its size, how it was made, its fingerprint,
+    /// the entrance and exit cells and sides, and how many cells long the solution is
+    /// Anything unknown is null, like the entrances of a maze that doesn't have any. Seeds and fingerprints are written as strings,
+    /// since JSON numbers can't hold every u64, the seed in decimal as the binary's `--seed` takes it and the fingerprint in hex.
+    pub fn write_metadata_json<W: Write>(&self, mut writer: W, metadata: &MazeMetadata) -> io::Result<()> {
+        let side = |direction: Direction| match direction {
+            Direction::Up => "top",
+            Direction::Right => "right",
+            Direction::Down => "bottom",
+            Direction::Left => "left",
+        };
+        let opening = |opening: Option<Opening>| match opening {
+            Some(Opening { cell, side: direction }) => format!("{{ \"x\": {}, \"y\": {}, \"side\": \"{}\" }}", cell.0, cell.1, side(direction)),
+            None => String::from("null"),
+        };
+        let or_null = |value: Option<String>| value.unwrap_or_else(|| String::from("null"));
+
+        writeln!(writer, "{{")?;
+        writeln!(writer, "  \"width\": {},", self.size.0)?;
+        writeln!(writer, "  \"height\": {},", self.size.1)?;
+        writeln!(writer, "  \"algorithm\": {},", or_null(metadata.algorithm.map(|algorithm| format!("\"{}\"", algorithm))))?;
+        writeln!(writer, "  \"seed\": {},", or_null(metadata.seed.map(|seed| format!("\"{}\"", seed))))?;
+        writeln!(writer, "  \"fingerprint\": \"{:016x}\",", self.fingerprint())?;
+        writeln!(writer, "  \"entrance\": {},", opening(self.entrances.map(|(entrance, _)| entrance)))?;
+        writeln!(writer, "  \"exit\": {},", opening(self.entrances.map(|(_, exit)| exit)))?;
+        let solution_length = self.entrances.and_then(|(entrance, exit)| self.solve(entrance.cell, exit.cell)).map(|path| path.len().to_string());
+        writeln!(writer, "  \"solution_length\": {}", or_null(solution_length))?;
+        writeln!(writer, "}}")?;
+
+        Ok(())
+    }
+
+    /// Saves the maze like `save_image`, and `write_metadata_json` next to it with the extension swapped for .json, e.g. maze.json for maze.png
+    #[cfg(feature = "image")]
+    pub fn save_image_with_metadata<P: AsRef<Path>>(&self, path: P, metadata: &MazeMetadata) -> Result<(), MazeError> {
+        let path = path.as_ref();
+        self.save_image(path)?;
+
+        let mut json = BufWriter::new(File::create(path.with_extension("json"))?);
+        self.write_metadata_json(&mut json, metadata)?;
+        json.flush()?;
+
+        info!("Saved the maze and its metadata");
+
+        Ok(())
+    }
+}
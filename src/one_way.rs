@@ -0,0 +1,66 @@
+use alloc::{string::String, vec::Vec};
+
+use super::{Maze, MazeError, CellIndex, Direction};
+
+impl Maze {
+    /// Makes the open passage between two neighboring cells walkable only from `from` to `to`
+    /// The solvers and distance measures respect the direction, while `validate` and `regions` still see an ordinary passage.
+    /// Replaces a one-way mark going the other way. Fails with NotAdjacent if the cells aren't neighbors, or NoPassage if the wall between them is closed.
+    pub fn make_one_way(&mut self, from: CellIndex, to: CellIndex) -> Result<(), MazeError> {
+        let direction = self.passage_direction(from, to)?;
+        if !self.can_move(from, direction) {
+            return Err(MazeError::NoPassage { from, to });
+        }
+
+        info!("Made passage from ({}, {}) to ({}, {}) one-way", from.0, from.1, to.0, to.1);
+
+        self.make_two_way(from, to);
+        self.one_way.push((from, to));
+        Ok(())
+    }
+
+    /// Lets the passage between two cells be walked both ways again. Returns false if it wasn't one-way.
+    pub fn make_two_way(&mut self, from: CellIndex, to: CellIndex) -> bool {
+        let before = self.one_way.len();
+        self.one_way.retain(|&(a, b)| (a, b) != (from, to) && (a, b) != (to, from));
+        self.one_way.len() != before
+    }
+
+    /// Every one-way passage, as the (from, to) cells it can be walked between, in the order they were made
+    pub fn one_way_passages(&self) -> &[(CellIndex, CellIndex)] {
+        &self.one_way
+    }
+
+    /// Returns true if there's a passage from one neighboring cell to the other and it can be walked in that direction
+    pub fn can_pass(&self, from: CellIndex, to: CellIndex) -> bool {
+        Direction::between(from, to).is_some_and(|direction| self.can_move(from, direction)) && !self.one_way.contains(&(to, from))
+    }
+
+    /// Draws the maze as text with one character per pixel: `#` for walls, spaces for paths,
+    /// and an arrow on every one-way passage pointing the way it can be walked
+    pub fn to_ascii(&self) -> String {
+        let (width, height) = self.pixel_size();
+        let pixels = self.to_pixels();
+
+        let mut rows: Vec<Vec<char>> = (0..height)
+            .map(|y| pixels.iter().map(|col| if col[y].is_wall() { '#' } else { ' ' }).collect())
+            .collect();
+        for &(from, to) in &self.one_way {
+            // Safe to unwrap since make_one_way only accepts neighbors
+            let arrow = match Direction::between(from, to).unwrap() {
+                Direction::Up => '^',
+                Direction::Right => '>',
+                Direction::Down => 'v',
+                Direction::Left => '<',
+            };
+            rows[from.1 + to.1 + 1][from.0 + to.0 + 1] = arrow;
+        }
+
+        let mut text = String::with_capacity((width + 1) * height);
+        for row in rows {
+            text.extend(row);
+            text.push('\n');
+        }
+        text
+    }
+}
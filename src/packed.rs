@@ -0,0 +1,178 @@
+use alloc::{borrow::Cow, vec, vec::Vec};
+
+use super::{Cell, CellIndex};
+
+/// Bits each cell needs: one per side, and one for whether it's carved
+pub(super) const CELL_BITS: usize = 5;
+/// Cells never straddle two words, so the top 4 bits of every word go unused
+pub(super) const CELLS_PER_WORD: usize = 64 / CELL_BITS;
+const CELL_MASK: u64 = (1 << CELL_BITS) - 1;
+
+/// With the `z-order` feature, cells are stored in square tiles this many cells across
+#[cfg(feature = "z-order")]
+const TILE_SIZE: usize = 8;
+
+/// One of every possible cell, so lookups can hand out references without a byte per cell to point at
+static ALL_CELLS: [Cell; 1 << CELL_BITS] = all_cells();
+
+const fn all_cells() -> [Cell; 1 << CELL_BITS] {
+    let mut cells = [Cell(0); 1 << CELL_BITS];
+    let mut bits = 0;
+    while bits < cells.len() {
+        cells[bits] = Cell(bits as u8);
+        bits += 1;
+    }
+    cells
+}
+
+/// Cells packed 12 to a u64 word, in the order they'd sit in a flat Vec
+/// That's about two thirds of the memory of a byte per cell, e.g. 150 MB instead of 225 MB for a 15,000 x 15,000 maze.
+/// It's not the 1 bit per pixel of the rendered grid, about 112 MB for that maze, since cells also keep entrances and one-way doors;
+/// `PixelBits` is that grid, for when only the picture is wanted.
+/// Unused bits are always zero, so derived equality and hashing only see the cells.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(super) struct PackedCells {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl PackedCells {
+    /// Empty cells for a maze of the given size, enough for every offset `grid_offset` gives
+    pub(super) fn grid(size: (usize, usize)) -> Self {
+        PackedCells::new(grid_len(size))
+    }
+
+    /// `len` empty cells
+    pub(super) fn new(len: usize) -> Self {
+        let cells = PackedCells { words: vec![0; words_for(len)], len };
+        #[cfg(feature = "memory-stats")]
+        super::memory::record_grid(cells.words.capacity() * 8);
+        cells
+    }
+
+    /// Empties every cell and changes the number of cells to `len`, reusing the allocation where it can
+    pub(super) fn reset(&mut self, len: usize) {
+        self.words.clear();
+        self.words.resize(words_for(len), 0);
+        self.len = len;
+        #[cfg(feature = "memory-stats")]
+        super::memory::record_grid(self.words.capacity() * 8);
+    }
+
+    /// The cell at an offset. Offsets past the end but inside the last word give an empty cell, so callers check bounds themselves.
+    pub(super) fn get(&self, offset: usize) -> &'static Cell {
+        let (word, shift) = locate(offset);
+        &ALL_CELLS[(self.words[word] >> shift & CELL_MASK) as usize]
+    }
+
+    /// Replaces the cell at an offset. Panics if it's past the end.
+    pub(super) fn set(&mut self, offset: usize, cell: Cell) {
+        assert!(offset < self.len, "cell offset {} is past the end of {} cells", offset, self.len);
+        let (word, shift) = locate(offset);
+        self.words[word] = self.words[word] & !(CELL_MASK << shift) | (cell.0 as u64 & CELL_MASK) << shift;
+    }
+
+    /// `len` cells from words in the layout `words` returns, or None if there are the wrong number of words or any unused bits are set
+    pub(super) fn from_words(words: Vec<u64>, len: usize) -> Option<Self> {
+        if words.len() != words_for(len) {
+            return None;
+        }
+
+        let unused_top_bits = !0 << (CELLS_PER_WORD * CELL_BITS);
+        let past_the_end = match len % CELLS_PER_WORD {
+            0 => 0,
+            used => !0 << (used * CELL_BITS),
+        };
+        let tail_clean = words.last().is_none_or(|&last| last & past_the_end == 0);
+        if !tail_clean || words.iter().any(|&word| word & unused_top_bits != 0) {
+            return None;
+        }
+
+        #[cfg(feature = "memory-stats")]
+        super::memory::record_grid(words.capacity() * 8);
+        Some(PackedCells { words, len })
+    }
+
+    /// The packed words, 12 cells to each, with cell `i` in bits `5 * (i % 12)` and up of word `i / 12`
+    pub(super) fn words(&self) -> &[u64] {
+        &self.words
+    }
+
+    /// Every cell in order
+    pub(super) fn iter(&self) -> impl Iterator<Item = Cell> + '_ {
+        (0..self.len).map(move |offset| *self.get(offset))
+    }
+
+    /// The cells of a maze of the given size laid out row by row, as the raw format stores them, which is how they're already stored without `z-order`
+    pub(super) fn row_major(&self, size: (usize, usize)) -> Cow<'_, PackedCells> {
+        #[cfg(feature = "z-order")]
+        {
+            let mut rows = PackedCells::new(size.0 * size.1);
+            for y in 0..size.1 {
+                for x in 0..size.0 {
+                    rows.set(y * size.0 + x, *self.get(grid_offset(size, (x, y))));
+                }
+            }
+            Cow::Owned(rows)
+        }
+        #[cfg(not(feature = "z-order"))]
+        {
+            let _ = size;
+            Cow::Borrowed(self)
+        }
+    }
+
+    /// The reverse of `row_major`
+    pub(super) fn from_row_major(rows: PackedCells, size: (usize, usize)) -> PackedCells {
+        #[cfg(feature = "z-order")]
+        {
+            let mut cells = PackedCells::grid(size);
+            for y in 0..size.1 {
+                for x in 0..size.0 {
+                    cells.set(grid_offset(size, (x, y)), *rows.get(y * size.0 + x));
+                }
+            }
+            cells
+        }
+        #[cfg(not(feature = "z-order"))]
+        {
+            let _ = size;
+            rows
+        }
+    }
+}
+
+/// How many cells `PackedCells::grid` holds for a maze of the given size
+/// With `z-order` the grid is rounded up to whole tiles, so mazes narrower than a tile waste some space.
+pub(super) fn grid_len(size: (usize, usize)) -> usize {
+    #[cfg(feature = "z-order")]
+    return size.0.div_ceil(TILE_SIZE) * size.1.div_ceil(TILE_SIZE) * TILE_SIZE * TILE_SIZE;
+    #[cfg(not(feature = "z-order"))]
+    return size.0 * size.1;
+}
+
+/// Where a cell of a maze of the given size is stored. The cell must be inside the maze.
+/// Normally that's row by row. With `z-order`, the grid is split into 8x8 tiles stored row by row, and the cells of each tile
+/// are stored along a Z-order curve, so cells that are near each other in any direction are mostly in the same few words.
+pub(super) fn grid_offset(size: (usize, usize), cell: CellIndex) -> usize {
+    #[cfg(feature = "z-order")]
+    {
+        // Interleaves the bits of a coordinate inside a tile with zeros
+        let spread = |v: usize| (v & 1) | (v & 2) << 1 | (v & 4) << 2;
+        let tile = (cell.1 / TILE_SIZE) * size.0.div_ceil(TILE_SIZE) + cell.0 / TILE_SIZE;
+        tile * TILE_SIZE * TILE_SIZE + (spread(cell.0 % TILE_SIZE) | spread(cell.1 % TILE_SIZE) << 1)
+    }
+    #[cfg(not(feature = "z-order"))]
+    {
+        cell.1 * size.0 + cell.0
+    }
+}
+
+fn words_for(len: usize) -> usize {
+    len.div_ceil(CELLS_PER_WORD)
+}
+
+/// The word holding a cell, and how far up that word the cell's bits start
+fn locate(offset: usize) -> (usize, usize) {
+    (offset / CELLS_PER_WORD, offset % CELLS_PER_WORD * CELL_BITS)
+}
@@ -0,0 +1,110 @@
+use alloc::vec::Vec;
+
+use rand::{Rng, SeedableRng, prelude::SliceRandom};
+use rand_chacha::ChaCha8Rng;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use super::{Maze, MazeError, CellIndex, Direction, Generator, Piece};
+
+/// (offset, size, seed) of one region
+type Region = (CellIndex, (usize, usize), u64);
+
+impl Maze {
+    /// Generates a big maze on all cores, by generating square regions of `region_size` cells at the same time and joining them up
+    /// Each region is a uniform maze of its own, and one doorway is carved along each border of a random spanning tree of the regions,
+    /// so the result is still a perfect maze. It isn't a uniformly random one though: paths cross region borders less often than they would otherwise.
+    /// The result is bit-identical to `from_seed_regions` with the same arguments, however many cores there are.
+    /// Fails with OutOfRange if `region_size` is 0.
+    #[cfg(feature = "parallel")]
+    pub fn from_seed_parallel(width: usize, height: usize, seed: u64, region_size: usize) -> Result<Maze, MazeError> {
+        Maze::from_regions(width, height, seed, region_size, |regions| regions.par_iter()
+            .map(|&(_, (region_width, region_height), seed)| Generator::from_seed(seed).generate(region_width, region_height))
+            .collect())
+    }
+
+    /// Generates the same maze as `from_seed_parallel` one region at a time on the current thread, with no `parallel` feature needed
+    /// This is the canonical definition of the output for a seed. A ChaCha8 RNG seeded with `seed` first draws one u64 per region, row by row
+    /// from the top left, and each region is generated with `Maze::from_seed` using its own u64. The same RNG then shuffles the region
+    /// borders to pick the spanning tree, and draws where along each chosen border its doorway goes.
+    pub fn from_seed_regions(width: usize, height: usize, seed: u64, region_size: usize) -> Result<Maze, MazeError> {
+        Maze::from_regions(width, height, seed, region_size, |regions| regions.iter()
+            .map(|&(_, (region_width, region_height), seed)| Generator::from_seed(seed).generate(region_width, region_height))
+            .collect())
+    }
+
+    /// Both region generators, with `generate` making the maze for each (offset, size, seed) region in order
+    fn from_regions<F: FnOnce(&[Region]) -> Result<Vec<Maze>, MazeError>>(width: usize, height: usize, seed: u64, region_size: usize, generate: F) -> Result<Maze, MazeError> {
+        if width == 0 || height == 0 {
+            return Err(MazeError::InvalidDimensions { got: (width, height) });
+        }
+        if region_size == 0 {
+            return Err(MazeError::OutOfRange { setting: "region_size", got: 0.0, min: 1.0, max: f64::INFINITY });
+        }
+
+        let (columns, rows) = (width.div_ceil(region_size), height.div_ceil(region_size));
+        info!("Generating a {}x{} maze as {}x{} regions", width, height, columns, rows);
+
+        // Every region, row by row. Seeds are drawn up front so the result doesn't depend on the order regions finish in.
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let regions: Vec<Region> = (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (column * region_size, row * region_size)))
+            .map(|(x, y)| ((x, y), (region_size.min(width - x), region_size.min(height - y)), rng.gen()))
+            .collect();
+
+        let mazes = generate(&regions)?;
+
+        // A random spanning tree of the regions, with Kruskal's algorithm over the borders between them in a random order
+        let mut borders = Vec::new();
+        for row in 0..rows {
+            for column in 0..columns {
+                let region = row * columns + column;
+                if column + 1 < columns {
+                    borders.push((region, region + 1, Direction::Right));
+                }
+                if row + 1 < rows {
+                    borders.push((region, region + columns, Direction::Down));
+                }
+            }
+        }
+        borders.shuffle(&mut rng);
+
+        let mut parent: Vec<usize> = (0..regions.len()).collect();
+        let mut doorways = Vec::with_capacity(regions.len() - 1);
+        for (region, neighbor, direction) in borders {
+            let (root, neighbor_root) = (find_root(&mut parent, region), find_root(&mut parent, neighbor));
+            if root == neighbor_root {
+                continue;
+            }
+            parent[root] = neighbor_root;
+
+            let ((x, y), (region_width, region_height), _) = regions[region];
+            doorways.push(match direction {
+                Direction::Right => {
+                    let door_y = y + rng.gen_range(0, region_height);
+                    ((x + region_width - 1, door_y), (x + region_width, door_y))
+                },
+                _ => {
+                    let door_x = x + rng.gen_range(0, region_width);
+                    ((door_x, y + region_height - 1), (door_x, y + region_height))
+                },
+            });
+        }
+
+        let pieces: Vec<Piece> = regions.iter().zip(&mazes).map(|(&(offset, _, _), maze)| Piece { offset, maze }).collect();
+        let maze = Maze::stitch((width, height), &pieces, &doorways)?;
+
+        info!("Region maze generation complete");
+
+        Ok(maze)
+    }
+}
+
+/// The representative of a region's group in the union-find forest, flattening the path on the way
+fn find_root(parent: &mut [usize], mut region: usize) -> usize {
+    while parent[region] != region {
+        parent[region] = parent[parent[region]];
+        region = parent[region];
+    }
+    region
+}
@@ -0,0 +1,64 @@
+#[cfg(feature = "image")]
+use image::RgbImage;
+
+use super::{Maze, MazeError, CellIndex, Direction};
+
+impl Maze {
+    /// Redraws only the given cells of a frame made by `to_rgba_bytes`, after they've been edited or carved
+    /// Each cell's own pixel and the four walls around it are repainted, which is all a change to one cell can affect.
+    /// Cells on both sides of a changed passage only need listing once. For live previews of big mazes, where redrawing
+    /// the whole frame for every step would be too slow. Fails with FrameSizeMismatch if the frame is the wrong size,
+    /// or OutOfBounds if a cell is outside the maze, in which case the frame is left as it was.
+    pub fn patch_rgba_bytes(&self, frame: &mut [u8], changed: &[CellIndex]) -> Result<(), MazeError> {
+        self.patch(frame, &[0, 0, 0, 255], &[255, 255, 255, 255], changed)
+    }
+
+    /// Redraws only the given cells of an image made by `build_image`, like `patch_rgba_bytes`
+    #[cfg(feature = "image")]
+    pub fn patch_image(&self, img: &mut RgbImage, changed: &[CellIndex]) -> Result<(), MazeError> {
+        let (width, height) = self.pixel_size();
+        if img.dimensions() != (width as u32, height as u32) {
+            return Err(MazeError::FrameSizeMismatch { expected: width * height * 3, got: img.len() });
+        }
+
+        self.patch(img, &[0, 0, 0], &[255, 255, 255], changed)
+    }
+
+    /// Repaints the pixels of the changed cells in a frame laid out like `render_into` with the same wall and path bytes
+    fn patch(&self, frame: &mut [u8], wall: &[u8], path: &[u8], changed: &[CellIndex]) -> Result<(), MazeError> {
+        let (width, height) = self.pixel_size();
+        let channels = wall.len();
+        if frame.len() != width * height * channels {
+            return Err(MazeError::FrameSizeMismatch { expected: width * height * channels, got: frame.len() });
+        }
+        if let Some(&cell) = changed.iter().find(|cell| cell.0 >= self.size.0 || cell.1 >= self.size.1) {
+            return Err(MazeError::OutOfBounds { cell });
+        }
+
+        debug!("Patching {} changed cells", changed.len());
+
+        let mut paint = |px: usize, py: usize, is_path: bool| {
+            let offset = (py * width + px) * channels;
+            frame[offset..offset + channels].copy_from_slice(if is_path { path } else { wall });
+        };
+        for &cell in changed {
+            let (px, py) = (2 * cell.0 + 1, 2 * cell.1 + 1);
+            paint(px, py, self.is_path(cell));
+
+            // A wall is a passage if the cell on either side of it is open towards the other, same as `path_pixels_in_row`
+            for direction in Direction::ALL {
+                let open = self.is_open(cell, direction)
+                    || self.step(cell, direction).is_some_and(|next| self.is_open(next, direction.reverse()));
+                let (wx, wy) = match direction {
+                    Direction::Up => (px, py - 1),
+                    Direction::Right => (px + 1, py),
+                    Direction::Down => (px, py + 1),
+                    Direction::Left => (px - 1, py),
+                };
+                paint(wx, wy, open);
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+//! The `play` subcommand: walk through the maze in the terminal with the arrow keys, WASD or hjkl
+//! The terminal is put in raw mode with `stty`, so this only works on Unix-like systems.
+
+use std::io::{self, Read, Write};
+use std::process::{Command, Stdio};
+
+use maze_generation::{CellIndex, CellIndexExt, CellType, Direction, Maze};
+
+/// The state of a game in progress
+struct Game {
+    maze: Maze,
+    /// Every tile of the rendered maze, indexed as tiles[x][y]
+    tiles: Vec<Vec<CellType>>,
+    start: CellIndex,
+    player: CellIndex,
+    exit: CellIndex,
+    /// Cells the player has been through, indexed as visited[x][y]
+    visited: Vec<Vec<bool>>,
+    moves: usize,
+    /// Draw the shortest path from the player to the exit
+    show_solution: bool,
+}
+
+/// What a key press asks for
+enum Action {
+    Move(Direction),
+    ToggleSolution,
+    Quit,
+    Nothing,
+}
+
+impl Game {
+    fn new(maze: Maze, start: CellIndex, exit: CellIndex) -> Self {
+        let (width, height) = maze.size();
+        let tiles = maze.scale(1).unwrap(); // Safe to unwrap since a factor of 1 is always valid
+        let mut visited = vec![vec![false; height]; width];
+        visited[start.0][start.1] = true;
+
+        Game { maze, tiles, start, player: start, exit, visited, moves: 0, show_solution: false }
+    }
+
+    fn try_move(&mut self, direction: Direction) {
+        if !self.maze.can_move(self.player, direction) {
+            return;
+        }
+
+        self.player = self.player.offset(direction).unwrap(); // Safe to unwrap since can_move only allows steps onto cells in the maze
+        self.visited[self.player.0][self.player.1] = true;
+        self.moves += 1;
+    }
+
+    /// The whole screen, two characters per tile so the maze keeps roughly the right shape. Lines end in \r\n since the terminal is raw.
+    fn render(&self) -> String {
+        let solution = if self.show_solution { self.maze.solve(self.player, self.exit).unwrap_or_default() } else { Vec::new() };
+        let (width, height) = (self.tiles.len(), self.tiles.first().map_or(0, Vec::len));
+
+        // Draw over the last frame from the top left instead of clearing the screen, so redraws don't flicker
+        let mut screen = String::from("\x1b[H");
+        for y in 0..height {
+            for x in 0..width {
+                // Cells are at odd tile coordinates, and the walls and passages between them at the rest
+                let cell = (x / 2, y / 2);
+                let on_cell = x % 2 == 1 && y % 2 == 1;
+                let tile = match self.tiles[x][y] {
+                    CellType::Wall => "██",
+                    _ if on_cell && cell == self.player => "@@",
+                    _ if on_cell && cell == self.exit => "\x1b[32mXX\x1b[0m",
+                    _ if on_cell && solution.contains(&cell) => "\x1b[33m()\x1b[0m",
+                    _ if on_cell && self.visited[cell.0][cell.1] => "··",
+                    _ => "  ",
+                };
+                screen.push_str(tile);
+            }
+            screen.push_str("\r\n");
+        }
+
+        if self.player == self.exit {
+            let shortest = self.maze.solve(self.start, self.exit).unwrap().len() - 1; // Safe to unwrap since the player walked here, so there's a path
+            screen.push_str(&format!("You made it out in {} moves! The shortest way is {}. Press any key to quit.\r\n", self.moves, shortest));
+        } else {
+            screen.push_str(&format!("Moves: {}   Arrow keys, WASD or hjkl to move, p to show the way out, q to quit\x1b[K\r\n", self.moves));
+        }
+        screen
+    }
+}
+
+/// Reads what a burst of bytes from the terminal asks for. Arrow keys come in as escape sequences.
+fn action(keys: &[u8]) -> Action {
+    match keys {
+        [27, b'[', b'A', ..] | [b'w', ..] | [b'k', ..] => Action::Move(Direction::Up),
+        [27, b'[', b'B', ..] | [b's', ..] | [b'j', ..] => Action::Move(Direction::Down),
+        [27, b'[', b'C', ..] | [b'd', ..] | [b'l', ..] => Action::Move(Direction::Right),
+        [27, b'[', b'D', ..] | [b'a', ..] | [b'h', ..] => Action::Move(Direction::Left),
+        [b'p', ..] => Action::ToggleSolution,
+        [b'q', ..] | [3, ..] | [27] => Action::Quit,
+        _ => Action::Nothing,
+    }
+}
+
+/// Runs `stty` on the terminal the game is being played in, returning what it printed
+fn stty(args: &[&str]) -> io::Result<String> {
+    let output = Command::new("stty").args(args).stdin(Stdio::inherit()).output()?;
+    if !output.status.success() {
+        return Err(io::Error::other("stty failed, is stdin a terminal?"));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Plays the maze from `start` until the player reaches `exit` or quits
+pub fn play(maze: Maze, start: CellIndex, exit: CellIndex) -> io::Result<()> {
+    let saved = stty(&["-g"])?;
+    // Raw so keys arrive one at a time without echo, and a read waits at most a tenth of a second once a key arrives so a lone Esc gets through
+    stty(&["raw", "-echo", "min", "1", "time", "1"])?;
+
+    let result = game_loop(Game::new(maze, start, exit));
+
+    stty(&[&saved])?;
+    print!("\x1b[?25h");
+    io::stdout().flush()?;
+    result
+}
+
+fn game_loop(mut game: Game) -> io::Result<()> {
+    let stdin = io::stdin();
+    let mut stdin = stdin.lock();
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+    // Clear the screen once and hide the cursor
+    write!(stdout, "\x1b[2J\x1b[?25l")?;
+
+    let mut keys = [0u8; 8];
+    loop {
+        stdout.write_all(game.render().as_bytes())?;
+        stdout.flush()?;
+
+        let read = stdin.read(&mut keys)?;
+        if read == 0 || game.player == game.exit {
+            return Ok(());
+        }
+        match action(&keys[..read]) {
+            Action::Move(direction) => game.try_move(direction),
+            Action::ToggleSolution => game.show_solution = !game.show_solution,
+            Action::Quit => return Ok(()),
+            Action::Nothing => {},
+        }
+    }
+}
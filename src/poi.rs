@@ -0,0 +1,135 @@
+use alloc::{string::String, vec, vec::Vec};
+
+#[cfg(feature = "image")]
+use image::{Rgb, RgbImage};
+use rand::{Rng, seq::SliceRandom};
+
+use super::{Maze, MazeError, CellIndex};
+
+/// Rules for scattering points of interest with `Maze::place_points`. Distances are steps along the maze's passages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct PlacementRules {
+    /// How many points to place
+    pub count: usize,
+    /// Minimum steps between any two points with the same label, counting ones placed before
+    pub min_spacing: usize,
+    /// Minimum steps from the entrance. Ignored if the maze has no entrances.
+    pub min_entrance_distance: usize,
+    /// Use dead ends before any other cells, e.g. for treasure at the end of a detour
+    pub prefer_dead_ends: bool,
+}
+
+impl Maze {
+    /// Picks path cells for points of interest like treasure, traps or spawns, and tags each one with `label`
+    /// Cells are tried in a random order, dead ends first if the rules prefer them, and each is taken if it's far enough from every
+    /// point already there, so a tight layout can fail where a cleverer one would fit. Returns the cells in the order they were placed.
+    /// Fails with NoRoom if fewer than `count` cells fit the rules, in which case nothing is tagged.
+    pub fn place_points<R: Rng + ?Sized>(&mut self, label: &str, rules: &PlacementRules, rng: &mut R) -> Result<Vec<CellIndex>, MazeError> {
+        let from_entrance = match self.entrances {
+            Some((entrance, _)) if rules.min_entrance_distance > 0 => Some(self.bfs_distances(entrance.cell)),
+            _ => None,
+        };
+
+        let mut candidates: Vec<CellIndex> = self.path_cells()
+            .filter(|cell| from_entrance.as_ref().is_none_or(|distances| {
+                distances[cell.0][cell.1].is_some_and(|distance| distance >= rules.min_entrance_distance)
+            }))
+            .collect();
+        candidates.shuffle(rng);
+        if rules.prefer_dead_ends {
+            // A stable sort, so the dead ends and the rest each stay shuffled
+            candidates.sort_by_key(|&cell| self.linked_cells(cell).len() != 1);
+        }
+
+        // The steps from each cell to its nearest point so far, or None if no point is reachable from it
+        let mut nearest: Vec<Vec<Option<usize>>> = vec![vec![None; self.size.1]; self.size.0];
+        if rules.min_spacing > 0 {
+            let existing: Vec<CellIndex> = self.cells_tagged(label).collect();
+            for cell in existing {
+                keep_nearest(&mut nearest, self.bfs_distances(cell));
+            }
+        }
+
+        let mut placed = Vec::new();
+        for cell in candidates {
+            if placed.len() == rules.count {
+                break;
+            }
+            if nearest[cell.0][cell.1].is_some_and(|distance| distance < rules.min_spacing) {
+                continue;
+            }
+
+            placed.push(cell);
+            if rules.min_spacing > 0 {
+                keep_nearest(&mut nearest, self.bfs_distances(cell));
+            }
+        }
+
+        if placed.len() < rules.count {
+            return Err(MazeError::NoRoom { label: String::from(label), placed: placed.len(), wanted: rules.count });
+        }
+
+        info!("Placed {} points labeled {}", placed.len(), label);
+
+        for &cell in &placed {
+            self.add_tag(cell, label)?;
+        }
+        Ok(placed)
+    }
+
+    /// Picks `count` spawn cells whose distances in steps to `goal` are all within `tolerance` of each other, for fair multiplayer starts
+    /// Of all the ranges of distances that hold enough cells, the one farthest from the goal is used, and the spawns are picked from it at random.
+    /// The goal itself is never a spawn. Returns None if the goal is a wall or no range holds `count` cells.
+    pub fn balanced_spawns<R: Rng + ?Sized>(&self, goal: CellIndex, count: usize, tolerance: usize, rng: &mut R) -> Option<Vec<CellIndex>> {
+        if !self.is_path(goal) {
+            return None;
+        }
+
+        let distances = self.bfs_distances(goal);
+        let mut cells: Vec<(usize, CellIndex)> = self.path_cells()
+            .filter_map(|cell| distances[cell.0][cell.1].map(|distance| (distance, cell)))
+            .filter(|&(distance, _)| distance > 0)
+            .collect();
+        cells.sort_unstable();
+
+        // Try each distance as the nearest end of the range, farthest first
+        let mut range: Vec<CellIndex> = (0..cells.len()).rev()
+            .filter(|&start| start == 0 || cells[start - 1].0 != cells[start].0)
+            .map(|start| &cells[start..cells.partition_point(|&(distance, _)| distance <= cells[start].0 + tolerance)])
+            .find(|range| range.len() >= count)?
+            .iter()
+            .map(|&(_, cell)| cell)
+            .collect();
+        let (spawns, _) = range.partial_shuffle(rng, count);
+        let spawns = spawns.to_vec();
+
+        debug!("Picked spawns {:?} around ({}, {})", spawns, goal.0, goal.1);
+
+        Some(spawns)
+    }
+
+    /// Like `build_marked_image`, with every cell tagged with one of the labels drawn in its color, e.g. the points from `place_points`
+    /// A cell with more than one of the labels gets the color of the last one listed.
+    #[cfg(feature = "image")]
+    pub fn build_points_image(&self, markers: &[(&str, Rgb<u8>)]) -> RgbImage {
+        let mut img = self.build_marked_image();
+        for &(label, color) in markers {
+            for (x, y) in self.cells_tagged(label) {
+                img.put_pixel(2 * x as u32 + 1, 2 * y as u32 + 1, color);
+            }
+        }
+        img
+    }
+}
+
+/// Lowers each cell's distance to the nearest point to its distance to a new one, where that's closer
+fn keep_nearest(nearest: &mut [Vec<Option<usize>>], distances: Vec<Vec<Option<usize>>>) {
+    for (column, distances) in nearest.iter_mut().zip(distances) {
+        for (near, distance) in column.iter_mut().zip(distances) {
+            *near = match (*near, distance) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            };
+        }
+    }
+}
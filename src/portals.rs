@@ -0,0 +1,42 @@
+use super::{Maze, MazeError, CellIndex};
+
+impl Maze {
+    /// Links two path cells with a portal, so moving into one lets you step straight out of the other
+    /// The solvers, region labeling and distance measures all treat a portal like a passage between the two cells, while
+    /// `validate` and the wall follower only look at the real passages. Fails with InvalidPortal if either cell is a wall,
+    /// they're the same cell, or either one already has a portal.
+    pub fn add_portal(&mut self, from: CellIndex, to: CellIndex) -> Result<(), MazeError> {
+        if from == to || !self.is_path(from) || !self.is_path(to) || self.portal_partner(from).is_some() || self.portal_partner(to).is_some() {
+            return Err(MazeError::InvalidPortal { from, to });
+        }
+
+        info!("Linked ({}, {}) and ({}, {}) with a portal", from.0, from.1, to.0, to.1);
+
+        self.portals.push((from, to));
+        Ok(())
+    }
+
+    /// Removes the portal at either end and returns both of its ends, or None if the cell has no portal
+    pub fn remove_portal(&mut self, cell: CellIndex) -> Option<(CellIndex, CellIndex)> {
+        let index = self.portals.iter().position(|&(a, b)| a == cell || b == cell)?;
+        Some(self.portals.remove(index))
+    }
+
+    /// Every portal, as the pair of cells it links, in the order they were added
+    pub fn portals(&self) -> &[(CellIndex, CellIndex)] {
+        &self.portals
+    }
+
+    /// The cell at the other end of this cell's portal, if it has one
+    pub fn portal_partner(&self, cell: CellIndex) -> Option<CellIndex> {
+        self.portals.iter().find_map(|&(a, b)| {
+            if cell == a {
+                Some(b)
+            } else if cell == b {
+                Some(a)
+            } else {
+                None
+            }
+        })
+    }
+}
@@ -0,0 +1,25 @@
+
+
+/// How far along a maze generation is, passed to the callback given to `Maze::with_progress`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GenerationProgress {
+    /// Number of cells added to the maze so far
+    pub cells_carved: usize,
+    /// Number of cells the finished maze will have, or the target for a `Generator::with_fill` maze, which the last walk can carve past
+    pub total_cells: usize,
+    /// Number of random walks added to the maze so far
+    pub walks_completed: usize,
+}
+
+impl GenerationProgress {
+    /// Percentage of cells carved so far, from 0 to 100
+    /// Wilson's algorithm speeds up as the maze grows, so this tends to crawl at first and race at the end.
+    pub fn percent_done(&self) -> f64 {
+        (100.0 * self.cells_carved as f64 / self.total_cells as f64).min(100.0)
+    }
+
+    /// Returns true once every cell has been carved
+    pub fn is_done(&self) -> bool {
+        self.cells_carved >= self.total_cells
+    }
+}
@@ -0,0 +1,170 @@
+use alloc::{vec, vec::Vec};
+
+#[cfg(feature = "image")]
+use image::{Rgb, RgbImage};
+use rand::Rng;
+
+#[cfg(feature = "image")]
+use super::Direction;
+use super::{Maze, MazeError, CellIndex, GenerationEvent};
+
+/// Which loop-erased walk added each cell of a maze, and in what order, for seeing how Wilson's algorithm built it
+/// Each walk is a separate branch grafted onto the tree, so coloring cells by walk shows the branches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Provenance {
+    size: (usize, usize),
+    /// Indexed by x * height + y. The walk that added each cell and its place in the order cells were added, or None if no event added it.
+    cells: Vec<Option<(usize, usize)>>,
+    /// Walks recorded so far, counting the initial cell as walk 0
+    walks: usize,
+    /// Cells added so far
+    added: usize,
+}
+
+/// What `build_provenance_image` colors the cells by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProvenanceColoring {
+    /// A different hue for each walk, so the branches stand out
+    Walk,
+    /// A gradient from blue for the first cells added to red for the last
+    Order,
+}
+
+impl Provenance {
+    /// Records where every cell came from in the events of a `Maze::generation_trace` for a maze of the given size
+    pub fn from_events(size: (usize, usize), events: &[GenerationEvent]) -> Self {
+        let mut provenance = Provenance::new(size);
+        for event in events {
+            provenance.record(event);
+        }
+        provenance
+    }
+
+    /// The walk that added a cell, 0 for the initial cell and counting up from 1 for the walks after it
+    /// None if the cell is outside the maze or wasn't added by the events recorded.
+    pub fn walk_of(&self, cell: CellIndex) -> Option<usize> {
+        self.get(cell).map(|(walk, _)| walk)
+    }
+
+    /// How many cells were added before this one, or None if it's outside the maze or wasn't added by the events recorded
+    pub fn order_of(&self, cell: CellIndex) -> Option<usize> {
+        self.get(cell).map(|(_, order)| order)
+    }
+
+    /// Number of walks recorded, counting the initial cell as one
+    pub fn walk_count(&self) -> usize {
+        self.walks
+    }
+
+    /// Number of cells the events recorded added
+    pub fn cells_added(&self) -> usize {
+        self.added
+    }
+
+    /// Size of the maze the provenance covers, in cells
+    pub fn size(&self) -> (usize, usize) {
+        self.size
+    }
+
+    fn new(size: (usize, usize)) -> Self {
+        Provenance { size, cells: vec![None; size.0 * size.1], walks: 0, added: 0 }
+    }
+
+    fn get(&self, cell: CellIndex) -> Option<(usize, usize)> {
+        if cell.0 >= self.size.0 || cell.1 >= self.size.1 {
+            return None;
+        }
+        self.cells[cell.0 * self.size.1 + cell.1]
+    }
+
+    fn record(&mut self, event: &GenerationEvent) {
+        let cells: &[CellIndex] = match event {
+            GenerationEvent::InitialCell(cell) => core::slice::from_ref(cell),
+            // The last cell of a committed walk was already part of the maze
+            GenerationEvent::CellsCommitted(walk) => &walk[..walk.len().saturating_sub(1)],
+            _ => return,
+        };
+        // A walk's cells are all added at once, but they're ordered from the maze outwards, so every cell comes after the one it branches from
+        for &(x, y) in cells.iter().rev() {
+            if x < self.size.0 && y < self.size.1 {
+                self.cells[x * self.size.1 + y] = Some((self.walks, self.added));
+                self.added += 1;
+            }
+        }
+        self.walks += 1;
+    }
+}
+
+impl Maze {
+    /// Generates a maze the same way as `with_rng`, recording which walk added each cell and in what order
+    pub fn with_provenance<R: Rng>(width: usize, height: usize, rng: R) -> Result<(Maze, Provenance), MazeError> {
+        let mut trace = Maze::generation_trace(width, height, rng)?;
+        let mut provenance = Provenance::new((width, height));
+        for event in trace.by_ref() {
+            provenance.record(&event);
+        }
+
+        debug!("Recorded {} walks", provenance.walk_count());
+
+        Ok((trace.into_maze(), provenance))
+    }
+
+    /// Like `build_image`, but with each cell colored by the walk that added it or by when it was added
+    /// A passage takes the color of whichever of its cells was added later, since the walk that added that cell opened it.
+    /// Cells the provenance doesn't cover stay white. Fails with SizeMismatch if the provenance is for a maze of a different size.
+    #[cfg(feature = "image")]
+    pub fn build_provenance_image(&self, provenance: &Provenance, coloring: ProvenanceColoring) -> Result<RgbImage, MazeError> {
+        if provenance.size != self.size {
+            return Err(MazeError::SizeMismatch { expected: self.size, got: provenance.size });
+        }
+
+        let color = |(walk, order): (usize, usize)| match coloring {
+            // Stepping the hue by the golden angle keeps walks added one after another far apart in color
+            ProvenanceColoring::Walk => hue(walk * 137 % 360),
+            ProvenanceColoring::Order => {
+                let red = (order * 255 / provenance.added.saturating_sub(1).max(1)) as u8;
+                Rgb([red, 64, 255 - red])
+            },
+        };
+
+        let mut img = self.build_image();
+        for cell in self.path_cells() {
+            let source = match provenance.get(cell) {
+                Some(source) => source,
+                None => continue,
+            };
+            let (px, py) = (2 * cell.0 as u32 + 1, 2 * cell.1 as u32 + 1);
+            img.put_pixel(px, py, color(source));
+            for &(direction, (wx, wy)) in &[(Direction::Right, (px + 1, py)), (Direction::Down, (px, py + 1))] {
+                if !self.can_move(cell, direction) {
+                    continue;
+                }
+                let later = match self.step(cell, direction).and_then(|next| provenance.get(next)) {
+                    Some(next) => if next.1 > source.1 { next } else { source },
+                    None => source,
+                };
+                img.put_pixel(wx, wy, color(later));
+            }
+        }
+
+        Ok(img)
+    }
+}
+
+/// A bright, slightly pale color of the given hue in degrees
+#[cfg(feature = "image")]
+fn hue(degrees: usize) -> Rgb<u8> {
+    const LOW: usize = 90;
+    const HIGH: usize = 240;
+    let rising = (LOW + (HIGH - LOW) * (degrees % 60) / 60) as u8;
+    let falling = (HIGH - (HIGH - LOW) * (degrees % 60) / 60) as u8;
+    let (low, high) = (LOW as u8, HIGH as u8);
+    match degrees / 60 {
+        0 => Rgb([high, rising, low]),
+        1 => Rgb([falling, high, low]),
+        2 => Rgb([low, high, rising]),
+        3 => Rgb([low, falling, high]),
+        4 => Rgb([rising, low, high]),
+        _ => Rgb([high, low, falling]),
+    }
+}
@@ -0,0 +1,123 @@
+use alloc::{format, vec::Vec};
+#[cfg(feature = "std")]
+use std::{fs, io::{self, BufWriter, Write}, path::Path};
+
+use super::{Maze, MazeError};
+use super::format::Reader;
+use super::packed::{PackedCells, CELL_BITS, CELLS_PER_WORD};
+
+/// Every raw cell grid starts with these bytes
+const RAW_MAGIC: &[u8; 4] = b"MZRW";
+const RAW_VERSION: u16 = 1;
+/// Size of the header, which keeps the words after it 8 byte aligned
+const RAW_HEADER_LEN: usize = 32;
+
+impl Maze {
+    /// The cell grid exactly as it's stored in memory, behind a 32 byte header, for memory mapping from other tools or later runs
+    /// All numbers are little endian. The header is:
+    /// - the magic bytes `MZRW`
+    /// - the layout version, currently 1, as a u16
+    /// - bits per cell (5) and cells per word (12), as a u8 each
+    /// - width and height in cells, as u64s
+    /// - the number of words that follow, as a u64
+    ///
+    /// Then come the u64 words, with the cells row by row and cell `i` in bits `5 * (i % 12)` and up of word `i / 12`.
+    /// Each cell holds its open sides in the low four bits (up, right, down, left) and whether it's carved in the fifth.
+    /// Only the cells are kept: entrances, labeled openings, portals, one-way passages and tags need `to_bytes`.
+    /// With the `z-order` feature the cells are stored differently in memory, so they're rearranged into rows on the way out and in.
+    pub fn to_raw(&self) -> Vec<u8> {
+        let rows = self.cells.row_major(self.size);
+        let mut bytes = Vec::with_capacity(RAW_HEADER_LEN + rows.words().len() * 8);
+        bytes.extend_from_slice(&raw_header(self.size, &rows));
+        for word in rows.words() {
+            bytes.extend_from_slice(&word.to_le_bytes());
+        }
+        #[cfg(feature = "memory-stats")]
+        super::memory::record_export(bytes.len());
+        bytes
+    }
+
+    /// Loads a cell grid written by `to_raw`, e.g. straight from a memory mapped file
+    /// Fails with InvalidFormat if the data is truncated or isn't a valid maze, and UnsupportedVersion for a newer layout.
+    pub fn from_raw(bytes: &[u8]) -> Result<Maze, MazeError> {
+        let mut reader = Reader { bytes };
+
+        if reader.take(RAW_MAGIC.len(), "header")? != RAW_MAGIC {
+            return Err(MazeError::InvalidFormat(format!("data doesn't start with {:?}", RAW_MAGIC)));
+        }
+        let version = reader.u16("version")?;
+        if version != RAW_VERSION {
+            return Err(MazeError::UnsupportedVersion { version });
+        }
+        if (reader.u8("bits per cell")? as usize, reader.u8("cells per word")? as usize) != (CELL_BITS, CELLS_PER_WORD) {
+            return Err(MazeError::InvalidFormat("cells aren't packed 12 to a word with 5 bits each".into()));
+        }
+
+        let width = reader.u64("width")? as usize;
+        let height = reader.u64("height")? as usize;
+        if width == 0 || height == 0 {
+            return Err(MazeError::InvalidDimensions { got: (width, height) });
+        }
+        let word_count = reader.u64("word count")? as usize;
+        let cell_count = width.checked_mul(height)
+            .filter(|cells| cells.div_ceil(CELLS_PER_WORD) == word_count)
+            .ok_or_else(|| MazeError::InvalidFormat(format!("{} words can't hold a {}x{} maze", word_count, width, height)))?;
+        // Taken before allocating, so a corrupt header can't ask for more words than there is data
+        let data = reader.take(word_count.saturating_mul(8), "cells")?;
+        if !reader.bytes.is_empty() {
+            return Err(MazeError::InvalidFormat(format!("{} unexpected bytes after the cells", reader.bytes.len())));
+        }
+
+        let words = data.chunks_exact(8).map(|chunk| {
+            let mut word = [0; 8];
+            word.copy_from_slice(chunk);
+            u64::from_le_bytes(word)
+        }).collect();
+        let rows = PackedCells::from_words(words, cell_count)
+            .ok_or_else(|| MazeError::InvalidFormat("unused bits are set".into()))?;
+        let maze = Maze::from_cells((width, height), PackedCells::from_row_major(rows, (width, height)));
+        maze.check_loaded()?;
+
+        Ok(maze)
+    }
+
+    /// Writes `to_raw`'s layout to a writer, without building it in memory first
+    #[cfg(feature = "std")]
+    pub fn write_raw<W: Write>(&self, writer: W) -> io::Result<()> {
+        #[cfg(feature = "memory-stats")]
+        let writer = super::memory::CountingWriter::new(writer);
+        let mut writer = writer;
+        let rows = self.cells.row_major(self.size);
+        writer.write_all(&raw_header(self.size, &rows))?;
+        for word in rows.words() {
+            writer.write_all(&word.to_le_bytes())?;
+        }
+        writer.flush()
+    }
+
+    /// Saves the cell grid to a file with `write_raw`
+    #[cfg(feature = "std")]
+    pub fn save_raw<P: AsRef<Path>>(&self, path: P) -> Result<(), MazeError> {
+        self.write_raw(BufWriter::new(fs::File::create(path)?))?;
+        Ok(())
+    }
+
+    /// Loads a cell grid saved with `save_raw`
+    #[cfg(feature = "std")]
+    pub fn load_raw<P: AsRef<Path>>(path: P) -> Result<Maze, MazeError> {
+        Maze::from_raw(&fs::read(path)?)
+    }
+}
+
+/// The header for a maze of the given size with the given cells in row order
+fn raw_header(size: (usize, usize), rows: &PackedCells) -> [u8; RAW_HEADER_LEN] {
+    let mut header = [0; RAW_HEADER_LEN];
+    header[..4].copy_from_slice(RAW_MAGIC);
+    header[4..6].copy_from_slice(&RAW_VERSION.to_le_bytes());
+    header[6] = CELL_BITS as u8;
+    header[7] = CELLS_PER_WORD as u8;
+    header[8..16].copy_from_slice(&(size.0 as u64).to_le_bytes());
+    header[16..24].copy_from_slice(&(size.1 as u64).to_le_bytes());
+    header[24..32].copy_from_slice(&(rows.words().len() as u64).to_le_bytes());
+    header
+}
@@ -0,0 +1,163 @@
+use alloc::{collections::VecDeque, format, vec, vec::Vec};
+
+#[cfg(feature = "image")]
+use image::{Rgb, RgbImage};
+use rand::{Rng, seq::SliceRandom};
+
+#[cfg(feature = "image")]
+use super::Direction;
+use super::{Maze, MazeError, CellIndex};
+
+/// Connected groups of path cells, as found by `Maze::regions`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Regions {
+    /// Indexed as labels[x][y]. The id of the region each path cell belongs to, or None for walls.
+    pub labels: Vec<Vec<Option<usize>>>,
+    /// Number of cells in each region, indexed by region id
+    pub sizes: Vec<usize>,
+}
+
+impl Regions {
+    /// The region the given cell belongs to, or None if it's a wall or outside the maze
+    pub fn region_of(&self, cell: CellIndex) -> Option<usize> {
+        self.labels.get(cell.0).and_then(|col| col.get(cell.1)).copied().flatten()
+    }
+
+    /// Number of separate regions. A properly connected maze has exactly one.
+    pub fn count(&self) -> usize {
+        self.sizes.len()
+    }
+
+    /// Size of the maze the labels cover, in cells
+    fn size(&self) -> (usize, usize) {
+        (self.labels.len(), self.labels.first().map_or(0, Vec::len))
+    }
+}
+
+impl Maze {
+    /// Flood fills the path cells to label each connected region
+    /// Region ids are handed out in the order regions are found, scanning column by column.
+    /// Portals join the regions at their ends, and one-way passages count as joining their cells either way.
+    pub fn regions(&self) -> Regions {
+        info!("Labeling connected regions");
+
+        let mut labels: Vec<Vec<Option<usize>>> = vec![vec![None; self.size.1]; self.size.0];
+        let mut sizes = Vec::new();
+        let mut frontier = VecDeque::new();
+
+        for x in 0..self.size.0 {
+            for y in 0..self.size.1 {
+                if !self.is_path((x, y)) || labels[x][y].is_some() {
+                    continue;
+                }
+
+                let region = sizes.len();
+                let mut size: usize = 1;
+                labels[x][y] = Some(region);
+                frontier.push_back((x, y));
+
+                while let Some(current) = frontier.pop_front() {
+                    for neighbor in self.linked_cells(current) {
+                        if labels[neighbor.0][neighbor.1].is_none() {
+                            labels[neighbor.0][neighbor.1] = Some(region);
+                            size += 1;
+                            frontier.push_back(neighbor);
+                        }
+                    }
+                }
+
+                sizes.push(size);
+            }
+        }
+
+        info!("Found {} regions", sizes.len());
+
+        Regions { labels, sizes }
+    }
+
+    /// Splits the path cells into `zones` contiguous zones, for giving parts of a big maze their own theme
+    /// Each zone grows out from a random path cell, one step at a time in turn, so every cell joins the zone whose start is the fewest steps away.
+    /// Zones are returned as `Regions`, numbered in the order their starts were picked. Cells that can't be reached from any start, in a maze
+    /// that isn't connected, are left out. Fails with OutOfRange if `zones` is 0 or there are more zones than path cells.
+    pub fn partition<R: Rng + ?Sized>(&self, zones: usize, rng: &mut R) -> Result<Regions, MazeError> {
+        let mut starts: Vec<CellIndex> = self.path_cells().collect();
+        if zones == 0 || zones > starts.len() {
+            return Err(MazeError::OutOfRange { setting: "zones", got: zones as f64, min: 1.0, max: starts.len() as f64 });
+        }
+
+        info!("Partitioning the maze into {} zones", zones);
+
+        let (starts, _) = starts.partial_shuffle(rng, zones);
+        let mut labels: Vec<Vec<Option<usize>>> = vec![vec![None; self.size.1]; self.size.0];
+        let mut sizes = vec![1; zones];
+        let mut frontier: VecDeque<CellIndex> = starts.iter().copied().collect();
+        for (zone, &(x, y)) in starts.iter().enumerate() {
+            labels[x][y] = Some(zone);
+        }
+
+        // Every start is queued before any of its neighbors, so the zones all grow one step at a time
+        while let Some(current) = frontier.pop_front() {
+            let zone = labels[current.0][current.1].unwrap(); // Safe to unwrap since cells get a zone before being queued
+            for neighbor in self.linked_cells(current) {
+                if labels[neighbor.0][neighbor.1].is_none() {
+                    labels[neighbor.0][neighbor.1] = Some(zone);
+                    sizes[zone] += 1;
+                    frontier.push_back(neighbor);
+                }
+            }
+        }
+
+        debug!("Zone sizes: {:?}", sizes);
+
+        Ok(Regions { labels, sizes })
+    }
+
+    /// Tags every labeled cell with `prefix` followed by its region id, e.g. "zone0", so the zones from `partition` are saved with the maze
+    /// Fails with SizeMismatch if the regions are for a maze of a different size.
+    pub fn tag_regions(&mut self, regions: &Regions, prefix: &str) -> Result<(), MazeError> {
+        if regions.size() != self.size {
+            return Err(MazeError::SizeMismatch { expected: self.size, got: regions.size() });
+        }
+
+        for (x, column) in regions.labels.iter().enumerate() {
+            for (y, region) in column.iter().enumerate() {
+                if let Some(region) = region {
+                    self.add_tag((x, y), format!("{}{}", prefix, region))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Like `build_image`, but with the paths of each region tinted its own color, like `build_terrain_image`
+    /// Colors cycle through six pale tints in region order. Fails with SizeMismatch if the regions are for a maze of a different size.
+    #[cfg(feature = "image")]
+    pub fn build_regions_image(&self, regions: &Regions) -> Result<RgbImage, MazeError> {
+        const TINTS: [Rgb<u8>; 6] = [
+            Rgb([255, 200, 200]), Rgb([200, 255, 200]), Rgb([200, 200, 255]),
+            Rgb([255, 240, 190]), Rgb([230, 200, 255]), Rgb([190, 245, 245]),
+        ];
+
+        if regions.size() != self.size {
+            return Err(MazeError::SizeMismatch { expected: self.size, got: regions.size() });
+        }
+
+        let mut img = self.build_image();
+        for cell in self.path_cells() {
+            let color = match regions.region_of(cell) {
+                Some(region) => TINTS[region % TINTS.len()],
+                None => continue,
+            };
+            let (px, py) = (2 * cell.0 as u32 + 1, 2 * cell.1 as u32 + 1);
+            img.put_pixel(px, py, color);
+            // Passages inside a region take its tint, and ones between regions stay white
+            for &(direction, (wx, wy)) in &[(Direction::Right, (px + 1, py)), (Direction::Down, (px, py + 1))] {
+                if self.can_move(cell, direction) && self.step(cell, direction).and_then(|next| regions.region_of(next)) == regions.region_of(cell) {
+                    img.put_pixel(wx, wy, color);
+                }
+            }
+        }
+
+        Ok(img)
+    }
+}
@@ -0,0 +1,63 @@
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+
+use super::{Maze, MazeError, Generator, WalkStats};
+
+/// Where the time and steps went while making one maze, see `Generator::generate_with_report`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct GenerationReport {
+    /// The random walks taken for this maze alone
+    pub walks: WalkStats,
+    /// Time spent generating, from the empty grid to the finished maze
+    pub generation_time: Duration,
+    /// Time spent in `time_render`, if it's been called
+    pub render_time: Option<Duration>,
+}
+
+impl GenerationReport {
+    /// Runs `render`, adding the time it takes to the report, and hands back what it rendered
+    /// Works with any renderer, e.g. `report.time_render(|| maze.build_image())`. Calling it more than once adds up the times.
+    pub fn time_render<T, F: FnOnce() -> T>(&mut self, render: F) -> T {
+        let start = Instant::now();
+        let rendered = render();
+        self.render_time = Some(self.render_time.unwrap_or_default() + start.elapsed());
+        rendered
+    }
+}
+
+impl WalkStats {
+    /// Both sets of counters together, as though they'd been counted in one go
+    fn combined(self, other: WalkStats) -> WalkStats {
+        WalkStats {
+            walks: self.walks + other.walks,
+            steps: self.steps + other.steps,
+            restarts: self.restarts + other.restarts,
+            longest_walk: self.longest_walk.max(other.longest_walk),
+            cells_erased: self.cells_erased + other.cells_erased,
+            cells_committed: self.cells_committed + other.cells_committed,
+            starts_checked: self.starts_checked + other.starts_checked,
+        }
+    }
+}
+
+impl<R: Rng> Generator<R> {
+    /// Generates a maze like `generate`, along with a report of how long it took and what its walks did, for comparing algorithms and settings
+    /// The generator's own `walk_stats` still count this maze too.
+    pub fn generate_with_report(&mut self, width: usize, height: usize) -> Result<(Maze, GenerationReport), MazeError> {
+        let earlier = self.walk_stats();
+        self.reset_walk_stats();
+
+        let start = Instant::now();
+        let result = self.generate(width, height);
+        let generation_time = start.elapsed();
+
+        let walks = self.walk_stats();
+        self.stats = earlier.combined(walks);
+
+        let report = GenerationReport { walks, generation_time, render_time: None };
+        info!("Generation report: {:?}", report);
+
+        Ok((result?, report))
+    }
+}
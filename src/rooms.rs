@@ -0,0 +1,79 @@
+use alloc::vec::Vec;
+
+use rand::Rng;
+
+use super::{Maze, MazeError, CellIndex, Direction};
+
+impl Maze {
+    /// Opens every wall between the cells from (x0, y0) up to but not including (x1, y1), leaving an open room, e.g. for an arena in a game
+    /// Walls are only ever removed, so a connected maze stays connected, with the room reached by every passage that already led into it.
+    /// One-way passages inside the room become two-way. Returns how many passages were opened. Fails with InvalidRegion like `crop`.
+    /// The room has loops and open 2x2 blocks, so the maze no longer passes `validate` or `validate_braided`.
+    pub fn carve_room(&mut self, x0: usize, y0: usize, x1: usize, y1: usize) -> Result<usize, MazeError> {
+        if x0 >= x1 || y0 >= y1 || x1 > self.size.0 || y1 > self.size.1 {
+            return Err(MazeError::InvalidRegion { from: (x0, y0), to: (x1, y1) });
+        }
+
+        info!("Carving a room from ({}, {}) to ({}, {})", x0, y0, x1, y1);
+
+        let mut opened = 0;
+        for x in x0..x1 {
+            for y in y0..y1 {
+                self.update_cell((x, y), |cell| cell.carve());
+                // Each passage inside the room once, from the cell on its left or above it
+                let neighbors = [(Direction::Right, (x + 1 < x1).then_some((x + 1, y))), (Direction::Down, (y + 1 < y1).then_some((x, y + 1)))];
+                for &(direction, neighbor) in &neighbors {
+                    if let Some(neighbor) = neighbor {
+                        if !self.can_move((x, y), direction) {
+                            opened += 1;
+                        }
+                        self.open_passage((x, y), neighbor);
+                        self.make_two_way((x, y), neighbor);
+                    }
+                }
+            }
+        }
+
+        debug!("Opened {} passages for the room", opened);
+
+        Ok(opened)
+    }
+
+    /// Carves up to `count` rooms with `carve_room`, each between `min_size` and `max_size` cells along each side, at random places where
+    /// they don't touch each other. Gives up on a room after a few tries if there's no space left for it, so fewer may be carved.
+    /// Returns the (from, to) corners of every room carved, `to` exclusive. Fails with OutOfRange if `min_size` is 0 or more than `max_size`.
+    pub fn carve_rooms<R: Rng + ?Sized>(&mut self, count: usize, min_size: usize, max_size: usize, rng: &mut R) -> Result<Vec<(CellIndex, CellIndex)>, MazeError> {
+        if min_size == 0 || min_size > max_size {
+            return Err(MazeError::OutOfRange { setting: "min_size", got: min_size as f64, min: 1.0, max: max_size as f64 });
+        }
+
+        info!("Carving {} rooms of {} to {} cells across", count, min_size, max_size);
+
+        const TRIES_PER_ROOM: usize = 20;
+        let mut rooms: Vec<(CellIndex, CellIndex)> = Vec::with_capacity(count);
+        for _ in 0..count {
+            for _ in 0..TRIES_PER_ROOM {
+                let width = rng.gen_range(min_size, max_size + 1);
+                let height = rng.gen_range(min_size, max_size + 1);
+                if width > self.size.0 || height > self.size.1 {
+                    continue;
+                }
+                let x0 = rng.gen_range(0, self.size.0 - width + 1);
+                let y0 = rng.gen_range(0, self.size.1 - height + 1);
+                let room = ((x0, y0), (x0 + width, y0 + height));
+
+                // At least one cell of corridor between any two rooms, so they don't merge into one
+                let apart = |&(from, to): &(CellIndex, CellIndex)| {
+                    room.1.0 < from.0 || to.0 < room.0.0 || room.1.1 < from.1 || to.1 < room.0.1
+                };
+                if rooms.iter().all(apart) {
+                    self.carve_room(x0, y0, x0 + width, y0 + height)?;
+                    rooms.push(room);
+                    break;
+                }
+            }
+        }
+
+        Ok(rooms)
+    }
+}
@@ -0,0 +1,99 @@
+use alloc::{format, vec::Vec};
+
+use super::{Maze, MazeError, Cell, CellType, Direction, Opening, PackedCells, grid_offset};
+
+impl Maze {
+    /// The maze as a grid of wall and path tiles, indexed as tiles[x][y], with every cell and every wall blown up to an n x n block
+    /// With a factor of 1 this is the same layout as `build_image`. Useful as a tile map for games, where corridors need to be wider than one tile.
+    pub fn scale(&self, factor: usize) -> Result<Vec<Vec<CellType>>, MazeError> {
+        if factor == 0 {
+            return Err(MazeError::InvalidScale);
+        }
+
+        let (width, height) = self.pixel_size();
+        info!("Scaling maze by {} to {}x{} tiles", factor, width * factor, height * factor);
+
+        let tiles = self.to_pixels().iter()
+            .flat_map(|col| {
+                let scaled_col: Vec<CellType> = col.iter().flat_map(|&tile| core::iter::repeat_n(tile, factor)).collect();
+                core::iter::repeat_n(scaled_col, factor)
+            })
+            .collect();
+
+        Ok(tiles)
+    }
+
+    /// Reads a maze back from a grid of tiles laid out like `scale(1)`, indexed as tiles[x][y]
+    /// This is the inverse of `scale(1)`: the maze itself only stores which sides of each cell are open, a quarter of the tiles,
+    /// so converting to tiles and back is for tools that edit the tile grid. Fails with InvalidImage like reading an image does.
+    pub fn from_tiles(tiles: &[Vec<CellType>]) -> Result<Maze, MazeError> {
+        let (width, height) = (tiles.len(), tiles.first().map_or(0, Vec::len));
+        if let Some(x) = tiles.iter().position(|col| col.len() != height) {
+            return Err(MazeError::InvalidImage(format!("column {} is {} tiles tall, but column 0 is {}", x, tiles[x].len(), height)));
+        }
+
+        info!("Reading maze from {}x{} tiles", width, height);
+
+        Maze::from_path_pixels((width, height), |x, y| !tiles[x][y].is_wall())
+    }
+
+    /// Builds a maze from a grid of pixels laid out like `build_image`, telling paths from walls with `path_at(x, y)`
+    /// Cell (x, y) is read from pixel (2x + 1, 2y + 1) and the passages from the pixels next to it. If exactly two openings
+    /// are found in the outer wall they become the maze's entrances.
+    pub(super) fn from_path_pixels<F: Fn(usize, usize) -> bool>(pixel_size: (usize, usize), path_at: F) -> Result<Maze, MazeError> {
+        let (pixel_width, pixel_height) = pixel_size;
+        if pixel_width < 3 || pixel_height < 3 || pixel_width.is_multiple_of(2) || pixel_height.is_multiple_of(2) {
+            return Err(MazeError::InvalidImage(format!("mazes are at least 3x3 pixels with odd sides, got {}x{}", pixel_width, pixel_height)));
+        }
+
+        let size = ((pixel_width - 1) / 2, (pixel_height - 1) / 2);
+
+        // Corners between cells have nothing to be stored in, so they must be walls
+        for x in (0..pixel_width).step_by(2) {
+            for y in (0..pixel_height).step_by(2) {
+                if path_at(x, y) {
+                    return Err(MazeError::InvalidImage(format!("pixel ({}, {}) sits between cells and must be a wall", x, y)));
+                }
+            }
+        }
+
+        let mut cells = PackedCells::grid(size);
+        let mut openings = Vec::new();
+        for y in 0..size.1 {
+            for x in 0..size.0 {
+                let mut cell = Cell::default();
+                let (px, py) = (x * 2 + 1, y * 2 + 1);
+                let sides = [
+                    (Direction::Up, px, py - 1, y == 0),
+                    (Direction::Right, px + 1, py, x + 1 == size.0),
+                    (Direction::Down, px, py + 1, y + 1 == size.1),
+                    (Direction::Left, px - 1, py, x == 0),
+                ];
+                let open_sides: Vec<_> = sides.iter().filter(|&&(_, wx, wy, _)| path_at(wx, wy)).collect();
+
+                if !path_at(px, py) {
+                    if !open_sides.is_empty() {
+                        return Err(MazeError::InvalidImage(format!("pixel ({}, {}) is a passage into a wall", open_sides[0].1, open_sides[0].2)));
+                    }
+                    continue;
+                }
+
+                cell.carve();
+                for &&(direction, _, _, on_border) in &open_sides {
+                    cell.open(direction);
+                    if on_border {
+                        openings.push(Opening { cell: (x, y), side: direction });
+                    }
+                }
+                cells.set(grid_offset(size, (x, y)), cell);
+            }
+        }
+
+        let entrances = match openings[..] {
+            [entrance, exit] => Some((entrance, exit)),
+            _ => None,
+        };
+
+        Ok(Maze { entrances, ..Maze::from_cells(size, cells) })
+    }
+}
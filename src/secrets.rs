@@ -0,0 +1,65 @@
+use alloc::vec::Vec;
+
+#[cfg(feature = "image")]
+use image::{Rgb, RgbImage};
+
+use super::{Maze, MazeError, CellIndex};
+
+/// Secret doors hidden in walls, kept alongside a maze like a `KeyLayer`
+/// Each door joins two neighboring cells through the wall between them. The maze itself still has the wall, so `build_image` and every
+/// other export show it as one. That's the view for players, and `build_secret_image` and `with_secrets_opened` are the view for whoever runs the game.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct SecretLayer {
+    /// (one side, other side) for every door
+    doors: Vec<(CellIndex, CellIndex)>,
+}
+
+impl SecretLayer {
+    /// A layer with no secret doors
+    pub fn new() -> Self {
+        SecretLayer::default()
+    }
+
+    /// Hides a door in the wall between two neighboring cells
+    pub fn add_door(&mut self, from: CellIndex, to: CellIndex) {
+        self.doors.push((from, to));
+    }
+
+    /// Every secret door, as (one side, other side)
+    pub fn doors(&self) -> &[(CellIndex, CellIndex)] {
+        &self.doors
+    }
+}
+
+impl Maze {
+    /// A copy of the maze with every secret door carved into a real passage, for exports and solvers that should know about them
+    /// Fails with NotAdjacent for a door between cells that aren't neighbors.
+    pub fn with_secrets_opened(&self, layer: &SecretLayer) -> Result<Maze, MazeError> {
+        let mut opened = self.clone();
+        for &(from, to) in &layer.doors {
+            opened.carve(from, to)?;
+        }
+        Ok(opened)
+    }
+
+    /// Finds the shortest path from start to goal like `solve`, also going through secret doors
+    /// Returns None if either cell is a wall or no path exists. Fails with NotAdjacent for a door between cells that aren't neighbors.
+    pub fn solve_with_secrets(&self, layer: &SecretLayer, start: CellIndex, goal: CellIndex) -> Result<Option<Vec<CellIndex>>, MazeError> {
+        info!("Solving maze with {} secret doors", layer.doors.len());
+        Ok(self.with_secrets_opened(layer)?.solve(start, goal))
+    }
+
+    /// Like `build_image`, with every secret door drawn purple where the player view has a wall
+    /// Fails with NotAdjacent for a door between cells that aren't neighbors.
+    #[cfg(feature = "image")]
+    pub fn build_secret_image(&self, layer: &SecretLayer) -> Result<RgbImage, MazeError> {
+        let mut img = self.build_image();
+        for &(from, to) in &layer.doors {
+            let direction = self.passage_direction(from, to)?;
+            if !self.can_move(from, direction) {
+                img.put_pixel((from.0 + to.0 + 1) as u32, (from.1 + to.1 + 1) as u32, Rgb([160, 0, 200]));
+            }
+        }
+        Ok(img)
+    }
+}
@@ -0,0 +1,116 @@
+use alloc::{vec, vec::Vec};
+
+use std::io::Write;
+
+use image::{Delay, DynamicImage, Frame, Rgb, RgbImage};
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::imageops::FilterType;
+
+use super::{Maze, MazeError, CellIndex, SolveEvent, TracedSolver};
+
+const VISITED_COLOR: Rgb<u8> = Rgb([170, 200, 255]);
+const FRONTIER_COLOR: Rgb<u8> = Rgb([255, 170, 0]);
+const PATH_COLOR: Rgb<u8> = Rgb([230, 40, 40]);
+
+/// How `solve_animation_frames` and `write_solve_gif` animate a solver, for teaching and demos
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SolveAnimation {
+    pub solver: TracedSolver,
+    /// Pixels across every cell and wall
+    pub scale: usize,
+    /// Most frames of exploration, each covering an equal share of the solver's steps. One more frame at the end shows the path.
+    pub frames: usize,
+    /// How long each frame of a GIF shows, in milliseconds. The last frame shows for ten times as long.
+    pub frame_delay_ms: u32,
+}
+
+/// Breadth first search at 4 pixels a cell over 60 frames, 50 ms each
+impl Default for SolveAnimation {
+    fn default() -> Self {
+        SolveAnimation { solver: TracedSolver::BreadthFirst, scale: 4, frames: 60, frame_delay_ms: 50 }
+    }
+}
+
+impl Maze {
+    /// Renders the solver's exploration from start to goal as a sequence of frames: cells it has been through light blue,
+    /// the breadth first frontier or the walker's current cell orange, and in the last frame the route it found in red
+    /// The last frame is the plain exploration if there's no route. Fails with InvalidScale for a scale of 0, or OutOfRange for no frames.
+    pub fn solve_animation_frames(&self, animation: &SolveAnimation, start: CellIndex, goal: CellIndex) -> Result<Vec<RgbImage>, MazeError> {
+        if animation.scale == 0 {
+            return Err(MazeError::InvalidScale);
+        }
+        if animation.frames == 0 {
+            return Err(MazeError::OutOfRange { setting: "frames", got: 0.0, min: 1.0, max: f64::INFINITY });
+        }
+
+        let events = self.solver_events(animation.solver, start, goal);
+        let steps = events.iter().filter(|event| matches!(event, SolveEvent::Visited(_))).count();
+        let steps_per_frame = steps.div_ceil(animation.frames).max(1);
+        info!("Animating {:?} over {} steps in up to {} frames", animation.solver, steps, animation.frames + 1);
+
+        // Drawn at one pixel a cell and scaled up for each frame
+        let mut img = self.build_image();
+        let (width, height) = img.dimensions();
+        let scaled = |img: &RgbImage| image::imageops::resize(img, width * animation.scale as u32, height * animation.scale as u32, FilterType::Nearest);
+        let paint = |img: &mut RgbImage, cell: CellIndex, color: Rgb<u8>| img.put_pixel(2 * cell.0 as u32 + 1, 2 * cell.1 as u32 + 1, color);
+
+        let mut frames = Vec::with_capacity(animation.frames + 1);
+        let mut visited = vec![vec![false; self.size.1]; self.size.0];
+        let mut current: Option<CellIndex> = None;
+        let mut taken = 0;
+        for event in events {
+            match event {
+                SolveEvent::Visited(cell) => {
+                    // The walkers' last cell goes back to visited once they move on
+                    if let Some(previous) = current.replace(cell) {
+                        paint(&mut img, previous, VISITED_COLOR);
+                    }
+                    visited[cell.0][cell.1] = true;
+                    paint(&mut img, cell, FRONTIER_COLOR);
+                    for neighbor in self.linked_cells(cell).into_iter().filter(|&neighbor| visited[neighbor.0][neighbor.1]) {
+                        img.put_pixel((cell.0 + neighbor.0 + 1) as u32, (cell.1 + neighbor.1 + 1) as u32, VISITED_COLOR);
+                    }
+                    taken += 1;
+                    if taken % steps_per_frame == 0 {
+                        frames.push(scaled(&img));
+                    }
+                },
+                SolveEvent::FrontierAdded(cell) => paint(&mut img, cell, FRONTIER_COLOR),
+                SolveEvent::PathFound(path) => {
+                    for &cell in &path {
+                        paint(&mut img, cell, PATH_COLOR);
+                    }
+                    for pair in path.windows(2) {
+                        img.put_pixel((pair[0].0 + pair[1].0 + 1) as u32, (pair[0].1 + pair[1].1 + 1) as u32, PATH_COLOR);
+                    }
+                    current = None;
+                },
+                SolveEvent::NoPath => {},
+            }
+        }
+        if let Some(last) = current {
+            paint(&mut img, last, VISITED_COLOR);
+        }
+        frames.push(scaled(&img));
+
+        Ok(frames)
+    }
+
+    /// Writes the frames of `solve_animation_frames` as a looping GIF
+    /// GIF encoding picks a palette for every frame, which is slow for big images, so keep the maze and scale modest.
+    pub fn write_solve_gif<W: Write>(&self, writer: W, animation: &SolveAnimation, start: CellIndex, goal: CellIndex) -> Result<(), MazeError> {
+        let frames = self.solve_animation_frames(animation, start, goal)?;
+        let last = frames.len() - 1;
+
+        let mut encoder = GifEncoder::new(writer);
+        encoder.set_repeat(Repeat::Infinite)?;
+        encoder.encode_frames(frames.into_iter().enumerate().map(|(i, img)| {
+            let delay = if i == last { animation.frame_delay_ms * 10 } else { animation.frame_delay_ms };
+            Frame::from_parts(DynamicImage::ImageRgb8(img).into_rgba8(), 0, 0, Delay::from_numer_denom_ms(delay, 1))
+        }))?;
+
+        info!("Solver animation written");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,214 @@
+use alloc::{collections::VecDeque, vec, vec::Vec};
+
+use super::{Maze, CellIndex, Hand, Heuristic, Manhattan, Movement};
+
+/// A strategy for finding a path through a maze
+/// The built in solvers all implement this, so code that runs or compares solvers can accept any of them, or a custom one.
+/// Closures taking (maze, start, goal) work as solvers too.
+pub trait Solver {
+    /// Finds a path from start to goal, including both ends, or None if there isn't one
+    fn solve(&self, maze: &Maze, start: CellIndex, goal: CellIndex) -> Option<Vec<CellIndex>>;
+}
+
+impl<F> Solver for F where F: Fn(&Maze, CellIndex, CellIndex) -> Option<Vec<CellIndex>> {
+    fn solve(&self, maze: &Maze, start: CellIndex, goal: CellIndex) -> Option<Vec<CellIndex>> {
+        self(maze, start, goal)
+    }
+}
+
+/// Shortest path by breadth first search, see `Maze::solve`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BreadthFirstSolver;
+
+impl Solver for BreadthFirstSolver {
+    fn solve(&self, maze: &Maze, start: CellIndex, goal: CellIndex) -> Option<Vec<CellIndex>> {
+        maze.solve(start, goal)
+    }
+}
+
+/// Shortest path by A*, see `Maze::solve_astar_with_movement`
+#[derive(Debug, Clone, Copy)]
+pub struct AStarSolver<H: Heuristic = Manhattan> {
+    /// Guides the search towards the goal
+    pub heuristic: H,
+    /// Whether diagonal steps are allowed
+    pub movement: Movement,
+}
+
+impl Default for AStarSolver<Manhattan> {
+    /// Manhattan distance with orthogonal moves, the right choice for generated mazes
+    fn default() -> Self {
+        AStarSolver { heuristic: Manhattan, movement: Movement::Orthogonal }
+    }
+}
+
+impl<H: Heuristic> Solver for AStarSolver<H> {
+    fn solve(&self, maze: &Maze, start: CellIndex, goal: CellIndex) -> Option<Vec<CellIndex>> {
+        maze.solve_astar_with_movement(start, goal, &self.heuristic, self.movement)
+    }
+}
+
+/// The full traversal of a wall follower, see `Maze::solve_wall_follower`
+#[derive(Debug, Clone, Copy)]
+pub struct WallFollowerSolver(pub Hand);
+
+impl Solver for WallFollowerSolver {
+    fn solve(&self, maze: &Maze, start: CellIndex, goal: CellIndex) -> Option<Vec<CellIndex>> {
+        maze.solve_wall_follower(start, goal, self.0)
+    }
+}
+
+/// The corridor left after dead end filling, see `Maze::solve_dead_end_filling`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DeadEndFillingSolver;
+
+impl Solver for DeadEndFillingSolver {
+    fn solve(&self, maze: &Maze, start: CellIndex, goal: CellIndex) -> Option<Vec<CellIndex>> {
+        maze.solve_dead_end_filling(start, goal).map(|fill| fill.path)
+    }
+}
+
+#[cfg(feature = "std")]
+/// The route found by Trémaux's algorithm, see `Maze::solve_tremaux`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TremauxSolver;
+
+#[cfg(feature = "std")]
+impl Solver for TremauxSolver {
+    fn solve(&self, maze: &Maze, start: CellIndex, goal: CellIndex) -> Option<Vec<CellIndex>> {
+        maze.solve_tremaux(start, goal).map(|tremaux| tremaux.path)
+    }
+}
+
+impl Maze {
+    /// Solves the maze with any solver
+    pub fn solve_with<S: Solver + ?Sized>(&self, solver: &S, start: CellIndex, goal: CellIndex) -> Option<Vec<CellIndex>> {
+        solver.solve(self, start, goal)
+    }
+
+    /// Finds the shortest path between two cells using a breadth first search over the path cells
+    /// The returned path includes both the start and the goal. Returns None if either cell is a wall or no path exists.
+    pub fn solve(&self, start: CellIndex, goal: CellIndex) -> Option<Vec<CellIndex>> {
+        info!("Solving maze from ({}, {}) to ({}, {})", start.0, start.1, goal.0, goal.1);
+
+        if !self.is_path(start) || !self.is_path(goal) {
+            return None;
+        }
+
+        let path = self.breadth_first_search(start, goal, |_, _| false);
+        match &path {
+            Some(path) => info!("Solution found with length {}", path.len()),
+            None => info!("No solution exists"),
+        }
+
+        path
+    }
+
+    /// Breadth first search over the path cells, never taking a step (from, to) that `blocked` returns true for
+    pub(super) fn breadth_first_search<F: Fn(CellIndex, CellIndex) -> bool>(&self, start: CellIndex, goal: CellIndex, blocked: F) -> Option<Vec<CellIndex>> {
+        // For every discovered cell, the cell we discovered it from. This doubles as the visited set.
+        let mut came_from: Vec<Vec<Option<CellIndex>>> = vec![vec![None; self.size.1]; self.size.0];
+        came_from[start.0][start.1] = Some(start);
+
+        let mut frontier = VecDeque::new();
+        frontier.push_back(start);
+
+        while let Some(current) = frontier.pop_front() {
+            if current == goal {
+                return Some(reconstruct_path(&came_from, start, goal));
+            }
+
+            for neighbor in self.neighbors(current) {
+                if came_from[neighbor.0][neighbor.1].is_none() && !blocked(current, neighbor) {
+                    came_from[neighbor.0][neighbor.1] = Some(current);
+                    frontier.push_back(neighbor);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Counts how many different shortest paths there are between two cells. Saturates at u64::MAX.
+    /// A perfect maze always has exactly one; mazes with loops can have many. Returns 0 if either cell is a wall or no path exists.
+    pub fn count_shortest_paths(&self, start: CellIndex, goal: CellIndex) -> u64 {
+        if !self.is_path(start) || !self.is_path(goal) {
+            return 0;
+        }
+
+        // Every cell's count is the sum of the counts of the cells one step closer to the start
+        let mut distances: Vec<Vec<Option<usize>>> = vec![vec![None; self.size.1]; self.size.0];
+        let mut counts: Vec<Vec<u64>> = vec![vec![0; self.size.1]; self.size.0];
+        distances[start.0][start.1] = Some(0);
+        counts[start.0][start.1] = 1;
+
+        let mut frontier = VecDeque::new();
+        frontier.push_back(start);
+
+        while let Some(current) = frontier.pop_front() {
+            if current == goal {
+                break;
+            }
+
+            let distance = distances[current.0][current.1].unwrap(); // Safe to unwrap since cells get a distance before being queued
+            let count = counts[current.0][current.1];
+            for neighbor in self.neighbors(current) {
+                match distances[neighbor.0][neighbor.1] {
+                    None => {
+                        distances[neighbor.0][neighbor.1] = Some(distance + 1);
+                        counts[neighbor.0][neighbor.1] = count;
+                        frontier.push_back(neighbor);
+                    },
+                    Some(neighbor_distance) if neighbor_distance == distance + 1 => {
+                        counts[neighbor.0][neighbor.1] = counts[neighbor.0][neighbor.1].saturating_add(count);
+                    },
+                    Some(_) => {},
+                }
+            }
+        }
+
+        counts[goal.0][goal.1]
+    }
+
+    /// Returns true if there's exactly one shortest path between the two cells
+    /// Useful for checking braided puzzles still have a single intended solution.
+    pub fn has_unique_shortest_path(&self, start: CellIndex, goal: CellIndex) -> bool {
+        self.count_shortest_paths(start, goal) == 1
+    }
+
+    /// Breadth first search distances from the given cell to every path cell reachable from it, indexed as distances[x][y]
+    pub(super) fn bfs_distances(&self, start: CellIndex) -> Vec<Vec<Option<usize>>> {
+        let mut distances: Vec<Vec<Option<usize>>> = vec![vec![None; self.size.1]; self.size.0];
+        distances[start.0][start.1] = Some(0);
+
+        let mut frontier = VecDeque::new();
+        frontier.push_back(start);
+
+        while let Some(current) = frontier.pop_front() {
+            let distance = distances[current.0][current.1].unwrap(); // Safe to unwrap since cells get a distance before being queued
+            for neighbor in self.neighbors(current) {
+                if distances[neighbor.0][neighbor.1].is_none() {
+                    distances[neighbor.0][neighbor.1] = Some(distance + 1);
+                    frontier.push_back(neighbor);
+                }
+            }
+        }
+
+        distances
+    }
+}
+
+/// Walks a came-from grid backwards from the goal to build the path from start to goal
+pub(super) fn reconstruct_path(came_from: &[Vec<Option<CellIndex>>], start: CellIndex, goal: CellIndex) -> Vec<CellIndex> {
+    let mut path = vec![goal];
+    let mut current = goal;
+    while current != start {
+        current = came_from[current.0][current.1].unwrap(); // Safe to unwrap since every cell on the way back was discovered
+        path.push(current);
+    }
+    path.reverse();
+
+    debug!("Reconstructed path: {:?}", path);
+
+    path
+}
@@ -0,0 +1,47 @@
+use alloc::vec::Vec;
+
+use super::{Maze, CellIndex, Cell};
+
+impl Maze {
+    /// Fills dead ends back in as solid rock, up to `passes` times over, and returns how many cells were filled
+    /// Each pass fills every dead end at once, so it shortens every stub by one cell. Carved cells cut off from everything are filled too.
+    /// Cells with an entrance, exit, labeled opening or portal are kept, so enough passes leave only the loops and the routes between them.
+    /// A maze with none of those is filled in completely. Stops early once a pass finds nothing to fill. Tags on filled cells are dropped.
+    pub fn trim_dead_ends(&mut self, passes: usize) -> usize {
+        let mut kept: Vec<CellIndex> = self.entrances.iter().flat_map(|&(entrance, exit)| [entrance.cell, exit.cell]).collect();
+        kept.extend(self.openings().map(|(_, opening)| opening.cell));
+        kept.extend(self.portals.iter().flat_map(|&(a, b)| [a, b]));
+
+        let mut filled = 0;
+        for pass in 0..passes {
+            // A dead end is open to at most one neighbor, with no opening in the outer wall
+            let dead_ends: Vec<CellIndex> = self.path_cells()
+                .filter(|cell| !kept.contains(cell))
+                .filter(|&cell| {
+                    let open = self.open_directions(cell);
+                    open.len() <= 1 && open.iter().all(|&direction| self.step(cell, direction).is_some())
+                })
+                .collect();
+            if dead_ends.is_empty() {
+                break;
+            }
+
+            debug!("Trimming {} dead ends on pass {}", dead_ends.len(), pass + 1);
+            for &cell in &dead_ends {
+                // Two dead ends joined to each other are both filled, and the second has nothing left to close by the time it's reached
+                for direction in self.open_directions(cell) {
+                    let neighbor = self.step(cell, direction).unwrap(); // Safe to unwrap since dead ends have no openings in the outer wall
+                    self.update_cell(neighbor, |neighbor| neighbor.close(direction.reverse()));
+                    self.make_two_way(cell, neighbor);
+                }
+                self.update_cell(cell, |cell| *cell = Cell::default());
+                self.tags.remove(&cell);
+            }
+            filled += dead_ends.len();
+        }
+
+        info!("Trimmed {} dead end cells", filled);
+
+        filled
+    }
+}
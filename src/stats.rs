@@ -0,0 +1,133 @@
+use alloc::vec::Vec;
+
+use super::{Maze, CellIndex, Direction};
+
+/// Counts of each kind of cell in the maze, classified by which of its sides are open
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct MazeStats {
+    /// Cells with exactly one opening
+    pub dead_ends: usize,
+    /// Cells open on two opposite sides
+    pub straightaways: usize,
+    /// Cells open on two adjacent sides
+    pub turns: usize,
+    /// Cells open on three sides
+    pub t_junctions: usize,
+    /// Cells open on all four sides
+    pub crossroads: usize,
+    /// Cells open on three or four sides, i.e. T-junctions and crossroads together
+    pub junctions: usize,
+    /// Average number of steps in a corridor, going from one dead end or junction to the next
+    pub average_corridor_length: f64,
+    /// Steps in the longest corridor between dead ends and junctions
+    pub longest_corridor: usize,
+    /// Fraction of corridor cells (straightaways and turns) that are turns. High values make for twisty passages.
+    pub turn_frequency: f64,
+    /// Fraction of all cells that are corridor cells rather than dead ends or junctions.
+    /// Mazes with a high river factor have long winding passages, low ones are short and branchy.
+    pub river_factor: f64,
+    /// Steps along `Maze::solution`, or None if the maze has no path cells
+    pub solution_length: Option<usize>,
+}
+
+impl Maze {
+    /// Classifies every cell of the maze as a dead end, straightaway, turn, T-junction or crossroads
+    /// Comparing these between generators shows off their texture, e.g. Wilson's produces far more dead ends than a recursive backtracker.
+    /// Also measures the corridors and the solution, between the entrance and exit if they've been opened and along the longest path otherwise.
+    pub fn stats(&self) -> MazeStats {
+        info!("Collecting maze stats");
+
+        let mut stats = MazeStats::default();
+        for cell in self.path_cells() {
+            let open = self.open_directions(cell);
+            match open.len() {
+                1 => stats.dead_ends += 1,
+                2 if open[0].reverse() == open[1] => stats.straightaways += 1,
+                2 => stats.turns += 1,
+                3 => stats.t_junctions += 1,
+                4 => stats.crossroads += 1,
+                _ => {}, // Isolated cells, only possible in a 1x1 maze
+            }
+        }
+
+        let corridor_cells = stats.straightaways + stats.turns;
+        let total_cells = corridor_cells + stats.dead_ends + stats.t_junctions + stats.crossroads;
+        stats.junctions = stats.t_junctions + stats.crossroads;
+        let (average_corridor_length, longest_corridor) = self.corridor_lengths();
+        stats.average_corridor_length = average_corridor_length;
+        stats.longest_corridor = longest_corridor;
+        stats.turn_frequency = if corridor_cells == 0 { 0.0 } else { stats.turns as f64 / corridor_cells as f64 };
+        stats.river_factor = if total_cells == 0 { 0.0 } else { corridor_cells as f64 / total_cells as f64 };
+        stats.solution_length = self.solution().map(|path| path.len() - 1);
+
+        info!("Maze stats: {:?}", stats);
+
+        stats
+    }
+
+    /// Measures how far each dead end is from the nearest junction, and returns how many dead ends there are at each depth
+    /// `histogram[depth]` is the number of dead ends `depth` steps away from a junction. In a maze with no junctions at all,
+    /// dead ends are measured to the far end of the corridor instead.
+    pub fn dead_end_depths(&self) -> Vec<usize> {
+        let mut histogram: Vec<usize> = Vec::new();
+
+        for dead_end in self.path_cells() {
+            let open = self.open_directions(dead_end);
+            if open.len() != 1 {
+                continue;
+            }
+
+            let depth = self.corridor_length(dead_end, open[0]);
+            if histogram.len() <= depth {
+                histogram.resize(depth + 1, 0);
+            }
+            histogram[depth] += 1;
+        }
+
+        info!("Dead end depths: {:?}", histogram);
+
+        histogram
+    }
+
+    /// Walks every corridor leaving every dead end and junction, and returns the average and longest of their lengths
+    /// Each corridor gets walked from both ends, which doesn't change either.
+    fn corridor_lengths(&self) -> (f64, usize) {
+        let mut total_length: usize = 0;
+        let mut longest: usize = 0;
+        let mut corridor_count: usize = 0;
+
+        for node in self.path_cells().filter(|&cell| self.open_directions(cell).len() != 2) {
+            for direction in self.open_directions(node) {
+                let length = self.corridor_length(node, direction);
+                total_length += length;
+                longest = longest.max(length);
+                corridor_count += 1;
+            }
+        }
+
+        if corridor_count == 0 {
+            (0.0, 0)
+        } else {
+            (total_length as f64 / corridor_count as f64, longest)
+        }
+    }
+
+    /// Number of cells stepped through following a corridor from `start` until reaching a dead end or junction
+    fn corridor_length(&self, start: CellIndex, mut direction: Direction) -> usize {
+        let mut current_pos = start;
+        let mut length: usize = 0;
+
+        while let Some(next) = self.step(current_pos, direction) {
+            current_pos = next;
+            length += 1;
+
+            let open = self.open_directions(current_pos);
+            if open.len() != 2 {
+                break;
+            }
+            direction = if open[0] == direction.reverse() { open[1] } else { open[0] };
+        }
+
+        length
+    }
+}
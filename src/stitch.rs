@@ -0,0 +1,137 @@
+use alloc::{vec, vec::Vec};
+
+use rand::{Rng, seq::SliceRandom};
+
+use super::{Maze, MazeError, CellIndex, Direction};
+
+/// A maze to place on the canvas when stitching, with its top left corner at `offset`
+#[derive(Debug, Clone, Copy)]
+pub struct Piece<'a> {
+    /// Where the piece's cell (0, 0) goes on the canvas
+    pub offset: CellIndex,
+    /// The maze to copy in
+    pub maze: &'a Maze,
+}
+
+impl Maze {
+    /// Places several mazes on an empty canvas of the given size, then carves a doorway between each pair of cells in `doorways`
+    /// Doorways use canvas coordinates. Openings in a piece's outer wall that face another canvas cell are sealed, and the pieces'
+    /// entrances, labeled openings, portals, one-way passages and tags are dropped. Canvas cells not covered by a piece are left uncarved. Fails if a piece doesn't fit, two pieces overlap,
+    /// a doorway isn't between neighbors, or the path cells don't end up as one connected region.
+    pub fn stitch(size: (usize, usize), pieces: &[Piece], doorways: &[(CellIndex, CellIndex)]) -> Result<Maze, MazeError> {
+        info!("Stitching {} pieces onto a {}x{} canvas", pieces.len(), size.0, size.1);
+
+        let mut maze = Maze::empty(size.0, size.1)?;
+        let mut covered = vec![vec![false; size.1]; size.0];
+
+        for piece in pieces {
+            let (x0, y0) = piece.offset;
+            let end = (x0 + piece.maze.size.0, y0 + piece.maze.size.1);
+            if end.0 > size.0 || end.1 > size.1 {
+                return Err(MazeError::InvalidRegion { from: piece.offset, to: end });
+            }
+
+            for ((x, y), mut cell) in piece.maze.cells() {
+                let canvas_cell = (x0 + x, y0 + y);
+                if covered[canvas_cell.0][canvas_cell.1] {
+                    return Err(MazeError::Overlap { cell: canvas_cell });
+                }
+                covered[canvas_cell.0][canvas_cell.1] = true;
+
+                // Border openings of the piece would be one way passages once there's a cell on the other side
+                for &direction in Direction::ALL.iter() {
+                    if piece.maze.step((x, y), direction).is_none() && maze.step(canvas_cell, direction).is_some() {
+                        cell.close(direction);
+                    }
+                }
+                maze.update_cell(canvas_cell, |slot| *slot = cell);
+            }
+        }
+
+        for &(from, to) in doorways {
+            maze.carve(from, to)?;
+        }
+
+        let regions = maze.regions().count();
+        if regions > 1 {
+            return Err(MazeError::Disconnected { regions });
+        }
+
+        Ok(maze)
+    }
+
+    /// Joins another maze onto the given side of this one with `tile`, opening `passages` doorways at random places along the seam
+    /// The two mazes must be the same height to join left or right, and the same width to join above or below, or it fails with SizeMismatch.
+    pub fn join<R: Rng + ?Sized>(&self, other: &Maze, side: Direction, passages: usize, rng: &mut R) -> Result<Maze, MazeError> {
+        match side {
+            Direction::Right => Maze::tile(&[&[self, other]], passages, rng),
+            Direction::Left => Maze::tile(&[&[other, self]], passages, rng),
+            Direction::Down => Maze::tile(&[&[self], &[other]], passages, rng),
+            Direction::Up => Maze::tile(&[&[other], &[self]], passages, rng),
+        }
+    }
+
+    /// Lays a grid of mazes out edge to edge, given as rows from the top, and opens `passages` doorways at random places along every seam
+    /// between neighboring pieces, for assembling huge mazes from pieces generated separately. Pieces in a row must be the same height
+    /// and pieces in a column the same width, or it fails with SizeMismatch. Fails with OutOfRange if `passages` is 0 or more than a seam is long.
+    /// Every piece is joined to all its neighbors, so a grid of perfect mazes bigger than one row or column gets loops between the pieces.
+    /// Like `stitch`, the pieces' entrances and openings are dropped.
+    pub fn tile<R: Rng + ?Sized>(grid: &[&[&Maze]], passages: usize, rng: &mut R) -> Result<Maze, MazeError> {
+        let columns = grid.first().map_or(0, |row| row.len());
+        if columns == 0 {
+            return Err(MazeError::InvalidDimensions { got: (columns, grid.len()) });
+        }
+
+        // Every column is as wide as its top piece and every row as tall as its leftmost one
+        let widths: Vec<usize> = grid[0].iter().map(|piece| piece.size.0).collect();
+        let heights: Vec<usize> = grid.iter().map(|row| row[0].size.1).collect();
+        let mut pieces = Vec::with_capacity(grid.len() * columns);
+        let mut y0 = 0;
+        for (row, &height) in grid.iter().zip(&heights) {
+            if row.len() != columns {
+                return Err(MazeError::InvalidDimensions { got: (row.len(), grid.len()) });
+            }
+            let mut x0 = 0;
+            for (&maze, &width) in row.iter().zip(&widths) {
+                if maze.size != (width, height) {
+                    return Err(MazeError::SizeMismatch { expected: (width, height), got: maze.size });
+                }
+                pieces.push(Piece { offset: (x0, y0), maze });
+                x0 += width;
+            }
+            y0 += height;
+        }
+        let size = (widths.iter().sum(), heights.iter().sum());
+
+        // Seams between columns run the height of each row, and seams between rows the width of each column
+        let column_seams = heights.iter().filter(|_| columns > 1);
+        let row_seams = widths.iter().filter(|_| grid.len() > 1);
+        let shortest_seam = column_seams.chain(row_seams).min();
+        if passages == 0 || shortest_seam.is_some_and(|&seam| passages > seam) {
+            let max = shortest_seam.map_or(f64::INFINITY, |&seam| seam as f64);
+            return Err(MazeError::OutOfRange { setting: "passages", got: passages as f64, min: 1.0, max });
+        }
+
+        info!("Tiling {} pieces into a {}x{} maze with {} passages along each seam", pieces.len(), size.0, size.1, passages);
+
+        // Each piece opens doorways into the piece to its right and the piece below it
+        let mut doorways = Vec::new();
+        let pick = |seam: usize, rng: &mut R| {
+            let mut places: Vec<usize> = (0..seam).collect();
+            places.partial_shuffle(rng, passages);
+            places.truncate(passages);
+            places
+        };
+        for (i, piece) in pieces.iter().enumerate() {
+            let ((x0, y0), (width, height)) = (piece.offset, piece.maze.size);
+            if (i + 1) % columns != 0 {
+                doorways.extend(pick(height, rng).into_iter().map(|y| ((x0 + width - 1, y0 + y), (x0 + width, y0 + y))));
+            }
+            if i + columns < pieces.len() {
+                doorways.extend(pick(width, rng).into_iter().map(|x| ((x0 + x, y0 + height - 1), (x0 + x, y0 + height))));
+            }
+        }
+
+        Maze::stitch(size, &pieces, &doorways)
+    }
+}
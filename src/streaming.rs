@@ -0,0 +1,152 @@
+use alloc::{format, vec};
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use image::Rgb;
+use png::{BitDepth, ColorType, Encoder};
+
+use rand::Rng;
+
+use super::{Maze, MazeError, Direction, ImageStyle};
+
+impl Maze {
+    /// Writes the maze as a PNG, laid out like `build_image`, one pixel row at a time so the whole image is never held in memory
+    /// Pixels are stored as 1 bit greyscale, so the file looks the same as a saved `build_image` but is much smaller. Meant for mazes
+    /// too big to render with `build_image`, which needs 3 bytes per pixel on top of the maze.
+    pub fn write_png<W: Write>(&self, writer: W) -> Result<(), MazeError> {
+        let (width, height) = self.pixel_size();
+        info!("Streaming a {}x{} PNG", width, height);
+
+        self.write_png_region(writer, (0, 0), (width, height))?;
+
+        info!("PNG streaming complete");
+
+        Ok(())
+    }
+
+    /// Like `build_styled_image` saved as a PNG, but streamed one pixel row at a time like `write_png`, so memory stays at one row however big the image
+    /// Fails with InvalidScale if `scale` is 0. See `write_png_with_style` to mark the entrance and exit too.
+    pub fn write_styled_png<W: Write>(&self, writer: W, scale: usize, wall: Rgb<u8>, path: Rgb<u8>) -> Result<(), MazeError> {
+        self.write_png_with_style(writer, &ImageStyle { corridor_width: scale, wall_thickness: scale, wall, path, ..ImageStyle::default() })
+    }
+
+    /// Streams the maze to a PNG file with `write_png`
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> Result<(), MazeError> {
+        let mut file = BufWriter::new(File::create(path)?);
+        self.write_png(&mut file)?;
+        file.flush()?;
+        Ok(())
+    }
+
+    /// Generates a maze with `eller_rows` and streams it to a PNG as each row is finished, so no more than a row of the maze is ever in memory
+    /// The image is laid out like `build_image`, in 1 bit greyscale like `write_png`.
+    pub fn write_eller_png<R: Rng, W: Write>(width: usize, height: usize, rng: R, writer: W) -> Result<(), MazeError> {
+        let rows = Maze::eller_rows(width, height, rng)?;
+        let (pixel_width, pixel_height) = (2 * width + 1, 2 * height + 1);
+        info!("Streaming a {}x{} PNG while generating", pixel_width, pixel_height);
+
+        #[cfg(feature = "memory-stats")]
+        let writer = super::memory::CountingWriter::new(writer);
+
+        let mut encoder = Encoder::new(writer, pixel_width as u32, pixel_height as u32);
+        encoder.set_color(ColorType::Grayscale);
+        encoder.set_depth(BitDepth::One);
+        let mut png = encoder.write_header().map_err(io::Error::from)?;
+        let mut stream = png.stream_writer();
+
+        // The top wall, then each row of cells followed by the row of walls and passages below it
+        let mut pixels = vec![0u8; pixel_width.div_ceil(8)];
+        stream.write_all(&pixels)?;
+        for row in rows {
+            pixels.iter_mut().for_each(|byte| *byte = 0);
+            for (x, cell) in row.iter().enumerate() {
+                set_bit(&mut pixels, 2 * x + 1);
+                if cell.is_open(Direction::Right) {
+                    set_bit(&mut pixels, 2 * x + 2);
+                }
+            }
+            stream.write_all(&pixels)?;
+
+            pixels.iter_mut().for_each(|byte| *byte = 0);
+            for (x, _) in row.iter().enumerate().filter(|(_, cell)| cell.is_open(Direction::Down)) {
+                set_bit(&mut pixels, 2 * x + 1);
+            }
+            stream.write_all(&pixels)?;
+        }
+        stream.finish().map_err(io::Error::from)?;
+
+        info!("PNG streaming complete");
+
+        Ok(())
+    }
+
+    /// Splits the rendered maze into square tiles of `tile_size` pixels and saves each one as a PNG in `dir`, for mazes too big for a single image
+    /// Tiles are named `tile_<column>_<row>.png`, counting from the top left, and the ones along the right and bottom edges are cut short.
+    /// `tiles.csv` in the same directory lists every tile with where it goes in the full image. The directory is created if needed.
+    /// Fails with OutOfRange if `tile_size` is 0.
+    pub fn save_tiles<P: AsRef<Path>>(&self, dir: P, tile_size: usize) -> Result<(), MazeError> {
+        if tile_size == 0 {
+            return Err(MazeError::OutOfRange { setting: "tile_size", got: 0.0, min: 1.0, max: f64::INFINITY });
+        }
+
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+
+        let (width, height) = self.pixel_size();
+        let (columns, rows) = (width.div_ceil(tile_size), height.div_ceil(tile_size));
+        info!("Saving a {}x{} image as {}x{} tiles", width, height, columns, rows);
+
+        let mut manifest = BufWriter::new(File::create(dir.join("tiles.csv"))?);
+        writeln!(manifest, "file,column,row,x,y,width,height")?;
+        for row in 0..rows {
+            for column in 0..columns {
+                let (x, y) = (column * tile_size, row * tile_size);
+                let size = (tile_size.min(width - x), tile_size.min(height - y));
+                let name = format!("tile_{}_{}.png", column, row);
+
+                let mut file = BufWriter::new(File::create(dir.join(&name))?);
+                self.write_png_region(&mut file, (x, y), size)?;
+                file.flush()?;
+                writeln!(manifest, "{},{},{},{},{},{},{}", name, column, row, x, y, size.0, size.1)?;
+            }
+        }
+        manifest.flush()?;
+
+        info!("Tiles saved");
+
+        Ok(())
+    }
+
+    /// Streams the pixels from `origin` to `origin + size` of the rendered maze as a 1 bit greyscale PNG
+    fn write_png_region<W: Write>(&self, writer: W, origin: (usize, usize), size: (usize, usize)) -> Result<(), MazeError> {
+        let (width, height) = size;
+        #[cfg(feature = "memory-stats")]
+        let writer = super::memory::CountingWriter::new(writer);
+        let mut encoder = Encoder::new(writer, width as u32, height as u32);
+        encoder.set_color(ColorType::Grayscale);
+        encoder.set_depth(BitDepth::One);
+        let mut png = encoder.write_header().map_err(io::Error::from)?;
+        let mut stream = png.stream_writer();
+
+        let mut row = vec![0u8; width.div_ceil(8)];
+        for py in origin.1..origin.1 + height {
+            row.iter_mut().for_each(|byte| *byte = 0);
+            self.path_pixels_in_row(py, |px| {
+                if px >= origin.0 && px < origin.0 + width {
+                    set_bit(&mut row, px - origin.0);
+                }
+            });
+            stream.write_all(&row)?;
+        }
+        stream.finish().map_err(io::Error::from)?;
+
+        Ok(())
+    }
+}
+
+/// Makes a pixel of a 1 bit row white. Eight pixels go in each byte, leftmost in the highest bit.
+fn set_bit(row: &mut [u8], px: usize) {
+    row[px / 8] |= 0x80 >> (px % 8);
+}
@@ -0,0 +1,162 @@
+use alloc::{vec, vec::Vec};
+
+use std::io::{self, Write};
+
+use image::{Rgb, RgbImage};
+use png::{BitDepth, ColorType, Encoder};
+
+use super::{Maze, MazeError, CellIndex, tile_span};
+
+/// How `build_image_with_style` and `write_png_with_style` draw a maze
+/// The markers color the entrance and exit holes in the outer wall so a printed maze shows where to start and finish.
+/// Either one is left as a plain hole if its color is None, and both are if the maze has no entrances.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ImageStyle {
+    /// Pixels across every cell and every passage between cells
+    pub corridor_width: usize,
+    /// Pixels across every wall
+    pub wall_thickness: usize,
+    pub wall: Rgb<u8>,
+    pub path: Rgb<u8>,
+    /// Color of the entrance hole
+    pub entrance: Option<Rgb<u8>>,
+    /// Color of the exit hole
+    pub exit: Option<Rgb<u8>>,
+}
+
+impl ImageStyle {
+    /// Green for the entrance and red for the exit, as in `build_marked_image`
+    pub const MARKERS: (Rgb<u8>, Rgb<u8>) = (Rgb([0, 255, 0]), Rgb([255, 0, 0]));
+
+    /// (corridor width, wall thickness), or InvalidScale if either is 0
+    fn widths(&self) -> Result<(usize, usize), MazeError> {
+        if self.corridor_width == 0 || self.wall_thickness == 0 {
+            return Err(MazeError::InvalidScale);
+        }
+        Ok((self.corridor_width, self.wall_thickness))
+    }
+}
+
+/// Black walls and white paths at one pixel each with no markers, the same as `build_image`
+impl Default for ImageStyle {
+    fn default() -> Self {
+        ImageStyle { corridor_width: 1, wall_thickness: 1, wall: Rgb([0, 0, 0]), path: Rgb([255, 255, 255]), entrance: None, exit: None }
+    }
+}
+
+impl Maze {
+    /// Renders the maze with the given style
+    /// Fails with InvalidScale if the corridor width or wall thickness is 0.
+    pub fn build_image_with_style(&self, style: &ImageStyle) -> Result<RgbImage, MazeError> {
+        let widths = style.widths()?;
+        let (width, height) = self.styled_size(widths);
+        let mut bytes = Vec::new();
+        self.render_into(&mut bytes, &style.wall.0, &style.path.0, widths);
+        let mut img = RgbImage::from_raw(width as u32, height as u32, bytes).unwrap(); // Safe to unwrap since render_into makes exactly that many pixels
+
+        self.draw_markers(&mut img, style, widths);
+
+        Ok(img)
+    }
+
+    /// Renders a picture maze: the style's walls, with `background` showing through every path pixel
+    /// The background is stretched to the size of the image first, so any picture fits any maze. The style's path color goes unused.
+    /// Fails with InvalidScale if the corridor width or wall thickness is 0.
+    pub fn build_picture_image(&self, style: &ImageStyle, background: &RgbImage) -> Result<RgbImage, MazeError> {
+        let widths = style.widths()?;
+        let (width, height) = self.styled_size(widths);
+        info!("Rendering a {}x{} picture maze", width, height);
+
+        // One byte per image pixel, 1 where the picture shows through
+        let mut mask = Vec::new();
+        self.render_into(&mut mask, &[0], &[1], widths);
+        let mut img = image::imageops::resize(background, width as u32, height as u32, image::imageops::FilterType::Triangle);
+        for (pixel, &is_path) in img.pixels_mut().zip(&mask) {
+            if is_path == 0 {
+                *pixel = style.wall;
+            }
+        }
+
+        self.draw_markers(&mut img, style, widths);
+
+        Ok(img)
+    }
+
+    /// Writes the maze as a PNG with the given style, one pixel row at a time like `write_png`, so memory stays at one row however big the image
+    /// Pixels are stored as indices into a palette of the style's colors, 1 bit each without markers and 2 bits with them.
+    /// Fails with InvalidScale if the corridor width or wall thickness is 0.
+    pub fn write_png_with_style<W: Write>(&self, writer: W, style: &ImageStyle) -> Result<(), MazeError> {
+        let widths = style.widths()?;
+        let height = self.pixel_size().1;
+        let (image_width, image_height) = self.styled_size(widths);
+        info!("Streaming a {}x{} PNG", image_width, image_height);
+
+        // Palette indices are 0 for walls, 1 for paths, and 2 and up for each marker
+        let markers = self.markers(style);
+        let mut palette = vec![style.wall, style.path];
+        palette.extend(markers.iter().map(|&(_, color)| color));
+        let depth = if markers.is_empty() { 1 } else { 2 };
+
+        #[cfg(feature = "memory-stats")]
+        let writer = super::memory::CountingWriter::new(writer);
+        let mut encoder = Encoder::new(writer, image_width as u32, image_height as u32);
+        encoder.set_color(ColorType::Indexed);
+        encoder.set_depth(if depth == 1 { BitDepth::One } else { BitDepth::Two });
+        encoder.set_palette(palette.iter().flat_map(|color| color.0).collect());
+        let mut png = encoder.write_header().map_err(io::Error::from)?;
+        let mut stream = png.stream_writer();
+
+        // Each pixel of the rendered maze becomes a block of image pixels, so each row is drawn once and written as many times as the block is tall
+        let mut row = vec![0u8; (image_width * depth).div_ceil(8)];
+        for py in 0..height {
+            row.iter_mut().for_each(|byte| *byte = 0);
+            self.path_pixels_in_row(py, |px| tile_span(px, widths).for_each(|px| set_index(&mut row, px, 1, depth)));
+            for (i, &((x, _), _)) in markers.iter().enumerate().filter(|(_, ((_, y), _))| *y == py) {
+                tile_span(x, widths).for_each(|px| set_index(&mut row, px, 2 + i as u8, depth));
+            }
+            for _ in tile_span(py, widths) {
+                stream.write_all(&row)?;
+            }
+        }
+        stream.finish().map_err(io::Error::from)?;
+
+        info!("PNG streaming complete");
+
+        Ok(())
+    }
+
+    /// Size of the maze drawn at the (corridor, wall) widths, in image pixels
+    fn styled_size(&self, widths: (usize, usize)) -> (usize, usize) {
+        let (width, height) = self.pixel_size();
+        (tile_span(width - 1, widths).end, tile_span(height - 1, widths).end)
+    }
+
+    /// Paints the style's markers over an image rendered at the (corridor, wall) widths
+    fn draw_markers(&self, img: &mut RgbImage, style: &ImageStyle, widths: (usize, usize)) {
+        for ((x, y), color) in self.markers(style) {
+            for py in tile_span(y, widths) {
+                for px in tile_span(x, widths) {
+                    img.put_pixel(px as u32, py as u32, color);
+                }
+            }
+        }
+    }
+
+    /// The pixel and color of every marker the style draws on this maze
+    fn markers(&self, style: &ImageStyle) -> Vec<(CellIndex, Rgb<u8>)> {
+        match self.entrances {
+            Some((entrance, exit)) => [(entrance, style.entrance), (exit, style.exit)].iter()
+                .filter_map(|&(opening, color)| color.map(|color| (opening.pixel(), color)))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Sets a pixel of a row packed `depth` bits to a pixel to a palette index, leftmost pixel in the highest bits
+fn set_index(row: &mut [u8], px: usize, index: u8, depth: usize) {
+    let per_byte = 8 / depth;
+    let shift = 8 - depth * (px % per_byte + 1);
+    let mask = ((1u8 << depth) - 1) << shift;
+    row[px / per_byte] = row[px / per_byte] & !mask | index << shift;
+}
@@ -0,0 +1,69 @@
+use alloc::{format, string::String, vec, vec::Vec};
+use core::fmt::Write;
+
+use super::{Maze, MazeError, Provenance};
+
+/// Seconds each frame of `to_animated_svg` takes to fade in
+const FADE_SECONDS: f64 = 0.25;
+
+impl Maze {
+    /// Draws the maze as an SVG where the paths fade in with CSS animations in the order generation added them, see `with_provenance`
+    /// Cells and passages are drawn a pixel each, laid out like `build_image`, so the SVG stays crisp at any zoom.
+    /// The animation takes `seconds` and has `frames` steps, each one a single path of every pixel added in that stretch of the order, so the file
+    /// grows with the maze but not with the number of frames. A passage appears with whichever of its cells was added later, and paths the
+    /// provenance doesn't cover appear first. Fails with SizeMismatch if the provenance is for a maze of a different size,
+    /// or OutOfRange for no frames or a length that isn't positive.
+    pub fn to_animated_svg(&self, provenance: &Provenance, seconds: f64, frames: usize) -> Result<String, MazeError> {
+        if provenance.size() != self.size {
+            return Err(MazeError::SizeMismatch { expected: self.size, got: provenance.size() });
+        }
+        if frames == 0 {
+            return Err(MazeError::OutOfRange { setting: "frames", got: 0.0, min: 1.0, max: f64::INFINITY });
+        }
+        if seconds <= 0.0 || !seconds.is_finite() {
+            return Err(MazeError::OutOfRange { setting: "seconds", got: seconds, min: 0.0, max: f64::INFINITY });
+        }
+
+        let (width, height) = self.pixel_size();
+        info!("Drawing a {}x{} animated SVG in {} frames", width, height, frames);
+
+        // Which frame each path pixel appears in, from the order of the cells it belongs to
+        let cells = provenance.cells_added().max(1);
+        let order = |x: usize, y: usize| provenance.order_of((x, y)).unwrap_or(0);
+        let frame_of = |px: usize, py: usize| {
+            let (x, y) = (px / 2, py / 2);
+            let added = match (px % 2, py % 2) {
+                (1, 1) => order(x, y),
+                // Passages between a cell and the one to its left, or above it. Passages out of the maze only have the one cell.
+                (0, _) => order(x.min(self.size.0 - 1), y).max(order(x.saturating_sub(1), y)),
+                _ => order(x, y.min(self.size.1 - 1)).max(order(x, y.saturating_sub(1))),
+            };
+            added * frames / cells
+        };
+        let mut paths = vec![String::new(); frames];
+        // Marked a row at a time first, since some pixels get painted twice
+        let mut row = vec![false; width];
+        for py in 0..height {
+            row.iter_mut().for_each(|is_path| *is_path = false);
+            self.path_pixels_in_row(py, |px| row[px] = true);
+            for px in (0..width).filter(|&px| row[px]) {
+                write!(paths[frame_of(px, py)], "M{} {}h1v1h-1z", px, py).unwrap(); // Safe to unwrap since writing to a String can't fail
+            }
+        }
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {} {}\" shape-rendering=\"crispEdges\">\n\
+             <style>path{{fill:#fff;opacity:0;animation:f {}s linear forwards}}@keyframes f{{to{{opacity:1}}}}</style>\n\
+             <rect width=\"100%\" height=\"100%\" fill=\"#000\"/>\n",
+            width, height, FADE_SECONDS,
+        );
+        let steps: Vec<(usize, String)> = paths.into_iter().enumerate().filter(|(_, path)| !path.is_empty()).collect();
+        for (frame, path) in steps {
+            let delay = seconds * frame as f64 / frames as f64;
+            writeln!(svg, "<path style=\"animation-delay:{:.3}s\" d=\"{}\"/>", delay, path).unwrap(); // Safe to unwrap since writing to a String can't fail
+        }
+        svg.push_str("</svg>\n");
+
+        Ok(svg)
+    }
+}
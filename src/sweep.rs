@@ -0,0 +1,102 @@
+use alloc::vec::Vec;
+use std::io::{self, Write};
+use std::time::Instant;
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use super::{Algorithm, MazeError};
+
+/// Mean and standard deviation of one measurement across a sweep
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Summary {
+    /// Average over every maze
+    pub mean: f64,
+    /// Population standard deviation over every maze
+    pub stddev: f64,
+}
+
+impl Summary {
+    /// Population mean and standard deviation of the samples
+    fn of(samples: &[f64]) -> Summary {
+        if samples.is_empty() {
+            return Summary::default();
+        }
+
+        let count = samples.len() as f64;
+        let mean = samples.iter().sum::<f64>() / count;
+        let variance = samples.iter().map(|sample| (sample - mean).powi(2)).sum::<f64>() / count;
+
+        Summary { mean, stddev: variance.sqrt() }
+    }
+}
+
+/// Aggregate statistics for one algorithm in a sweep
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweepResult {
+    /// The generation algorithm, written to the CSV by its `Display` name
+    pub algorithm: Algorithm,
+    /// Number of mazes generated
+    pub mazes: usize,
+    /// Number of dead ends, as counted by `Maze::stats`
+    pub dead_ends: Summary,
+    /// Length of the longest path, which is where the entrance and exit would go
+    pub solution_length: Summary,
+    /// Time taken to generate each maze, in milliseconds
+    pub generation_millis: Summary,
+}
+
+/// Generates `count` mazes of the given size with every algorithm in `Algorithm::ALL` and summarizes how they compare
+/// The mazes are seeded `seed`, `seed + 1` and so on, so every algorithm gets the same seeds and a sweep can be repeated exactly.
+pub fn sweep(width: usize, height: usize, count: usize, seed: u64) -> Result<Vec<SweepResult>, MazeError> {
+    let mut results = Vec::with_capacity(Algorithm::ALL.len());
+
+    for &algorithm in Algorithm::ALL.iter() {
+        info!("Sweeping {} mazes with {}", count, algorithm);
+
+        let mut dead_ends = Vec::with_capacity(count);
+        let mut solution_lengths = Vec::with_capacity(count);
+        let mut generation_millis = Vec::with_capacity(count);
+
+        for index in 0..count {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed.wrapping_add(index as u64));
+            let start_time = Instant::now();
+            let maze = algorithm.generate(width, height, &mut rng)?;
+            generation_millis.push(start_time.elapsed().as_secs_f64() * 1000.0);
+
+            dead_ends.push(maze.stats().dead_ends as f64);
+            solution_lengths.push(maze.longest_path().map_or(0, |path| path.len()) as f64);
+        }
+
+        results.push(SweepResult {
+            algorithm,
+            mazes: count,
+            dead_ends: Summary::of(&dead_ends),
+            solution_length: Summary::of(&solution_lengths),
+            generation_millis: Summary::of(&generation_millis),
+        });
+    }
+
+    Ok(results)
+}
+
+/// Writes sweep results as CSV, one row per algorithm
+pub fn write_sweep_csv<W: Write>(results: &[SweepResult], mut writer: W) -> io::Result<()> {
+    writeln!(writer, "algorithm,mazes,dead_ends_mean,dead_ends_stddev,solution_length_mean,solution_length_stddev,generation_ms_mean,generation_ms_stddev")?;
+    for result in results {
+        writeln!(
+            writer,
+            "{},{},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3}",
+            result.algorithm,
+            result.mazes,
+            result.dead_ends.mean,
+            result.dead_ends.stddev,
+            result.solution_length.mean,
+            result.solution_length.stddev,
+            result.generation_millis.mean,
+            result.generation_millis.stddev,
+        )?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use alloc::{vec, vec::Vec};
+
+use rand::Rng;
+
+use super::{Maze, MazeError, CellIndex, Axis, Piece, Rotation};
+
+/// Takes each cell of a maze to where a symmetry moves it
+type CellMap<'a> = dyn Fn(CellIndex) -> CellIndex + 'a;
+
+/// The symmetries `Maze::symmetric` can give a maze
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    /// The right half is the left half mirrored. Needs an even width.
+    Mirror,
+    /// The maze looks the same turned 180 degrees. Needs an even width and an odd height.
+    HalfTurn,
+    /// Each quarter is the top left one mirrored left to right, top to bottom or both. Needs an even width and height.
+    MirrorBoth,
+    /// The maze looks the same turned 90 degrees. Needs a square maze with an even width.
+    QuarterTurn,
+}
+
+impl Maze {
+    /// Generates a symmetric maze, for logos and competition puzzles: one half or quarter is a random maze, and the rest are copies of it
+    /// mirrored or turned, joined by doorways placed so the symmetry still holds.
+    /// Two-fold symmetries give a perfect maze. Four-fold ones have exactly one loop, around the middle, since any symmetric set of doorways
+    /// that joins all four quarters joins them in a ring. A 2x2 four-fold maze is one open room. Fails with OutOfRange if the size doesn't suit the symmetry.
+    pub fn symmetric<R: Rng + ?Sized>(width: usize, height: usize, symmetry: Symmetry, rng: &mut R) -> Result<Maze, MazeError> {
+        if width == 0 || height == 0 {
+            return Err(MazeError::InvalidDimensions { got: (width, height) });
+        }
+        if !width.is_multiple_of(2) {
+            return Err(MazeError::OutOfRange { setting: "width", got: width as f64, min: 2.0, max: f64::INFINITY });
+        }
+        let height_fits = match symmetry {
+            Symmetry::Mirror => true,
+            Symmetry::HalfTurn => !height.is_multiple_of(2),
+            Symmetry::MirrorBoth => height.is_multiple_of(2),
+            Symmetry::QuarterTurn => height == width,
+        };
+        if !height_fits {
+            let (min, max) = if symmetry == Symmetry::QuarterTurn { (width as f64, width as f64) } else { (1.0, f64::INFINITY) };
+            return Err(MazeError::OutOfRange { setting: "height", got: height as f64, min, max });
+        }
+
+        info!("Generating a {}x{} maze with {:?} symmetry", width, height, symmetry);
+
+        let four_fold = matches!(symmetry, Symmetry::MirrorBoth | Symmetry::QuarterTurn);
+        let (half_width, half_height) = (width / 2, if four_fold { height / 2 } else { height });
+        let base = Maze::with_rng(half_width, half_height, rng)?;
+
+        // Every copy of the base, with the symmetry that takes a cell of the base's part of the maze to that copy's part
+        let flip_x = |(x, y): CellIndex| (width - 1 - x, y);
+        let flip_y = |(x, y): CellIndex| (x, height - 1 - y);
+        let turn = |(x, y): CellIndex| (width - 1 - y, x);
+        let half_turn = |(x, y): CellIndex| (width - 1 - x, height - 1 - y);
+        let three_quarter_turn = |(x, y): CellIndex| (y, height - 1 - x);
+        let copies: Vec<(CellIndex, Maze, &CellMap)> = match symmetry {
+            Symmetry::Mirror => vec![((half_width, 0), base.mirrored(Axis::Vertical), &flip_x)],
+            Symmetry::HalfTurn => vec![((half_width, 0), base.rotated(Rotation::Half), &half_turn)],
+            Symmetry::MirrorBoth => vec![
+                ((half_width, 0), base.mirrored(Axis::Vertical), &flip_x),
+                ((0, half_height), base.mirrored(Axis::Horizontal), &flip_y),
+                ((half_width, half_height), base.rotated(Rotation::Half), &half_turn),
+            ],
+            Symmetry::QuarterTurn => vec![
+                ((half_width, 0), base.rotated(Rotation::Quarter), &turn),
+                ((half_width, half_height), base.rotated(Rotation::Half), &half_turn),
+                ((0, half_height), base.rotated(Rotation::ThreeQuarters), &three_quarter_turn),
+            ],
+        };
+
+        // One doorway from the base to the copy on its right, and for mirroring both ways one to the copy below it too.
+        // Each one's images under the symmetry join up the rest. The half turn's has to be in the middle row to be its own image.
+        // Four-fold doorways next to the middle would open up the 2x2 block there, so they're kept away from it unless the maze is only 2x2.
+        let (right_row, down_column) = match symmetry {
+            Symmetry::Mirror => (rng.gen_range(0, half_height), None),
+            Symmetry::HalfTurn => (height / 2, None),
+            Symmetry::MirrorBoth => loop {
+                let (row, column) = (rng.gen_range(0, half_height), rng.gen_range(0, half_width));
+                if (row, column) != (half_height - 1, half_width - 1) || (half_width, half_height) == (1, 1) {
+                    break (row, Some(column));
+                }
+            },
+            Symmetry::QuarterTurn => (rng.gen_range(0, (half_height - 1).max(1)), None),
+        };
+        let mut doorways = vec![((half_width - 1, right_row), (half_width, right_row))];
+        if let Some(column) = down_column {
+            doorways.push(((column, half_height - 1), (column, half_height)));
+        }
+        for &door in &doorways.clone() {
+            for &(_, _, image) in &copies {
+                let (a, b) = (image(door.0), image(door.1));
+                if !doorways.iter().any(|&other| other == (a, b) || other == (b, a)) {
+                    doorways.push((a, b));
+                }
+            }
+        }
+
+        let mut pieces = vec![Piece { offset: (0, 0), maze: &base }];
+        pieces.extend(copies.iter().map(|(offset, maze, _)| Piece { offset: *offset, maze }));
+        let maze = Maze::stitch((width, height), &pieces, &doorways)?;
+
+        debug!("Joined the copies with doorways {:?}", doorways);
+
+        Ok(maze)
+    }
+}
@@ -0,0 +1,59 @@
+use alloc::{string::String, vec::Vec};
+
+use super::{Maze, MazeError, CellIndex};
+
+impl Maze {
+    /// Tags a cell with a name like "spawn", "treasure" or "trap", for games and tools to find later
+    /// Tags are saved with the maze by `to_bytes` and follow their cells through transforms and crops.
+    /// A cell can have any number of tags, and tagging a cell twice with the same name does nothing. Fails with OutOfBounds for cells outside the maze.
+    pub fn add_tag<T: Into<String>>(&mut self, cell: CellIndex, tag: T) -> Result<(), MazeError> {
+        if cell.0 >= self.size.0 || cell.1 >= self.size.1 {
+            return Err(MazeError::OutOfBounds { cell });
+        }
+
+        let tag = tag.into();
+        let tags = self.tags.entry(cell).or_default();
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+        Ok(())
+    }
+
+    /// Removes a tag from a cell. Returns false if the cell didn't have it.
+    pub fn remove_tag(&mut self, cell: CellIndex, tag: &str) -> bool {
+        let tags = match self.tags.get_mut(&cell) {
+            Some(tags) => tags,
+            None => return false,
+        };
+
+        let before = tags.len();
+        tags.retain(|existing| existing != tag);
+        let removed = tags.len() != before;
+        if tags.is_empty() {
+            self.tags.remove(&cell);
+        }
+        removed
+    }
+
+    /// The tags on a cell, in the order they were added
+    pub fn tags(&self, cell: CellIndex) -> &[String] {
+        self.tags.get(&cell).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns true if the cell has the tag
+    pub fn has_tag(&self, cell: CellIndex, tag: &str) -> bool {
+        self.tags(cell).iter().any(|existing| existing == tag)
+    }
+
+    /// Every cell with the tag, scanning column by column
+    pub fn cells_tagged<'a>(&'a self, tag: &'a str) -> impl Iterator<Item = CellIndex> + 'a {
+        self.tags.iter()
+            .filter(move |(_, tags)| tags.iter().any(|existing| existing == tag))
+            .map(|(&cell, _)| cell)
+    }
+
+    /// Every tagged cell along with its tags, scanning column by column
+    pub fn tagged_cells(&self) -> impl Iterator<Item = (CellIndex, &[String])> {
+        self.tags.iter().map(|(&cell, tags)| (cell, tags.as_slice()))
+    }
+}
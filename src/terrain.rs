@@ -0,0 +1,156 @@
+use alloc::{vec, vec::Vec};
+
+#[cfg(feature = "image")]
+use image::{Rgb, RgbImage};
+use rand::Rng;
+
+use super::{Maze, MazeError, CellIndex, Manhattan, Movement, Solver};
+#[cfg(feature = "image")]
+use super::Direction;
+
+/// What the ground is like in a cell, which sets how much it costs to walk into it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Terrain {
+    /// The cheapest way through, costing 1
+    Road,
+    /// Plain ground, costing 2
+    #[default]
+    Ground,
+    /// Slow going, costing 4
+    Mud,
+    /// Slowest of all, costing 8
+    Water,
+}
+
+impl Terrain {
+    /// Cost of walking into a cell with this terrain. Never less than 1, so distance heuristics stay admissible.
+    pub fn cost(self) -> u32 {
+        match self {
+            Terrain::Road => 1,
+            Terrain::Ground => 2,
+            Terrain::Mud => 4,
+            Terrain::Water => 8,
+        }
+    }
+}
+
+/// A terrain for every cell of a maze, kept alongside it so the plain wall and path model stays untouched
+/// Build one the same size as the maze with `new` or `from_noise`, then paint it by hand with `set`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TerrainMap {
+    /// Indexed as cells[x][y]
+    cells: Vec<Vec<Terrain>>,
+    size: (usize, usize),
+}
+
+impl TerrainMap {
+    /// A map of the given size in cells, all plain ground
+    pub fn new(width: usize, height: usize) -> Self {
+        TerrainMap { cells: vec![vec![Terrain::Ground; height]; width], size: (width, height) }
+    }
+
+    /// A map with patches of terrain from smooth random noise, with features roughly `feature_size` cells across
+    /// Low ground floods with water and mud, and the highest ground becomes road.
+    pub fn from_noise<R: Rng + ?Sized>(width: usize, height: usize, feature_size: usize, rng: &mut R) -> Self {
+        let feature_size = feature_size.max(1);
+
+        // Random heights on a coarse lattice, blended between the four corners around each cell
+        let (lattice_width, lattice_height) = (width / feature_size + 2, height / feature_size + 2);
+        let lattice: Vec<Vec<f64>> = (0..lattice_width).map(|_| (0..lattice_height).map(|_| rng.gen()).collect()).collect();
+
+        let mut map = TerrainMap::new(width, height);
+        for x in 0..width {
+            for y in 0..height {
+                let (fx, fy) = ((x % feature_size) as f64 / feature_size as f64, (y % feature_size) as f64 / feature_size as f64);
+                let (lx, ly) = (x / feature_size, y / feature_size);
+                let top = lattice[lx][ly] * (1.0 - fx) + lattice[lx + 1][ly] * fx;
+                let bottom = lattice[lx][ly + 1] * (1.0 - fx) + lattice[lx + 1][ly + 1] * fx;
+                let height = top * (1.0 - fy) + bottom * fy;
+
+                map.cells[x][y] = match height {
+                    h if h < 0.2 => Terrain::Water,
+                    h if h < 0.4 => Terrain::Mud,
+                    h if h < 0.8 => Terrain::Ground,
+                    _ => Terrain::Road,
+                };
+            }
+        }
+
+        map
+    }
+
+    /// Size of the map in cells, as (width, height)
+    pub fn size(&self) -> (usize, usize) {
+        self.size
+    }
+
+    /// The terrain of a cell, or None if it's outside the map
+    pub fn get(&self, cell: CellIndex) -> Option<Terrain> {
+        self.cells.get(cell.0).and_then(|col| col.get(cell.1)).copied()
+    }
+
+    /// Changes the terrain of a cell. Cells outside the map are ignored.
+    pub fn set(&mut self, cell: CellIndex, terrain: Terrain) {
+        if let Some(slot) = self.cells.get_mut(cell.0).and_then(|col| col.get_mut(cell.1)) {
+            *slot = terrain;
+        }
+    }
+
+    /// Total cost of walking the path: every cell after the first is paid for on entry
+    pub fn path_cost(&self, path: &[CellIndex]) -> u32 {
+        path.iter().skip(1).filter_map(|&cell| self.get(cell)).map(Terrain::cost).sum()
+    }
+}
+
+/// Finds the cheapest path over a terrain map, see `Maze::solve_terrain`. Finds nothing if the map is the wrong size for the maze.
+#[derive(Debug, Clone, Copy)]
+pub struct TerrainSolver<'a>(pub &'a TerrainMap);
+
+impl Solver for TerrainSolver<'_> {
+    fn solve(&self, maze: &Maze, start: CellIndex, goal: CellIndex) -> Option<Vec<CellIndex>> {
+        maze.solve_terrain(self.0, start, goal).ok().flatten()
+    }
+}
+
+impl Maze {
+    /// Finds the cheapest path between two cells, where entering each cell costs its `Terrain::cost`
+    /// Fails with SizeMismatch if the map isn't the same size as the maze. Returns None like `solve` if there's no path.
+    pub fn solve_terrain(&self, terrain: &TerrainMap, start: CellIndex, goal: CellIndex) -> Result<Option<Vec<CellIndex>>, MazeError> {
+        if terrain.size != self.size {
+            return Err(MazeError::SizeMismatch { expected: self.size, got: terrain.size });
+        }
+
+        // Manhattan is still admissible since every cell costs at least 1
+        Ok(self.astar(start, goal, &Manhattan, Movement::Orthogonal, |cell| terrain.cells[cell.0][cell.1].cost() as f64).path)
+    }
+
+    /// Renders the maze like `build_image`, but with path pixels colored by terrain
+    /// Road is grey, ground white, mud brown and water blue. Passages take the color of the cell to their left or above.
+    #[cfg(feature = "image")]
+    pub fn build_terrain_image(&self, terrain: &TerrainMap) -> Result<RgbImage, MazeError> {
+        if terrain.size != self.size {
+            return Err(MazeError::SizeMismatch { expected: self.size, got: terrain.size });
+        }
+
+        let color = |cell: CellIndex| match terrain.cells[cell.0][cell.1] {
+            Terrain::Road => Rgb([160, 160, 160]),
+            Terrain::Ground => Rgb([255, 255, 255]),
+            Terrain::Mud => Rgb([140, 90, 40]),
+            Terrain::Water => Rgb([60, 120, 255]),
+        };
+
+        let mut img = self.build_image();
+        for cell in self.path_cells() {
+            let (px, py) = (2 * cell.0 as u32 + 1, 2 * cell.1 as u32 + 1);
+            img.put_pixel(px, py, color(cell));
+            if self.can_move(cell, Direction::Right) {
+                img.put_pixel(px + 1, py, color(cell));
+            }
+            if self.can_move(cell, Direction::Down) {
+                img.put_pixel(px, py + 1, color(cell));
+            }
+        }
+
+        Ok(img)
+    }
+}
@@ -0,0 +1,142 @@
+use alloc::{collections::VecDeque, vec, vec::Vec};
+
+use super::{Maze, CellIndex, Hand};
+use super::solvers::reconstruct_path;
+
+/// One step of a traced breadth first search, see `Maze::solve_trace`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveEvent {
+    /// A cell was taken off the frontier and its neighbors are about to be explored
+    Visited(CellIndex),
+    /// A newly discovered cell was added to the frontier
+    FrontierAdded(CellIndex),
+    /// The goal was reached, with the shortest path to it. Always the last event.
+    PathFound(Vec<CellIndex>),
+    /// The frontier ran out without reaching the goal. Always the last event.
+    NoPath,
+}
+
+/// A solver whose exploration `Maze::solver_events` can record
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TracedSolver {
+    /// Breadth first search, as in `solve_trace`
+    BreadthFirst,
+    /// Following one wall, as in `solve_wall_follower`
+    WallFollower(Hand),
+    /// Trémaux's algorithm, as in `solve_tremaux`
+    #[cfg(feature = "std")]
+    Tremaux,
+}
+
+/// Iterator over the events of a breadth first search, produced lazily one cell at a time
+pub struct SolveTrace<'a> {
+    maze: &'a Maze,
+    start: CellIndex,
+    goal: CellIndex,
+    came_from: Vec<Vec<Option<CellIndex>>>,
+    frontier: VecDeque<CellIndex>,
+    pending: VecDeque<SolveEvent>,
+    finished: bool,
+}
+
+impl<'a> Iterator for SolveTrace<'a> {
+    type Item = SolveEvent;
+
+    fn next(&mut self) -> Option<SolveEvent> {
+        if let Some(event) = self.pending.pop_front() {
+            return Some(event);
+        }
+        if self.finished {
+            return None;
+        }
+
+        let current = match self.frontier.pop_front() {
+            Some(current) => current,
+            None => {
+                self.finished = true;
+                return Some(SolveEvent::NoPath);
+            }
+        };
+
+        if current == self.goal {
+            self.finished = true;
+            self.pending.push_back(SolveEvent::PathFound(reconstruct_path(&self.came_from, self.start, self.goal)));
+        } else {
+            for neighbor in self.maze.neighbors(current) {
+                if self.came_from[neighbor.0][neighbor.1].is_none() {
+                    self.came_from[neighbor.0][neighbor.1] = Some(current);
+                    self.frontier.push_back(neighbor);
+                    self.pending.push_back(SolveEvent::FrontierAdded(neighbor));
+                }
+            }
+        }
+
+        Some(SolveEvent::Visited(current))
+    }
+}
+
+impl Maze {
+    /// Runs the same breadth first search as `solve`, but exposes each step as an event so the search can be animated or analyzed
+    /// The search only advances as the iterator is consumed.
+    pub fn solve_trace(&self, start: CellIndex, goal: CellIndex) -> SolveTrace<'_> {
+        let mut came_from: Vec<Vec<Option<CellIndex>>> = vec![vec![None; self.size.1]; self.size.0];
+        let mut frontier = VecDeque::new();
+        let mut pending = VecDeque::new();
+
+        // Cells outside the maze or in walls leave the frontier empty, so they produce a single NoPath
+        if self.is_path(start) && self.is_path(goal) {
+            came_from[start.0][start.1] = Some(start);
+            frontier.push_back(start);
+            pending.push_back(SolveEvent::FrontierAdded(start));
+        }
+
+        SolveTrace { maze: self, start, goal, came_from, frontier, pending, finished: false }
+    }
+
+    /// Runs a solver and records its exploration as the events of `solve_trace`, so any of them can be animated the same way
+    /// Breadth first search gives its events as they are. The walkers give a Visited for every step, so cells they walk back
+    /// through show up again, and end with the route they found with every dead end and loop they walked cut out.
+    pub fn solver_events(&self, solver: TracedSolver, start: CellIndex, goal: CellIndex) -> Vec<SolveEvent> {
+        let (traversal, path) = match solver {
+            TracedSolver::BreadthFirst => return self.solve_trace(start, goal).collect(),
+            TracedSolver::WallFollower(hand) => match self.solve_wall_follower(start, goal, hand) {
+                Some(traversal) => {
+                    let path = self.erase_loops(&traversal);
+                    (traversal, path)
+                },
+                None => return vec![SolveEvent::NoPath],
+            },
+            #[cfg(feature = "std")]
+            TracedSolver::Tremaux => match self.solve_tremaux(start, goal) {
+                Some(tremaux) => (tremaux.traversal, tremaux.path),
+                None => return vec![SolveEvent::NoPath],
+            },
+        };
+
+        let mut events: Vec<SolveEvent> = traversal.into_iter().map(SolveEvent::Visited).collect();
+        events.push(SolveEvent::PathFound(path));
+        events
+    }
+
+    /// Cuts every loop out of a walk, leaving a route with each cell on it once
+    fn erase_loops(&self, walk: &[CellIndex]) -> Vec<CellIndex> {
+        // Indexed as position[x][y]. Where each cell is on the route so far, so returning to a cell truncates back to it.
+        let mut position: Vec<Vec<Option<usize>>> = vec![vec![None; self.size.1]; self.size.0];
+        let mut route: Vec<CellIndex> = Vec::new();
+        for &cell in walk {
+            match position[cell.0][cell.1] {
+                Some(index) => {
+                    for &erased in &route[index + 1..] {
+                        position[erased.0][erased.1] = None;
+                    }
+                    route.truncate(index + 1);
+                },
+                None => {
+                    position[cell.0][cell.1] = Some(route.len());
+                    route.push(cell);
+                },
+            }
+        }
+        route
+    }
+}
@@ -0,0 +1,85 @@
+use super::{Maze, CellIndex, Direction, Opening, PackedCells};
+
+/// A clockwise rotation by a multiple of 90 degrees
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rotation {
+    /// 90 degrees
+    Quarter,
+    /// 180 degrees
+    Half,
+    /// 270 degrees
+    ThreeQuarters,
+}
+
+/// The line a maze is mirrored across
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    /// A horizontal line through the middle, swapping top and bottom
+    Horizontal,
+    /// A vertical line through the middle, swapping left and right
+    Vertical,
+}
+
+impl Maze {
+    /// A copy of the maze rotated clockwise, with its entrances moved along with it
+    pub fn rotated(&self, rotation: Rotation) -> Maze {
+        let (width, height) = self.size;
+        match rotation {
+            Rotation::Quarter => self.remapped((height, width), |(x, y)| (height - 1 - y, x), Direction::turn_right),
+            Rotation::Half => self.remapped((width, height), |(x, y)| (width - 1 - x, height - 1 - y), Direction::reverse),
+            Rotation::ThreeQuarters => self.remapped((height, width), |(x, y)| (y, width - 1 - x), Direction::turn_left),
+        }
+    }
+
+    /// A copy of the maze mirrored across the given axis, with its entrances moved along with it
+    pub fn mirrored(&self, axis: Axis) -> Maze {
+        let (width, height) = self.size;
+        match axis {
+            Axis::Horizontal => self.remapped(self.size, |(x, y)| (x, height - 1 - y), |direction| match direction {
+                Direction::Up | Direction::Down => direction.reverse(),
+                _ => direction,
+            }),
+            Axis::Vertical => self.remapped(self.size, |(x, y)| (width - 1 - x, y), |direction| match direction {
+                Direction::Left | Direction::Right => direction.reverse(),
+                _ => direction,
+            }),
+        }
+    }
+
+    /// A copy of the maze flipped across its top-left to bottom-right diagonal, so rows become columns
+    pub fn transposed(&self) -> Maze {
+        self.remapped((self.size.1, self.size.0), |(x, y)| (y, x), |direction| match direction {
+            Direction::Up => Direction::Left,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Right,
+            Direction::Left => Direction::Up,
+        })
+    }
+
+    /// Builds a new maze of the given size, moving every cell to `map_cell` and turning its openings with `map_direction`
+    fn remapped<C: Fn(CellIndex) -> CellIndex, D: Fn(Direction) -> Direction>(&self, size: (usize, usize), map_cell: C, map_direction: D) -> Maze {
+        let map_opening = |opening: Opening| Opening { cell: map_cell(opening.cell), side: map_direction(opening.side) };
+        let entrances = self.entrances.map(|(entrance, exit)| (map_opening(entrance), map_opening(exit)));
+        let openings = self.openings.iter().map(|(label, opening)| (label.clone(), map_opening(*opening))).collect();
+
+        let portals = self.portals.iter().map(|&(a, b)| (map_cell(a), map_cell(b))).collect();
+
+        let one_way = self.one_way.iter().map(|&(from, to)| (map_cell(from), map_cell(to))).collect();
+
+        let tags = self.tags.iter().map(|(&cell, tags)| (map_cell(cell), tags.clone())).collect();
+
+        let mut maze = Maze { cells: PackedCells::grid(size), size, entrances, openings, portals, one_way, tags };
+        for (index, cell) in self.cells() {
+            maze.update_cell(map_cell(index), |moved| {
+                if cell.is_carved() {
+                    moved.carve();
+                }
+                for &direction in Direction::ALL.iter().filter(|&&direction| cell.is_open(direction)) {
+                    moved.open(map_direction(direction));
+                }
+            });
+        }
+
+        maze
+    }
+}
@@ -0,0 +1,89 @@
+use alloc::{vec, vec::Vec};
+use std::collections::HashMap;
+
+use super::{Maze, CellIndex};
+
+/// A passage between two adjacent cells, stored with the smaller cell first so each passage has one key
+pub type Passage = (CellIndex, CellIndex);
+
+/// The result of solving a maze with Trémaux's algorithm
+#[derive(Debug, Clone)]
+pub struct Tremaux {
+    /// The route from start to goal, with every explored dead end and loop removed
+    pub path: Vec<CellIndex>,
+    /// Every step the solver took, in order, including walking back out of dead ends
+    pub traversal: Vec<CellIndex>,
+    /// How many times each passage was walked. Passages marked once make up the solution; passages marked twice were dead ends.
+    pub marks: HashMap<Passage, u8>,
+}
+
+fn passage(a: CellIndex, b: CellIndex) -> Passage {
+    if a <= b { (a, b) } else { (b, a) }
+}
+
+impl Maze {
+    /// Solves the maze with Trémaux's algorithm, which only needs to see the cell it's in and the marks left on its passages
+    /// Every passage walked gets a mark. Unmarked passages are always preferred, a passage is never walked a third time,
+    /// and arriving at an already visited cell through a new passage means turning straight back.
+    /// Returns None if either cell is a wall or the goal can't be reached.
+    pub fn solve_tremaux(&self, start: CellIndex, goal: CellIndex) -> Option<Tremaux> {
+        info!("Solving maze with Tremaux's algorithm from ({}, {}) to ({}, {})", start.0, start.1, goal.0, goal.1);
+
+        if !self.is_path(start) || !self.is_path(goal) {
+            return None;
+        }
+
+        let mut marks: HashMap<Passage, u8> = HashMap::new();
+        let mut visited = vec![vec![false; self.size.1]; self.size.0];
+        visited[start.0][start.1] = true;
+
+        let mut traversal = vec![start];
+        // The solution so far. Walking back onto the previous cell pops the current one off.
+        let mut solution = vec![start];
+
+        let mut current_pos = start;
+        let mut previous_pos: Option<CellIndex> = None;
+        let mut arrived_at_visited_cell = false;
+
+        while current_pos != goal {
+            let mark_count = |neighbor: CellIndex| *marks.get(&passage(current_pos, neighbor)).unwrap_or(&0);
+
+            let next = match previous_pos {
+                // We came down a fresh passage into somewhere we've already been, so it's a loop. Go back the way we came.
+                Some(previous) if arrived_at_visited_cell && mark_count(previous) == 1 => Some(previous),
+                _ => {
+                    let neighbors = self.neighbors(current_pos);
+                    neighbors.iter().copied().find(|&neighbor| mark_count(neighbor) == 0)
+                        .or_else(|| neighbors.iter().copied().find(|&neighbor| mark_count(neighbor) == 1))
+                }
+            };
+
+            let next = match next {
+                Some(next) => next,
+                None => {
+                    info!("Every passage has been walked twice, the goal is unreachable");
+                    return None;
+                }
+            };
+
+            *marks.entry(passage(current_pos, next)).or_insert(0) += 1;
+            traversal.push(next);
+
+            if solution.len() >= 2 && solution[solution.len() - 2] == next {
+                solution.pop();
+            } else {
+                solution.push(next);
+            }
+
+            arrived_at_visited_cell = visited[next.0][next.1];
+            visited[next.0][next.1] = true;
+            previous_pos = Some(current_pos);
+            current_pos = next;
+        }
+
+        debug!("Tremaux traversal: {:?}", traversal);
+        info!("Goal reached after {} steps, solution length {}", traversal.len() - 1, solution.len());
+
+        Some(Tremaux { path: solution, traversal, marks })
+    }
+}
@@ -0,0 +1,159 @@
+use alloc::{vec, vec::Vec};
+
+use super::{Maze, CellIndex, Direction};
+
+/// Something wrong with a maze, as found by `Maze::validate`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Violation {
+    /// A cell was never carved, so it isn't part of the maze
+    UncarvedCell(CellIndex),
+    /// A cell is open on a side where its neighbor isn't open back, so the passage only exists from one end
+    OneWayPassage(CellIndex, Direction),
+    /// A group of path cells that isn't connected to the rest of the maze. `cell` is one of the cells in it.
+    Disconnected { cell: CellIndex, size: usize },
+    /// Opening the passage between these two cells (in either order) closed a loop
+    Loop(CellIndex, CellIndex),
+    /// The 2x2 block of cells with this top left corner has all four passages between them open, leaving an open room instead of corridors
+    OpenBlock(CellIndex),
+}
+
+/// Minimal union-find over cell ids, used to spot loops and count connected regions in one pass
+struct DisjointSet {
+    parents: Vec<usize>,
+    sizes: Vec<usize>,
+}
+
+impl DisjointSet {
+    fn new(count: usize) -> Self {
+        DisjointSet { parents: (0..count).collect(), sizes: vec![1; count] }
+    }
+
+    fn find(&mut self, mut id: usize) -> usize {
+        while self.parents[id] != id {
+            self.parents[id] = self.parents[self.parents[id]];
+            id = self.parents[id];
+        }
+        id
+    }
+
+    /// Merges the sets containing a and b. Returns false if they were already in the same set.
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.sizes[a] < self.sizes[b] {
+            core::mem::swap(&mut a, &mut b);
+        }
+        self.parents[b] = a;
+        self.sizes[a] += self.sizes[b];
+        true
+    }
+}
+
+impl Maze {
+    /// Checks that the maze is a perfect maze: every cell is carved, all path cells form one connected region with no loops,
+    /// and every passage is open from both ends. Returns every violation found, so new generators and post-processing passes can be debugged.
+    pub fn validate(&self) -> Result<(), Vec<Violation>> {
+        info!("Validating maze");
+
+        report(self.violations(true, true))
+    }
+
+    /// Same as `validate`, but allows loops, for mazes that are meant to have more than one route like those from `braid` or `add_loops`
+    /// Open 2x2 rooms are still reported, since knocking through walls at random can leave them.
+    pub fn validate_braided(&self) -> Result<(), Vec<Violation>> {
+        info!("Validating braided maze");
+
+        report(self.violations(true, false))
+    }
+
+    /// Panics if the maze breaks any of the invariants `validate` checks, other than having uncarved cells
+    /// Generators call this after every step when the `check-invariants` feature is on, so bugs are caught where they happen.
+    #[cfg(feature = "check-invariants")]
+    pub(super) fn check_invariants(&self) {
+        let violations = self.violations(false, true);
+        assert!(violations.is_empty(), "Maze invariants broken: {:?}", violations);
+    }
+
+    /// Everything wrong with the maze. Uncarved cells are only reported if `require_carved` is set, so half generated mazes can be checked too,
+    /// and loops only if `require_perfect` is.
+    fn violations(&self, require_carved: bool, require_perfect: bool) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        let (x_size, y_size) = self.size;
+        let id = |cell: CellIndex| cell.0 * y_size + cell.1;
+        let mut regions = DisjointSet::new(x_size * y_size);
+
+        for x in 0..x_size {
+            for y in 0..y_size {
+                let cell = (x, y);
+
+                if !self.is_path(cell) {
+                    if require_carved {
+                        violations.push(Violation::UncarvedCell(cell));
+                    }
+                    continue;
+                }
+
+                for direction in self.open_directions(cell) {
+                    // Openings in the outer wall have no neighbor to match
+                    if let Some(neighbor) = self.step(cell, direction) {
+                        if !self.is_open(neighbor, direction.reverse()) {
+                            violations.push(Violation::OneWayPassage(cell, direction));
+                        }
+                    }
+                }
+
+                let (right, down) = (self.can_move(cell, Direction::Right), self.can_move(cell, Direction::Down));
+                if right && down && self.can_move((x + 1, y), Direction::Down) && self.can_move((x, y + 1), Direction::Right) {
+                    violations.push(Violation::OpenBlock(cell));
+                }
+
+                // Only look right and down so each passage is checked once
+                for direction in [Direction::Right, Direction::Down] {
+                    let neighbor = self.step(cell, direction).filter(|&neighbor| self.is_open(cell, direction) && self.is_open(neighbor, direction.reverse()));
+                    if let Some(neighbor) = neighbor {
+                        if !regions.union(id(cell), id(neighbor)) && require_perfect {
+                            violations.push(Violation::Loop(cell, neighbor));
+                        }
+                    }
+                }
+            }
+        }
+
+        // The first region found is treated as the maze, anything else is disconnected from it
+        let mut main_region: Option<usize> = None;
+        let mut reported_regions = Vec::new();
+        for x in 0..x_size {
+            for y in 0..y_size {
+                let cell = (x, y);
+                if !self.is_path(cell) {
+                    continue;
+                }
+                let region = regions.find(id(cell));
+                match main_region {
+                    None => main_region = Some(region),
+                    Some(main) if main == region => {},
+                    Some(_) if !reported_regions.contains(&region) => {
+                        reported_regions.push(region);
+                        violations.push(Violation::Disconnected { cell, size: regions.sizes[region] });
+                    },
+                    Some(_) => {},
+                }
+            }
+        }
+
+        violations
+    }
+}
+
+/// Turns the violations found into the result `validate` returns
+fn report(violations: Vec<Violation>) -> Result<(), Vec<Violation>> {
+    if violations.is_empty() {
+        info!("Maze is valid");
+        Ok(())
+    } else {
+        info!("Maze has {} violations", violations.len());
+        Err(violations)
+    }
+}
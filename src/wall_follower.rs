@@ -0,0 +1,68 @@
+use alloc::{vec, vec::Vec};
+
+use super::{Maze, CellIndex, Direction};
+
+/// Which hand the wall follower keeps on the wall
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hand {
+    Left,
+    Right
+}
+
+impl Maze {
+    /// Solves the maze by keeping one hand on the wall and walking until the goal is reached
+    /// Unlike the other solvers this returns the whole traversal, including every dead end explored and walked back out of.
+    /// On a perfect maze this always reaches the goal. Returns None if either cell is a wall or the walk loops without finding the goal.
+    pub fn solve_wall_follower(&self, start: CellIndex, goal: CellIndex, hand: Hand) -> Option<Vec<CellIndex>> {
+        info!("Solving maze with {:?} hand wall follower from ({}, {}) to ({}, {})", hand, start.0, start.1, goal.0, goal.1);
+
+        if !self.is_path(start) || !self.is_path(goal) {
+            return None;
+        }
+
+        let mut traversal = vec![start];
+        let mut current_pos = start;
+        let mut facing = Direction::Up;
+
+        // The walk is deterministic, so if we're ever in the same cell facing the same way twice we're going in circles
+        // Indexed as seen_states[x][y][facing]
+        let mut seen_states = vec![vec![[false; 4]; self.size.1]; self.size.0];
+
+        while current_pos != goal {
+            let seen = &mut seen_states[current_pos.0][current_pos.1][facing as usize];
+            if *seen {
+                info!("Wall follower looped without reaching the goal");
+                return None;
+            }
+            *seen = true;
+
+            // Prefer turning towards the wall, then going straight, then turning away, then turning back
+            let preferences = match hand {
+                Hand::Left => [facing.turn_left(), facing, facing.turn_right(), facing.reverse()],
+                Hand::Right => [facing.turn_right(), facing, facing.turn_left(), facing.reverse()],
+            };
+
+            let next = preferences.iter()
+                .filter_map(|&direction| self.step(current_pos, direction).map(|cell| (cell, direction)))
+                .find(|&(cell, _)| self.can_pass(current_pos, cell));
+
+            match next {
+                Some((cell, direction)) => {
+                    current_pos = cell;
+                    facing = direction;
+                    traversal.push(cell);
+                },
+                None => {
+                    // Start cell is completely boxed in
+                    info!("Wall follower has nowhere to go");
+                    return None;
+                }
+            }
+        }
+
+        debug!("Wall follower traversal: {:?}", traversal);
+        info!("Goal reached after {} steps", traversal.len() - 1);
+
+        Some(traversal)
+    }
+}
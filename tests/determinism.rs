@@ -0,0 +1,89 @@
+//! Pins the mazes produced by known seeds, so any change to generation or the RNG that would alter saved seeds shows up here.
+//! If a change is meant to alter generation, update these values and say so when releasing, since it breaks reproducibility for users.
+
+use maze_generation::{Maze, Generator};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/// (width, height, seed, fingerprint)
+const KNOWN_MAZES: [(usize, usize, u64, u64); 5] = [
+    (1, 1, 0, 0x928d6e64f9ba726f),
+    (10, 10, 0, 0xd1c6b2c8efc93315),
+    (10, 10, 1, 0x4c4d74bbd13a8976),
+    (31, 17, 42, 0xf795edd34c36aba7),
+    (64, 64, 12345, 0x524a1be7a01db63c),
+];
+
+/// (width, height, seed, region size, fingerprint) for `Maze::from_seed_regions`, which `from_seed_parallel` must match
+const KNOWN_REGION_MAZES: [(usize, usize, u64, usize, u64); 4] = [
+    (1, 1, 0, 1, 0x928d6e64f9ba726f),
+    (10, 10, 0, 3, 0xcd7c7908caf3f276),
+    (31, 17, 42, 8, 0x749106ac3a7426ed),
+    (64, 64, 12345, 16, 0x06e0528223762b82),
+];
+
+#[test]
+fn seeds_give_known_mazes() {
+    for &(width, height, seed, fingerprint) in KNOWN_MAZES.iter() {
+        let maze = Maze::from_seed(width, height, seed).unwrap();
+        assert_eq!(maze.fingerprint(), fingerprint, "{}x{} maze from seed {} changed", width, height, seed);
+    }
+}
+
+#[test]
+fn seeded_maze_renders_as_known_text() {
+    let maze = Maze::from_seed(4, 3, 7).unwrap();
+    let expected = "\
+█▀▀▀▀▀█▀█
+█▀█ █▀▀ █
+█ ▀ ▀▀▀ █
+▀▀▀▀▀▀▀▀▀
+";
+    assert_eq!(maze.to_string(), expected);
+}
+
+#[test]
+fn same_seed_gives_same_maze() {
+    assert_eq!(Maze::from_seed(40, 25, 99).unwrap(), Maze::from_seed(40, 25, 99).unwrap());
+}
+
+#[test]
+fn generator_matches_from_seed() {
+    for &(width, height, seed, fingerprint) in KNOWN_MAZES.iter() {
+        assert_eq!(Generator::from_seed(seed).generate(width, height).unwrap().fingerprint(), fingerprint);
+
+        let mut maze = Maze::from_seed(3, 3, 0).unwrap();
+        Generator::from_seed(seed).generate_into(&mut maze, width, height).unwrap();
+        assert_eq!(maze.fingerprint(), fingerprint);
+    }
+}
+
+#[test]
+fn generation_trace_matches_from_seed() {
+    for &(width, height, seed, fingerprint) in KNOWN_MAZES.iter() {
+        let trace = Maze::generation_trace(width, height, ChaCha8Rng::seed_from_u64(seed)).unwrap();
+        assert_eq!(trace.into_maze().fingerprint(), fingerprint);
+    }
+}
+
+#[test]
+fn region_seeds_give_known_mazes() {
+    for &(width, height, seed, region_size, fingerprint) in KNOWN_REGION_MAZES.iter() {
+        let maze = Maze::from_seed_regions(width, height, seed, region_size).unwrap();
+        assert_eq!(maze.fingerprint(), fingerprint, "{}x{} maze from seed {} in regions of {} changed", width, height, seed, region_size);
+
+        #[cfg(feature = "parallel")]
+        assert_eq!(Maze::from_seed_parallel(width, height, seed, region_size).unwrap(), maze);
+    }
+}
+
+#[test]
+fn sweeps_cover_every_algorithm_and_repeat_exactly() {
+    let results = maze_generation::sweep(12, 9, 4, 3).unwrap();
+    assert_eq!(results.iter().map(|result| result.algorithm).collect::<Vec<_>>(), maze_generation::Algorithm::ALL);
+
+    let again = maze_generation::sweep(12, 9, 4, 3).unwrap();
+    for (first, second) in results.iter().zip(&again) {
+        assert_eq!((first.dead_ends, first.solution_length), (second.dead_ends, second.solution_length));
+    }
+}
@@ -0,0 +1,272 @@
+//! Mazes saved by every released format version must keep loading. Each version gets a pinned file here that's never updated,
+//! only added to when the format changes.
+
+use maze_generation::{Maze, MazeError, EntrancePlacement, Opening, Direction, Algorithm, MazeMetadata};
+
+/// `Maze::from_seed(3, 2, 7)` with farthest entrances, as written by format version 1
+const VERSION_1: [u8; 39] = [
+    b'M', b'A', b'Z', b'E', 1, 0,
+    3, 0, 0, 0, 2, 0, 0, 0,
+    22, 26, 28, 21, 22, 25,
+    1, 0, 0, 0, 0, 1, 0, 0, 0, 2, 1, 0, 0, 0, 1, 0, 0, 0, 2,
+];
+
+/// The same maze with an extra opening labeled "north" at the top right, as written by format version 2
+const VERSION_2: [u8; 61] = [
+    b'M', b'A', b'Z', b'E', 2, 0,
+    3, 0, 0, 0, 2, 0, 0, 0,
+    22, 26, 29, 21, 22, 25,
+    1, 0, 0, 0, 0, 1, 0, 0, 0, 2, 1, 0, 0, 0, 1, 0, 0, 0, 2,
+    1, 0, 0, 0, 5, 0, 0, 0, b'n', b'o', b'r', b't', b'h', 2, 0, 0, 0, 0, 0, 0, 0, 0,
+];
+
+/// The same maze again with a portal linking the top left and bottom right cells, as written by format version 3
+const VERSION_3: [u8; 81] = [
+    b'M', b'A', b'Z', b'E', 3, 0,
+    3, 0, 0, 0, 2, 0, 0, 0,
+    22, 26, 29, 21, 22, 25,
+    1, 0, 0, 0, 0, 1, 0, 0, 0, 2, 1, 0, 0, 0, 1, 0, 0, 0, 2,
+    1, 0, 0, 0, 5, 0, 0, 0, b'n', b'o', b'r', b't', b'h', 2, 0, 0, 0, 0, 0, 0, 0, 0,
+    1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 1, 0, 0, 0,
+];
+
+/// The same maze again with the passage between the top two left cells made one-way to the right, as written by format version 4
+const VERSION_4: [u8; 101] = [
+    b'M', b'A', b'Z', b'E', 4, 0,
+    3, 0, 0, 0, 2, 0, 0, 0,
+    22, 26, 29, 21, 22, 25,
+    1, 0, 0, 0, 0, 1, 0, 0, 0, 2, 1, 0, 0, 0, 1, 0, 0, 0, 2,
+    1, 0, 0, 0, 5, 0, 0, 0, b'n', b'o', b'r', b't', b'h', 2, 0, 0, 0, 0, 0, 0, 0, 0,
+    1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 1, 0, 0, 0,
+    1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0,
+];
+
+/// The same maze again with "treasure" and "trap" tags on the bottom middle cell and "spawn" on the top right, as written by format version 5
+const VERSION_5: [u8; 158] = [
+    b'M', b'A', b'Z', b'E', 5, 0,
+    3, 0, 0, 0, 2, 0, 0, 0,
+    22, 26, 29, 21, 22, 25,
+    1, 0, 0, 0, 0, 1, 0, 0, 0, 2, 1, 0, 0, 0, 1, 0, 0, 0, 2,
+    1, 0, 0, 0, 5, 0, 0, 0, b'n', b'o', b'r', b't', b'h', 2, 0, 0, 0, 0, 0, 0, 0, 0,
+    1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 1, 0, 0, 0,
+    1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0,
+    2, 0, 0, 0,
+    1, 0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0,
+    8, 0, 0, 0, b't', b'r', b'e', b'a', b's', b'u', b'r', b'e', 4, 0, 0, 0, b't', b'r', b'a', b'p',
+    2, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 5, 0, 0, 0, b's', b'p', b'a', b'w', b'n',
+];
+
+/// `Maze::from_seed(3, 2, 7)` as written by `to_raw`, whose layout other tools depend on
+const RAW: [u8; 40] = [
+    b'M', b'Z', b'R', b'W', 1, 0, 5, 12,
+    3, 0, 0, 0, 0, 0, 0, 0,
+    2, 0, 0, 0, 0, 0, 0, 0,
+    1, 0, 0, 0, 0, 0, 0, 0,
+    86, 243, 40, 51, 0, 0, 0, 0,
+];
+
+/// `write_metadata_json` for `version_1_maze` made with Wilson's algorithm from seed 7
+const METADATA: &str = r#"{
+  "width": 3,
+  "height": 2,
+  "algorithm": "wilson",
+  "seed": "7",
+  "fingerprint": "de394e002236f91e",
+  "entrance": { "x": 0, "y": 1, "side": "bottom" },
+  "exit": { "x": 1, "y": 1, "side": "bottom" },
+  "solution_length": 6
+}
+"#;
+
+fn version_1_maze() -> Maze {
+    let mut maze = Maze::from_seed(3, 2, 7).unwrap();
+    maze.open_entrances(EntrancePlacement::At(
+        Opening { cell: (0, 1), side: Direction::Down },
+        Opening { cell: (1, 1), side: Direction::Down },
+    )).unwrap();
+    maze
+}
+
+#[test]
+fn version_1_still_loads() {
+    let maze = Maze::from_bytes(&VERSION_1).unwrap();
+    assert_eq!(maze, version_1_maze());
+    assert_eq!(maze.openings().count(), 0);
+}
+
+#[test]
+fn version_2_still_loads() {
+    let maze = Maze::from_bytes(&VERSION_2).unwrap();
+
+    let mut expected = version_1_maze();
+    expected.add_opening("north", Opening { cell: (2, 0), side: Direction::Up }).unwrap();
+    assert_eq!(maze, expected);
+}
+
+#[test]
+fn version_3_still_loads() {
+    let maze = Maze::from_bytes(&VERSION_3).unwrap();
+
+    let mut expected = Maze::from_bytes(&VERSION_2).unwrap();
+    expected.add_portal((0, 0), (2, 1)).unwrap();
+    assert_eq!(maze, expected);
+}
+
+#[test]
+fn version_4_still_loads() {
+    let maze = Maze::from_bytes(&VERSION_4).unwrap();
+
+    let mut expected = Maze::from_bytes(&VERSION_3).unwrap();
+    expected.make_one_way((0, 0), (1, 0)).unwrap();
+    assert_eq!(maze, expected);
+}
+
+#[test]
+fn version_5_still_loads() {
+    let maze = Maze::from_bytes(&VERSION_5).unwrap();
+
+    let mut expected = Maze::from_bytes(&VERSION_4).unwrap();
+    expected.add_tag((1, 1), "treasure").unwrap();
+    expected.add_tag((1, 1), "trap").unwrap();
+    expected.add_tag((2, 0), "spawn").unwrap();
+    assert_eq!(maze, expected);
+    assert_eq!(maze.tags((1, 1)), ["treasure", "trap"]);
+}
+
+#[test]
+fn mazes_round_trip() {
+    for seed in 0..20 {
+        let mut maze = Maze::from_seed(1 + seed as usize, 17, seed).unwrap();
+        assert_eq!(Maze::from_bytes(&maze.to_bytes()).unwrap(), maze);
+
+        maze.open_entrances(EntrancePlacement::Farthest);
+        assert_eq!(Maze::from_bytes(&maze.to_bytes()).unwrap(), maze);
+
+        maze.add_opening("corner", Opening { cell: (0, 0), side: Direction::Left }).unwrap();
+        assert_eq!(Maze::from_bytes(&maze.to_bytes()).unwrap(), maze);
+
+        if seed > 0 {
+            maze.add_portal((0, 0), (seed as usize, 16)).unwrap();
+            assert_eq!(Maze::from_bytes(&maze.to_bytes()).unwrap(), maze);
+
+            let to = maze.neighbors((0, 0))[0];
+            maze.make_one_way((0, 0), to).unwrap();
+            assert_eq!(Maze::from_bytes(&maze.to_bytes()).unwrap(), maze);
+        }
+
+        maze.add_tag((0, 16), "start").unwrap();
+        maze.add_tag((0, 16), "").unwrap();
+        assert_eq!(Maze::from_bytes(&maze.to_bytes()).unwrap(), maze);
+    }
+}
+
+#[test]
+fn raw_layout_is_stable() {
+    let maze = Maze::from_seed(3, 2, 7).unwrap();
+    assert_eq!(maze.to_raw(), RAW);
+    assert_eq!(Maze::from_raw(&RAW).unwrap(), maze);
+}
+
+#[test]
+fn metadata_layout_is_stable() {
+    let mut json = Vec::new();
+    version_1_maze().write_metadata_json(&mut json, &MazeMetadata { algorithm: Some(Algorithm::Wilson), seed: Some(7) }).unwrap();
+    assert_eq!(String::from_utf8(json).unwrap(), METADATA);
+
+    let mut json = Vec::new();
+    Maze::from_seed(3, 2, 7).unwrap().write_metadata_json(&mut json, &MazeMetadata::default()).unwrap();
+    let json = String::from_utf8(json).unwrap();
+    for key in &["algorithm", "seed", "entrance", "exit", "solution_length"] {
+        assert!(json.contains(&format!("\"{}\": null", key)), "{} isn't null in {}", key, json);
+    }
+}
+
+#[test]
+fn raw_grids_round_trip() {
+    for seed in 0..20 {
+        let width = 1 + seed as usize * 3;
+        let maze = Maze::from_seed(width, 13, seed).unwrap();
+        assert_eq!(Maze::from_raw(&maze.to_raw()).unwrap(), maze);
+
+        let mut written = Vec::new();
+        maze.write_raw(&mut written).unwrap();
+        assert_eq!(written, maze.to_raw());
+    }
+}
+
+#[test]
+fn bad_raw_data_is_rejected() {
+    for len in 0..RAW.len() {
+        assert!(Maze::from_raw(&RAW[..len]).is_err(), "truncated to {} bytes", len);
+    }
+
+    let mut newer = RAW;
+    newer[4] = 2;
+    assert!(matches!(Maze::from_raw(&newer), Err(MazeError::UnsupportedVersion { version: 2 })));
+
+    // A seventh cell in a six cell maze
+    let mut past_the_end = RAW;
+    past_the_end[36] = 1;
+    assert!(matches!(Maze::from_raw(&past_the_end), Err(MazeError::InvalidFormat(_))));
+
+    // Closing the first cell's right side leaves its neighbor with a one way passage
+    let mut one_way = RAW;
+    one_way[32] &= !2;
+    assert!(matches!(Maze::from_raw(&one_way), Err(MazeError::InvalidFormat(_))));
+
+    let mut wrong_size = RAW;
+    wrong_size[8] = 30;
+    assert!(matches!(Maze::from_raw(&wrong_size), Err(MazeError::InvalidFormat(_))));
+}
+
+#[test]
+fn unfinished_mazes_round_trip() {
+    let calls = std::cell::Cell::new(0);
+    let cancelled = Maze::with_cancellation(20, 20, &mut rand::thread_rng(), || {
+        calls.set(calls.get() + 1);
+        calls.get() > 3
+    });
+    if let Err(MazeError::Cancelled { partial }) = cancelled {
+        assert_eq!(Maze::from_bytes(&partial.to_bytes()).unwrap(), *partial);
+    }
+}
+
+#[test]
+fn bad_data_is_rejected() {
+    let newer = [b'M', b'A', b'Z', b'E', 0xff, 0xff];
+    assert!(matches!(Maze::from_bytes(&newer), Err(MazeError::UnsupportedVersion { version: 0xffff })));
+
+    for len in 0..VERSION_1.len() {
+        assert!(matches!(Maze::from_bytes(&VERSION_1[..len]), Err(MazeError::InvalidFormat(_))), "truncated to {} bytes", len);
+    }
+
+    for len in 0..VERSION_2.len() {
+        assert!(matches!(Maze::from_bytes(&VERSION_2[..len]), Err(MazeError::InvalidFormat(_))), "truncated to {} bytes", len);
+    }
+
+    for len in 0..VERSION_4.len() {
+        assert!(matches!(Maze::from_bytes(&VERSION_4[..len]), Err(MazeError::InvalidFormat(_))), "truncated to {} bytes", len);
+    }
+
+    for len in 0..VERSION_3.len() {
+        assert!(matches!(Maze::from_bytes(&VERSION_3[..len]), Err(MazeError::InvalidFormat(_))), "truncated to {} bytes", len);
+    }
+
+    for len in 0..VERSION_5.len() {
+        assert!(matches!(Maze::from_bytes(&VERSION_5[..len]), Err(MazeError::InvalidFormat(_))), "truncated to {} bytes", len);
+    }
+
+    // A tag on a cell outside the maze
+    let mut outside = VERSION_5;
+    outside[105] = 9;
+    assert!(matches!(Maze::from_bytes(&outside), Err(MazeError::InvalidFormat(_))));
+
+    let mut trailing = VERSION_1.to_vec();
+    trailing.push(0);
+    assert!(matches!(Maze::from_bytes(&trailing), Err(MazeError::InvalidFormat(_))));
+
+    // Closing the first cell's right side leaves its neighbor with a one way passage
+    let mut one_way = VERSION_1;
+    one_way[14] &= !2;
+    assert!(matches!(Maze::from_bytes(&one_way), Err(MazeError::InvalidFormat(_))));
+}
@@ -0,0 +1,1687 @@
+//! Property checks over sizes and seeds drawn by proptest: every generated maze, and everything derived from one, must be a perfect maze.
+//! Run with `cargo test --features check-invariants` to also check the invariants after every step of generation.
+//! New checks go into these properties, or get a strategy of their own, rather than looping over fixed sizes and seeds.
+
+use maze_generation::{Maze, MazeError, Direction, EntrancePlacement, Algorithm, Constraints, Rotation, Axis, Generator, WalkStats, DirectionWeights, GenerationEvent, Provenance, KeyLayer, Dungeon, PlacementRules, SecretLayer, Violation, Manhattan, Octile, Movement, AnsiOverlay, AStarSolver, AStarSearch, Solver, Symmetry, SolveEvent, TracedSolver, Hand, Opening, BreadthFirstSolver, WallFollowerSolver, DeadEndFillingSolver, TremauxSolver};
+use proptest::prelude::*;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/// Sizes from 1x1 up to this in both directions are drawn
+const MAX_SIZE: usize = 12;
+
+prop_compose! {
+    /// Mazes from `min` to `max` cells along each side, from any seed
+    fn mazes(min: usize, max: usize)(width in min..=max, height in min..=max, seed in any::<u64>()) -> Maze {
+        Maze::from_seed(width, height, seed).unwrap()
+    }
+}
+
+fn any_maze() -> impl Strategy<Value = Maze> {
+    mazes(1, MAX_SIZE)
+}
+
+fn size() -> impl Strategy<Value = usize> {
+    1..=MAX_SIZE
+}
+
+proptest! {
+    #[test]
+    fn generated_mazes_are_perfect(maze in any_maze()) {
+        prop_assert_eq!(maze.validate(), Ok(()), "{:?}", maze);
+    }
+
+    #[test]
+    fn every_cell_is_reachable(maze in any_maze()) {
+        let (width, height) = maze.size();
+        prop_assert_eq!(maze.path_cells().count(), width * height, "{:?}", maze);
+        prop_assert_eq!(maze.regions().count(), 1, "{:?}", maze);
+    }
+
+    #[test]
+    fn entrances_keep_the_maze_perfect(mut maze in any_maze()) {
+        prop_assume!(maze.size() != (1, 1));
+        let (entrance, exit) = maze.open_entrances(EntrancePlacement::Farthest).unwrap();
+        prop_assert_eq!(maze.validate(), Ok(()), "{:?}", maze);
+        prop_assert!(maze.has_unique_shortest_path(entrance, exit), "{:?}", maze);
+    }
+
+    #[test]
+    fn astar_finds_the_same_shortest_paths_as_bfs(maze in any_maze()) {
+        let (width, height) = maze.size();
+        let goal = (width - 1, height - 1);
+        let search = maze.solve_astar_with_stats((0, 0), goal, &Manhattan, Movement::Orthogonal);
+        let path = search.path.unwrap();
+        prop_assert_eq!(&path, &maze.solve((0, 0), goal).unwrap(), "{:?}", maze);
+        prop_assert_eq!(Some(path), AStarSolver::default().solve(&maze, (0, 0), goal));
+
+        // Every cell on the path but the goal had to be expanded, and in a perfect maze each cell is only queued once
+        prop_assert!(search.cells_expanded >= maze.solve((0, 0), goal).unwrap().len() - 1);
+        prop_assert!(search.cells_expanded <= width * height);
+        prop_assert!(search.peak_open >= 1);
+    }
+
+    #[test]
+    fn astar_finds_shortest_paths_through_portals(mut maze in any_maze(), ends in prop::collection::vec((any::<usize>(), any::<usize>()), 1..4)) {
+        let (width, height) = maze.size();
+        let cell = |i: usize| (i % (width * height) / height, i % height);
+        for (a, b) in ends {
+            // Portals sharing a cell are refused, which just leaves fewer of them
+            maze.add_portal(cell(a), cell(b)).ok();
+        }
+
+        let goal = (width - 1, height - 1);
+        let shortest = maze.solve((0, 0), goal).unwrap().len();
+        prop_assert_eq!(maze.solve_astar((0, 0), goal, &Manhattan).unwrap().len(), shortest, "{:?}", maze);
+        let search = maze.solve_astar_with_stats((0, 0), goal, &Manhattan, Movement::Orthogonal);
+        prop_assert_eq!(search.path.unwrap().len(), shortest);
+        prop_assert_eq!(AStarSolver::default().solve(&maze, (0, 0), goal).unwrap().len(), shortest);
+    }
+
+    #[test]
+    fn astar_expands_each_cell_at_most_once(mut maze in mazes(2, MAX_SIZE), loops in 0..30usize, seed in any::<u64>()) {
+        // Loops and diagonal moves give cells more than one way in, so cheaper routes turn up after a cell is first queued
+        maze.add_loops(loops, &mut ChaCha8Rng::seed_from_u64(seed));
+        // Walling in the goal makes the search expand everything it can reach before giving up
+        let (width, height) = maze.size();
+        let goal = (width - 1, height - 1);
+        for next in maze.neighbors(goal) {
+            maze.fill(goal, next).unwrap();
+        }
+        let reachable = maze.distances_from((0, 0)).unwrap().reachable();
+        for &movement in &[Movement::Orthogonal, Movement::Diagonal] {
+            let search = maze.solve_astar_with_stats((0, 0), goal, &Octile, movement);
+            prop_assert_eq!(search.path, None);
+            prop_assert_eq!(search.cells_expanded, reachable, "{:?}", movement);
+        }
+    }
+
+    #[test]
+    fn distance_maps_match_shortest_paths(maze in any_maze()) {
+        let (width, height) = maze.size();
+        let distances = maze.distances_from((0, 0)).unwrap();
+        prop_assert_eq!(distances.reachable(), width * height);
+        for x in 0..width {
+            for y in 0..height {
+                prop_assert_eq!(distances.get((x, y)), Some(maze.solve((0, 0), (x, y)).unwrap().len() - 1), "{:?}", maze);
+            }
+        }
+
+        let (farthest, length) = distances.farthest();
+        prop_assert_eq!(distances.get(farthest), Some(length));
+        prop_assert!(length < maze.longest_path().unwrap().len());
+    }
+
+    #[test]
+    fn diameter_ends_are_farthest_apart(mut maze in any_maze()) {
+        let (start, goal, length) = maze.diameter().unwrap();
+        prop_assert_eq!(maze.longest_path().unwrap().len(), length + 1);
+        prop_assert_eq!(maze.distances_from(start).unwrap().farthest().1, length, "{:?}", maze);
+
+        prop_assert_eq!(maze.tag_diameter(), Some((start, goal)));
+        prop_assert!(maze.tags(start).iter().any(|tag| tag == "start"));
+        prop_assert!(maze.tags(goal).iter().any(|tag| tag == "goal"));
+    }
+
+    #[test]
+    fn stats_measure_corridors_and_solutions(maze in any_maze()) {
+        let stats = maze.stats();
+        let solution = maze.longest_path().unwrap().len() - 1;
+        prop_assert_eq!(stats.junctions, stats.t_junctions + stats.crossroads);
+        prop_assert!(stats.longest_corridor as f64 >= stats.average_corridor_length);
+        // Corridors are paths in a perfect maze, so none can be longer than the longest one
+        prop_assert!(stats.longest_corridor <= solution, "{:?}", maze);
+        prop_assert_eq!(stats.solution_length, Some(solution));
+    }
+
+    #[test]
+    fn difficulty_scores_stay_in_range(maze in any_maze()) {
+        let difficulty = maze.difficulty().unwrap();
+        prop_assert!((0.0..=100.0).contains(&difficulty.score), "{:?}", difficulty);
+
+        let depths = maze.dead_end_depths();
+        let dead_ends: usize = depths.iter().sum();
+        if dead_ends > 0 {
+            let total: usize = depths.iter().enumerate().map(|(depth, &count)| depth * count).sum();
+            prop_assert_eq!(difficulty.mean_dead_end_depth, total as f64 / dead_ends as f64);
+        }
+    }
+
+    #[test]
+    fn braiding_removes_dead_ends(maze in any_maze(), seed in any::<u64>()) {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let dead_ends = maze.stats().dead_ends;
+
+        let mut unchanged = maze.clone();
+        prop_assert_eq!(unchanged.braid(0.0, &mut rng).unwrap(), 0);
+        prop_assert_eq!(&unchanged, &maze);
+
+        let mut half = maze.clone();
+        let removed = half.braid(0.5, &mut rng).unwrap();
+        prop_assert_eq!(half.stats().dead_ends, dead_ends - removed);
+        prop_assert!(removed <= (dead_ends as f64 * 0.5).round() as usize + 1);
+
+        // Every dead end has a wall to knock through unless the maze is a single row or column
+        let (width, height) = maze.size();
+        let mut braided = maze.clone();
+        braided.braid(1.0, &mut rng).unwrap();
+        if width > 1 && height > 1 {
+            prop_assert_eq!(braided.stats().dead_ends, 0, "{:?}", maze);
+        }
+        prop_assert_eq!(braided.distances_from((0, 0)).unwrap().reachable(), width * height);
+    }
+
+    #[test]
+    fn loops_add_alternative_routes(maze in any_maze(), seed in any::<u64>()) {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let (width, height) = maze.size();
+        let goal = (width - 1, height - 1);
+        prop_assert_eq!(maze.count_paths((0, 0), goal, 10), 1);
+
+        let mut looped = maze.clone();
+        let walls = width * (height - 1) + (width - 1) * height - (width * height - 1);
+        prop_assert_eq!(looped.add_loops(2, &mut rng), walls.min(2));
+        let paths = looped.count_paths((0, 0), goal, 20);
+        prop_assert_eq!(paths, looped.k_shortest_paths((0, 0), goal, 20).len(), "{:?}", maze);
+
+        // With every wall gone, every cell is joined to all of its neighbors
+        prop_assert_eq!(looped.add_loops(usize::MAX, &mut rng), walls - walls.min(2));
+        prop_assert_eq!(looped.stats().crossroads, width.saturating_sub(2) * height.saturating_sub(2));
+    }
+
+    #[test]
+    fn braided_mazes_only_pass_the_braided_check(maze in any_maze(), seed in any::<u64>()) {
+        prop_assert_eq!(maze.validate_braided(), Ok(()));
+
+        let mut braided = maze.clone();
+        if braided.braid(1.0, &mut ChaCha8Rng::seed_from_u64(seed)).unwrap() > 0 {
+            prop_assert!(braided.validate().unwrap_err().iter().all(|violation| matches!(violation, Violation::Loop(..) | Violation::OpenBlock(..))));
+            prop_assert!(braided.validate_braided().err().unwrap_or_default().iter().all(|violation| matches!(violation, Violation::OpenBlock(..))));
+        }
+    }
+
+    #[test]
+    fn rooms_open_up_without_disconnecting_anything(mut maze in mazes(3, MAX_SIZE), seed in any::<u64>()) {
+        let before = maze.clone();
+        let opened = maze.carve_room(1, 1, 3, 3).unwrap();
+        // A 2x2 room has 4 passages, and a perfect maze can't already have all of them
+        prop_assert!(opened > 0 && opened <= 4, "{:?}", before);
+        prop_assert!(maze.validate_braided().err().unwrap_or_default().iter().all(|violation| matches!(violation, Violation::OpenBlock(..))));
+        prop_assert!(maze.validate_braided().is_err());
+        prop_assert!(Direction::ALL.iter().all(|&direction| before.can_move((1, 1), direction) <= maze.can_move((1, 1), direction)));
+        prop_assert_eq!(maze.carve_room(1, 1, 3, 3).unwrap(), 0);
+
+        let mut arena = before.clone();
+        let rooms = arena.carve_rooms(4, 1, 3, &mut ChaCha8Rng::seed_from_u64(seed)).unwrap();
+        prop_assert!(!rooms.is_empty() && rooms.len() <= 4);
+        prop_assert_eq!(arena.regions().sizes.len(), 1);
+        for (i, a) in rooms.iter().enumerate() {
+            for b in &rooms[i + 1..] {
+                prop_assert!(a.1.0 < b.0.0 || b.1.0 < a.0.0 || a.1.1 < b.0.1 || b.1.1 < a.0.1, "{:?} touches {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn symmetric_mazes_match_their_own_reflections(size in 1..=MAX_SIZE / 2, seed in any::<u64>()) {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let passages = |maze: &Maze| -> usize {
+            let (width, height) = maze.size();
+            (0..width).flat_map(|x| (0..height).map(move |y| (x, y)))
+                .map(|cell| [Direction::Right, Direction::Down].iter().filter(|&&direction| maze.can_move(cell, direction)).count())
+                .sum()
+        };
+        let (width, odd, even) = (2 * size, 2 * size - 1, 2 * size);
+
+        let mirror = Maze::symmetric(width, odd, Symmetry::Mirror, &mut rng).unwrap();
+        prop_assert_eq!(&mirror.mirrored(Axis::Vertical), &mirror);
+        prop_assert_eq!(mirror.validate(), Ok(()));
+
+        let half_turn = Maze::symmetric(width, odd, Symmetry::HalfTurn, &mut rng).unwrap();
+        prop_assert_eq!(&half_turn.rotated(Rotation::Half), &half_turn);
+        prop_assert_eq!(half_turn.validate(), Ok(()));
+
+        // Four-fold mazes have one loop, so one more passage than a perfect maze
+        let mirror_both = Maze::symmetric(width, even, Symmetry::MirrorBoth, &mut rng).unwrap();
+        prop_assert_eq!(&mirror_both.mirrored(Axis::Vertical), &mirror_both);
+        prop_assert_eq!(&mirror_both.mirrored(Axis::Horizontal), &mirror_both);
+        prop_assert!(size == 1 || mirror_both.validate_braided().is_ok());
+        prop_assert_eq!(passages(&mirror_both), width * even);
+
+        let quarter_turn = Maze::symmetric(width, width, Symmetry::QuarterTurn, &mut rng).unwrap();
+        prop_assert_eq!(&quarter_turn.rotated(Rotation::Quarter), &quarter_turn);
+        prop_assert!(size == 1 || quarter_turn.validate_braided().is_ok());
+        prop_assert_eq!(passages(&quarter_turn), width * width);
+    }
+
+    #[test]
+    fn provenance_covers_every_cell_once(width in size(), height in size(), seed in any::<u64>()) {
+        let (maze, provenance) = Maze::with_provenance(width, height, ChaCha8Rng::seed_from_u64(seed)).unwrap();
+        prop_assert_eq!(&maze, &Maze::generation_trace(width, height, ChaCha8Rng::seed_from_u64(seed)).unwrap().into_maze());
+        prop_assert_eq!(provenance.walk_of((0, 0)), Some(0));
+        prop_assert_eq!(provenance.order_of((0, 0)), Some(0));
+        prop_assert_eq!(provenance.walk_of((width, 0)), None);
+
+        // Every cell is added exactly once, and each walk grows out of a cell that was added before it
+        let mut orders = vec![false; width * height];
+        for x in 0..width {
+            for y in 0..height {
+                let (walk, order) = (provenance.walk_of((x, y)).unwrap(), provenance.order_of((x, y)).unwrap());
+                prop_assert!(walk < provenance.walk_count());
+                prop_assert!(!orders[order], "order {} given twice", order);
+                orders[order] = true;
+                if walk > 0 {
+                    let earlier = maze.neighbors((x, y)).into_iter().any(|next| provenance.order_of(next).unwrap() < order);
+                    prop_assert!(earlier, "({}, {}) isn't joined to an earlier cell", x, y);
+                }
+            }
+        }
+
+        let events: Vec<GenerationEvent> = Maze::generation_trace(width, height, ChaCha8Rng::seed_from_u64(seed)).unwrap().collect();
+        let walks = events.iter().filter(|event| matches!(event, GenerationEvent::WalkStarted(_))).count();
+        prop_assert_eq!(provenance.walk_count(), walks + 1);
+        prop_assert_eq!(Provenance::from_events((width, height), &events), provenance);
+    }
+}
+
+#[test]
+fn searches_outside_the_maze_find_nothing() {
+    let maze = Maze::from_seed(4, 4, 0).unwrap();
+    let missing = maze.solve_astar_with_stats((0, 0), (4, 0), &Manhattan, Movement::Orthogonal);
+    assert_eq!(missing, AStarSearch { path: None, cells_expanded: 0, peak_open: 0 });
+    assert_eq!(maze.distances_from((4, 0)), None);
+    assert_eq!(maze.distances_from((0, 0)).unwrap().get((9, 9)), None);
+}
+
+#[test]
+fn portals_across_the_maze_keep_astar_shortest() {
+    let mut maze = Maze::from_seed(20, 20, 19).unwrap();
+    maze.add_portal((0, 0), (19, 19)).unwrap();
+    for &start in &[(0, 0), (19, 0), (0, 19), (19, 19), (10, 10)] {
+        for goal in maze.path_cells() {
+            let shortest = maze.solve(start, goal).unwrap().len();
+            assert_eq!(maze.solve_astar(start, goal, &Manhattan).unwrap().len(), shortest, "{:?} to {:?}", start, goal);
+        }
+    }
+}
+
+#[test]
+fn loops_make_routes_countable() {
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+    let mut maze = Maze::from_seed(6, 6, 0).unwrap();
+    maze.add_loops(4, &mut rng);
+    assert!(maze.count_paths((0, 0), (5, 5), 100) > 1);
+    assert_eq!(maze.count_paths((0, 0), (5, 5), 2), 2);
+    assert_eq!(maze.count_paths((0, 0), (6, 6), 2), 0);
+
+    let mut maze = Maze::from_seed(4, 4, 0).unwrap();
+    assert!(matches!(maze.braid(1.5, &mut rng), Err(MazeError::OutOfRange { .. })));
+}
+
+#[test]
+fn disconnected_mazes_fail_the_braided_check() {
+    let mut maze = Maze::from_seed(4, 4, 0).unwrap();
+    let (cell, neighbor) = (maze.neighbors((0, 0))[0], (0, 0));
+    maze.fill(cell, neighbor).unwrap();
+    assert!(matches!(maze.validate_braided().unwrap_err()[..], [Violation::Disconnected { .. }]));
+}
+
+#[test]
+fn rooms_outside_the_maze_are_refused() {
+    let mut maze = Maze::from_seed(4, 4, 0).unwrap();
+    assert!(matches!(maze.carve_room(2, 2, 5, 3), Err(MazeError::InvalidRegion { .. })));
+    assert!(matches!(maze.carve_rooms(1, 3, 2, &mut ChaCha8Rng::seed_from_u64(0)), Err(MazeError::OutOfRange { .. })));
+}
+
+#[test]
+fn symmetric_mazes_need_sizes_that_fold() {
+    let mut rng = ChaCha8Rng::seed_from_u64(6);
+    assert!(matches!(Maze::symmetric(5, 4, Symmetry::Mirror, &mut rng), Err(MazeError::OutOfRange { setting: "width", .. })));
+    assert!(matches!(Maze::symmetric(4, 4, Symmetry::HalfTurn, &mut rng), Err(MazeError::OutOfRange { setting: "height", .. })));
+    assert!(matches!(Maze::symmetric(4, 6, Symmetry::QuarterTurn, &mut rng), Err(MazeError::OutOfRange { setting: "height", .. })));
+}
+
+#[test]
+fn animated_svgs_draw_every_path_pixel_once() {
+    let (mut maze, provenance) = Maze::with_provenance(11, 7, ChaCha8Rng::seed_from_u64(6)).unwrap();
+    maze.open_entrances(EntrancePlacement::Farthest).unwrap();
+    let svg = maze.to_animated_svg(&provenance, 5.0, 20).unwrap();
+    assert!(svg.starts_with("<svg") && svg.ends_with("</svg>\n"));
+
+    let tiles = maze.scale(1).unwrap();
+    let mut drawn = vec![vec![false; tiles[0].len()]; tiles.len()];
+    let mut last_delay = 0.0;
+    for line in svg.lines().filter(|line| line.starts_with("<path")) {
+        let delay: f64 = line.split("animation-delay:").nth(1).unwrap().split('s').next().unwrap().parse().unwrap();
+        assert!(delay >= last_delay && delay < 5.0, "delay {} after {}", delay, last_delay);
+        last_delay = delay;
+        for pixel in line.split("d=\"").nth(1).unwrap().split('M').skip(1) {
+            let mut coordinates = pixel.split('h').next().unwrap().split(' ').map(|n| n.parse::<usize>().unwrap());
+            let (x, y) = (coordinates.next().unwrap(), coordinates.next().unwrap());
+            assert!(tiles[x][y].is_path() && !drawn[x][y], "pixel ({}, {})", x, y);
+            drawn[x][y] = true;
+        }
+    }
+    assert!(last_delay > 0.0);
+    for (x, column) in tiles.iter().enumerate() {
+        for (y, tile) in column.iter().enumerate() {
+            assert_eq!(drawn[x][y], tile.is_path(), "pixel ({}, {})", x, y);
+        }
+    }
+
+    assert!(matches!(maze.to_animated_svg(&provenance, 5.0, 0), Err(MazeError::OutOfRange { .. })));
+    assert!(matches!(maze.to_animated_svg(&provenance, 0.0, 20), Err(MazeError::OutOfRange { .. })));
+    let (_, other) = Maze::with_provenance(7, 11, ChaCha8Rng::seed_from_u64(6)).unwrap();
+    assert!(matches!(maze.to_animated_svg(&other, 5.0, 20), Err(MazeError::SizeMismatch { .. })));
+}
+
+#[test]
+fn ansi_previews_keep_the_shape_of_the_maze() {
+    // The escape codes in a line, and the characters left once they're taken out
+    let split = |line: &str| {
+        let (mut codes, mut text) = (Vec::new(), String::new());
+        let mut rest = line;
+        while let Some(start) = rest.find('\x1b') {
+            text.push_str(&rest[..start]);
+            let end = start + rest[start..].find('m').unwrap();
+            codes.push(rest[start + 2..end].to_string());
+            rest = &rest[end + 1..];
+        }
+        text.push_str(rest);
+        (codes, text)
+    };
+
+    let mut maze = Maze::from_seed(9, 6, 4).unwrap();
+    maze.open_entrances(EntrancePlacement::Farthest).unwrap();
+    let plain = maze.to_ansi(AnsiOverlay::Plain);
+    assert_eq!(plain.lines().count(), 7);
+    for line in plain.lines() {
+        let (codes, text) = split(line);
+        assert_eq!(text, "▀".repeat(19));
+        assert_eq!(codes.last().map(String::as_str), Some("0"));
+        assert!(codes.iter().all(|code| ["38;2;0;0;0", "38;2;255;255;255", "48;2;0;0;0", "48;2;255;255;255", "49", "0"].contains(&code.as_str())), "{:?}", codes);
+    }
+
+    let solution = maze.solution().unwrap();
+    let route = maze.to_ansi(AnsiOverlay::Route(&solution));
+    assert!(route.contains("230;40;40m"));
+    assert_eq!(split(&route).1, split(&plain).1);
+
+    let distances = maze.distances_from(solution[0]).unwrap();
+    let heatmap = maze.to_ansi(AnsiOverlay::Heatmap(&distances));
+    let shades: std::collections::HashSet<String> = heatmap.lines().flat_map(|line| split(line).0).collect();
+    assert!(shades.len() > 10, "{:?}", shades);
+}
+
+#[test]
+fn joined_and_tiled_mazes_stay_connected() {
+    let mut rng = ChaCha8Rng::seed_from_u64(8);
+    let (left, right, below) = (Maze::from_seed(5, 4, 1).unwrap(), Maze::from_seed(3, 4, 2).unwrap(), Maze::from_seed(5, 2, 3).unwrap());
+
+    // One passage between two perfect mazes keeps the result perfect
+    for &(other, side, size) in &[(&right, Direction::Right, (8, 4)), (&right, Direction::Left, (8, 4)), (&below, Direction::Down, (5, 6)), (&below, Direction::Up, (5, 6))] {
+        let joined = left.join(other, side, 1, &mut rng).unwrap();
+        assert_eq!(joined.size(), size);
+        assert_eq!(joined.validate(), Ok(()), "{:?}", side);
+    }
+
+    let two_passages = left.join(&right, Direction::Right, 3, &mut rng).unwrap();
+    let crossings = (0..4).filter(|&y| two_passages.can_move((4, y), Direction::Right)).count();
+    assert_eq!(crossings, 3);
+    assert_eq!(two_passages.regions().count(), 1);
+
+    let corner = Maze::from_seed(3, 2, 4).unwrap();
+    let tiled = Maze::tile(&[&[&left, &right], &[&below, &corner]], 2, &mut rng).unwrap();
+    assert_eq!(tiled.size(), (8, 6));
+    assert_eq!(tiled.regions().count(), 1);
+
+    assert!(matches!(left.join(&below, Direction::Right, 1, &mut rng), Err(MazeError::SizeMismatch { .. })));
+    assert!(matches!(Maze::tile(&[&[&left, &right], &[&corner, &below]], 1, &mut rng), Err(MazeError::SizeMismatch { .. })));
+    assert!(matches!(left.join(&right, Direction::Right, 0, &mut rng), Err(MazeError::OutOfRange { .. })));
+    assert!(matches!(left.join(&right, Direction::Right, 5, &mut rng), Err(MazeError::OutOfRange { .. })));
+    assert!(matches!(Maze::tile(&[], 1, &mut rng), Err(MazeError::InvalidDimensions { .. })));
+}
+
+proptest! {
+    #[test]
+    fn every_solver_finds_the_only_route(maze in any_maze(), start in any::<usize>(), goal in any::<usize>()) {
+        let (width, height) = maze.size();
+        let cell = |i: usize| (i % (width * height) / height, i % height);
+        let (start, goal) = (cell(start), cell(goal));
+        let path = maze.solve(start, goal).unwrap();
+        prop_assert_eq!(maze.solve_with(&BreadthFirstSolver, start, goal), Some(path.clone()));
+        prop_assert_eq!(maze.solve_with(&DeadEndFillingSolver, start, goal), Some(path.clone()), "{:?}", maze);
+        prop_assert_eq!(maze.solve_with(&TremauxSolver, start, goal), Some(path.clone()), "{:?}", maze);
+
+        // In a perfect maze everything off the route is a dead end
+        let fill = maze.solve_dead_end_filling(start, goal).unwrap();
+        let filled = fill.filled.iter().flatten().filter(|&&filled| filled).count();
+        prop_assert_eq!(filled, width * height - path.len());
+
+        // Passages on the route are walked once, and dead ends there and back
+        let tremaux = maze.solve_tremaux(start, goal).unwrap();
+        prop_assert_eq!(tremaux.marks.values().filter(|&&marks| marks == 1).count(), path.len() - 1);
+        prop_assert!(tremaux.marks.values().all(|&marks| marks == 1 || marks == 2));
+
+        for &hand in &[Hand::Left, Hand::Right] {
+            let traversal = maze.solve_with(&WallFollowerSolver(hand), start, goal).unwrap();
+            prop_assert_eq!(traversal.first(), Some(&start));
+            prop_assert_eq!(traversal.last(), Some(&goal));
+            prop_assert!(traversal.windows(2).all(|step| maze.neighbors(step[0]).contains(&step[1])), "{:?} {:?}", hand, maze);
+            prop_assert!(path.iter().all(|cell| traversal.contains(cell)), "{:?} {:?}", hand, maze);
+        }
+    }
+
+    #[test]
+    fn entrances_open_on_the_requested_sides(mut maze in any_maze(), sides in (prop::sample::select(Direction::ALL.to_vec()), prop::sample::select(Direction::ALL.to_vec()))) {
+        let (width, height) = maze.size();
+        let on_side = |cell: (usize, usize), side: Direction| match side {
+            Direction::Up => cell.1 == 0,
+            Direction::Right => cell.0 == width - 1,
+            Direction::Down => cell.1 == height - 1,
+            Direction::Left => cell.0 == 0,
+        };
+        // A side only one cell long can't hold both ends
+        if let Some((entrance, exit)) = maze.open_entrances(EntrancePlacement::Sides(sides.0, sides.1)) {
+            prop_assert!(on_side(entrance, sides.0) && on_side(exit, sides.1), "{:?}", maze);
+            let (entrance_opening, exit_opening) = maze.entrances().unwrap();
+            prop_assert_eq!((entrance_opening.side, exit_opening.side), sides);
+            prop_assert_eq!(maze.validate(), Ok(()), "{:?}", maze);
+        }
+    }
+
+    #[test]
+    fn labeled_openings_are_solved_between(mut maze in any_maze(), y in any::<usize>()) {
+        let (width, height) = maze.size();
+        let (door, window) = (Opening { cell: (0, y % height), side: Direction::Left }, Opening { cell: (width - 1, height - 1 - y % height), side: Direction::Right });
+        maze.add_opening("door", door).unwrap();
+        maze.add_opening("window", window).unwrap();
+        prop_assert_eq!(maze.solve_openings("door", "window"), maze.solve(door.cell, window.cell));
+        prop_assert_eq!(maze.solve_openings("door", "chimney"), None);
+
+        prop_assert_eq!(maze.remove_opening("door"), Some(door));
+        prop_assert_eq!(maze.opening("door"), None);
+        prop_assert_eq!(maze.solve_openings("door", "window"), None);
+        prop_assert_eq!(maze.validate(), Ok(()), "{:?}", maze);
+    }
+
+    #[test]
+    fn transformations_undo_each_other(mut maze in any_maze()) {
+        prop_assume!(maze.size() != (1, 1));
+        maze.open_entrances(EntrancePlacement::Farthest).unwrap();
+        let (width, height) = maze.size();
+
+        let quarter = maze.rotated(Rotation::Quarter);
+        prop_assert_eq!(quarter.size(), (height, width));
+        prop_assert_eq!(&quarter.rotated(Rotation::Quarter), &maze.rotated(Rotation::Half));
+        prop_assert_eq!(&quarter.rotated(Rotation::ThreeQuarters), &maze, "{:?}", maze);
+        prop_assert_eq!(&maze.rotated(Rotation::Half).rotated(Rotation::Half), &maze);
+        for &axis in &[Axis::Horizontal, Axis::Vertical] {
+            prop_assert_eq!(&maze.mirrored(axis).mirrored(axis), &maze, "{:?}", axis);
+        }
+        prop_assert_eq!(&maze.transposed().transposed(), &maze);
+        // A half turn is the same as flipping both ways
+        prop_assert_eq!(maze.mirrored(Axis::Horizontal).mirrored(Axis::Vertical), maze.rotated(Rotation::Half));
+    }
+
+    #[test]
+    fn constraints_every_maze_meets_pass_first_time(width in size(), height in size(), fraction in 0.0..=1.0, seed in any::<u64>()) {
+        // The route between opposite corners alone has this many cells, so the longest path always does
+        let constraints = Constraints { min_solution_length: Some(width + height - 1), ..Default::default() };
+        let (maze, attempts) = Maze::generate_until_with_rng(width, height, Algorithm::Wilson, &constraints, 1, &mut ChaCha8Rng::seed_from_u64(seed)).unwrap();
+        prop_assert_eq!(attempts, 1);
+        prop_assert!(constraints.is_met_by(&maze));
+
+        let difficulty = maze.difficulty().unwrap();
+        prop_assert_eq!(difficulty.solution_length, maze.longest_path().unwrap().len());
+        let met = difficulty.solution_length as f64 >= fraction * (width * height) as f64;
+        prop_assert_eq!(Constraints { min_solution_fraction: Some(fraction), ..Default::default() }.is_met_by(&maze), met);
+    }
+
+    #[test]
+    fn distance_matrices_match_shortest_paths(maze in mazes(1, 6)) {
+        let matrix = maze.distance_matrix(None);
+        let (width, height) = maze.size();
+        prop_assert_eq!(matrix.cells.len(), width * height);
+        for (row, &from) in matrix.cells.iter().enumerate() {
+            for (col, &to) in matrix.cells.iter().enumerate() {
+                prop_assert_eq!(matrix.get(row, col), Some(maze.solve(from, to).unwrap().len() - 1));
+                prop_assert_eq!(matrix.get(row, col), matrix.get(col, row));
+            }
+        }
+    }
+
+    #[test]
+    fn fills_never_disconnect_a_perfect_maze(mut maze in mazes(2, MAX_SIZE), i in any::<usize>()) {
+        let (width, height) = maze.size();
+        let cell = (i % (width * height) / height, i % height);
+        let next = maze.neighbors(cell)[0];
+        let before = maze.clone();
+        let refused = matches!(maze.fill_connected(cell, next), Err(MazeError::WouldDisconnect { .. }));
+        prop_assert!(refused, "{:?}", maze);
+        prop_assert_eq!(&maze, &before);
+    }
+
+    #[test]
+    fn saved_mazes_load_back(mut maze in any_maze(), labeled in any::<bool>()) {
+        prop_assume!(maze.size() != (1, 1));
+        maze.open_entrances(EntrancePlacement::Farthest).unwrap();
+        if labeled {
+            maze.add_opening("corner", Opening { cell: (0, 0), side: Direction::Up }).unwrap();
+        }
+        prop_assert_eq!(Maze::from_bytes(&maze.to_bytes()).unwrap(), maze);
+    }
+
+    #[test]
+    fn text_mazes_keep_the_shape_of_the_maze(maze in any_maze()) {
+        let (width, height) = maze.size();
+        let text = maze.to_string();
+        // Two pixel rows to a line, and one character per pixel column
+        prop_assert_eq!(text.lines().count(), height + 1);
+        prop_assert!(text.lines().all(|line| line.chars().count() == 2 * width + 1), "{}", text);
+        prop_assert!(text.lines().next().unwrap().chars().all(|c| c == '█' || c == '▀'));
+    }
+
+    #[test]
+    fn progress_counts_up_to_every_cell(width in size(), height in size(), seed in any::<u64>()) {
+        let mut reports = Vec::new();
+        let maze = Maze::with_progress(width, height, &mut ChaCha8Rng::seed_from_u64(seed), |progress| reports.push(progress.clone())).unwrap();
+        prop_assert_eq!(maze, Maze::from_seed(width, height, seed).unwrap());
+        prop_assert!(reports.windows(2).all(|pair| pair[0].cells_carved < pair[1].cells_carved && pair[0].walks_completed + 1 == pair[1].walks_completed));
+        if let Some(last) = reports.last() {
+            prop_assert!(last.is_done());
+            prop_assert_eq!(last.percent_done(), 100.0);
+        } else {
+            // A 1x1 maze is done before any walk
+            prop_assert_eq!(width * height, 1);
+        }
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn images_convert_back_to_mazes(maze in any_maze()) {
+        use core::convert::TryFrom;
+        let img = image::RgbImage::from(&maze);
+        prop_assert_eq!(&img, &maze.build_image());
+        prop_assert_eq!(Maze::try_from(&img).unwrap(), maze);
+    }
+}
+
+#[test]
+fn impossible_constraints_are_refused_up_front() {
+    let too_long = Constraints { min_solution_length: Some(17), ..Default::default() };
+    assert!(matches!(too_long.validate(4, 4), Err(MazeError::OutOfRange { setting: "min_solution_length", .. })));
+    assert!(matches!(Maze::generate_until(4, 4, &too_long, 100), Err(MazeError::OutOfRange { .. })));
+    let bad_fraction = Constraints { min_solution_fraction: Some(f64::NAN), ..Default::default() };
+    assert!(matches!(bad_fraction.validate(4, 4), Err(MazeError::OutOfRange { setting: "min_solution_fraction", .. })));
+    assert!(matches!(Constraints::default().validate(0, 4), Err(MazeError::InvalidDimensions { .. })));
+}
+
+#[test]
+fn solvers_refuse_cells_outside_the_maze() {
+    let maze = Maze::from_seed(4, 4, 0).unwrap();
+    assert_eq!(maze.solve((0, 0), (4, 0)), None);
+    assert!(maze.solve_dead_end_filling((0, 0), (4, 0)).is_none());
+    assert!(maze.solve_tremaux((9, 9), (0, 0)).is_none());
+    assert_eq!(maze.solve_wall_follower((0, 0), (0, 4), Hand::Left), None);
+}
+
+proptest! {
+    #[test]
+    fn placed_keys_have_to_be_collected_in_order(mut maze in mazes(5, MAX_SIZE), seed in any::<u64>()) {
+        maze.open_entrances(EntrancePlacement::Farthest).unwrap();
+        let solution = maze.solution().unwrap();
+        let (start, goal) = (solution[0], solution[solution.len() - 1]);
+        let layer = match maze.place_keys(3, &mut ChaCha8Rng::seed_from_u64(seed)) {
+            Ok(layer) => layer,
+            Err(MazeError::NoRoom { placed, wanted: 3, .. }) => {
+                prop_assert!(placed < 3);
+                return Ok(());
+            },
+            Err(err) => panic!("{}", err),
+        };
+
+        prop_assert_eq!(layer.doors().len(), 3);
+        prop_assert!(layer.keys().iter().all(|(cell, _)| !solution.contains(cell)), "keys go in side branches");
+        let route = maze.solve_with_keys(&layer, start, goal).unwrap().unwrap();
+        prop_assert_eq!(route.keys_collected, vec![0, 1, 2]);
+
+        // Without the keys the first door can't be passed
+        let mut locked = KeyLayer::new();
+        for &(from, to, key) in layer.doors() {
+            locked.add_door(from, to, key);
+        }
+        prop_assert_eq!(maze.solve_with_keys(&locked, start, goal).unwrap(), None);
+    }
+
+    #[test]
+    fn traced_solvers_end_with_the_only_route(maze in any_maze()) {
+        let (width, height) = maze.size();
+        let (start, goal) = ((0, 0), (width - 1, height - 1));
+        let route = maze.solve(start, goal).unwrap();
+
+        prop_assert_eq!(maze.solver_events(TracedSolver::BreadthFirst, start, goal), maze.solve_trace(start, goal).collect::<Vec<_>>());
+        // A perfect maze has exactly one route, so every solver that finds one finds the same path
+        for &solver in &[TracedSolver::WallFollower(Hand::Left), TracedSolver::WallFollower(Hand::Right), TracedSolver::Tremaux] {
+            let events = maze.solver_events(solver, start, goal);
+            prop_assert_eq!(events.last(), Some(&SolveEvent::PathFound(route.clone())), "{:?} on {}x{}", solver, width, height);
+            for event in &events[..events.len() - 1] {
+                match event {
+                    SolveEvent::Visited((x, y)) => prop_assert!(*x < width && *y < height),
+                    other => panic!("{:?} produced {:?} before the end", solver, other),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn sparse_mazes_stay_connected_and_trim_to_the_route(fraction in 0.05..=1.0, seed in any::<u64>()) {
+        let mut maze = Generator::from_seed(seed).with_fill(fraction).unwrap().generate(16, 12).unwrap();
+        let carved = maze.path_cells().count();
+        prop_assert!(carved as f64 >= fraction * 192.0, "{} cells carved for {}", carved, fraction);
+        // Every carved cell hangs off the same tree
+        prop_assert_eq!(maze.distances_from((0, 0)).unwrap().reachable(), carved);
+        let passages: usize = maze.path_cells().map(|cell| maze.neighbors(cell).len()).sum();
+        prop_assert_eq!(passages / 2, carved - 1);
+
+        maze.open_entrances(EntrancePlacement::Farthest).unwrap();
+        let route = maze.solution().unwrap();
+        let trimmed = maze.trim_dead_ends(usize::MAX);
+        prop_assert_eq!(trimmed, carved - route.len());
+        let mut remaining: Vec<_> = maze.path_cells().collect();
+        let mut expected = route.clone();
+        remaining.sort_unstable();
+        expected.sort_unstable();
+        prop_assert_eq!(remaining, expected);
+        prop_assert_eq!(maze.solution(), Some(route));
+        prop_assert_eq!(maze.trim_dead_ends(1), 0);
+    }
+
+    #[test]
+    fn carving_without_rooms_never_opens_a_block(maze in mazes(1, 6)) {
+        let (width, height) = maze.size();
+        let mut edited = maze.clone();
+        for x in 0..width {
+            for y in 0..height {
+                for &to in &[(x + 1, y), (x, y + 1)] {
+                    if to.0 >= width || to.1 >= height {
+                        continue;
+                    }
+                    let before = edited.clone();
+                    let mut carved = edited.clone();
+                    carved.carve((x, y), to).unwrap();
+                    let opens_block = carved.validate_braided().err().unwrap_or_default().iter().any(|violation| matches!(violation, Violation::OpenBlock(..)));
+                    match edited.carve_without_rooms((x, y), to) {
+                        Ok(()) => prop_assert!(!opens_block, "carving ({}, {}) to {:?} opened a block", x, y, to),
+                        Err(MazeError::WouldOpenBlock { .. }) => {
+                            prop_assert!(opens_block);
+                            prop_assert_eq!(&edited, &before);
+                        },
+                        Err(err) => panic!("{}", err),
+                    }
+                }
+            }
+        }
+        // Knocking through every wall that can go leaves no open block anywhere
+        prop_assert!(edited.validate_braided().err().unwrap_or_default().iter().all(|violation| !matches!(violation, Violation::OpenBlock(..))));
+    }
+
+    #[test]
+    fn transformations_keep_the_maze_perfect(maze in any_maze()) {
+        for transformed in [maze.rotated(Rotation::Quarter), maze.rotated(Rotation::Half), maze.mirrored(Axis::Vertical), maze.transposed()] {
+            prop_assert_eq!(transformed.validate(), Ok(()), "{:?}", maze);
+        }
+    }
+
+    #[test]
+    fn rendering_matches_the_tile_grid(mut maze in any_maze()) {
+        prop_assume!(maze.size() != (1, 1));
+        maze.open_entrances(EntrancePlacement::Farthest).unwrap();
+        let tiles = maze.scale(1).unwrap();
+        let rgba = maze.to_rgba_bytes();
+        for (y, row) in rgba.chunks(tiles.len() * 4).enumerate() {
+            for (x, pixel) in row.chunks(4).enumerate() {
+                let expected = if tiles[x][y].is_wall() { 0 } else { 255 };
+                prop_assert_eq!(pixel, [expected, expected, expected, 255], "pixel ({}, {}) of {:?}", x, y, maze);
+            }
+        }
+    }
+
+    #[test]
+    fn tile_grids_round_trip(mut maze in any_maze()) {
+        prop_assume!(maze.size() != (1, 1));
+        maze.open_entrances(EntrancePlacement::Farthest).unwrap();
+        let (entrance, exit) = maze.entrances().unwrap();
+        let tiles = maze.scale(1).unwrap();
+        let read = Maze::from_tiles(&tiles).unwrap();
+        prop_assert_eq!(read.scale(1).unwrap(), tiles, "{:?}", maze);
+        // Entrances are found by scanning, so they can come back in either order
+        let (a, b) = read.entrances().unwrap();
+        prop_assert!((a, b) == (entrance, exit) || (b, a) == (entrance, exit), "{:?}", maze);
+    }
+
+    #[test]
+    fn patched_frames_match_full_renders(width in size(), height in size(), seed in any::<u64>()) {
+        let mut trace = Maze::generation_trace(width, height, ChaCha8Rng::seed_from_u64(seed)).unwrap();
+        let mut frame = trace.maze().to_rgba_bytes();
+        while let Some(event) = trace.next() {
+            let changed = match event {
+                GenerationEvent::InitialCell(cell) => vec![cell],
+                GenerationEvent::CellsCommitted(cells) => cells,
+                _ => continue,
+            };
+            trace.maze().patch_rgba_bytes(&mut frame, &changed).unwrap();
+            prop_assert_eq!(&frame, &trace.maze().to_rgba_bytes(), "{:?}", trace.maze());
+        }
+
+        let mut maze = trace.into_maze();
+        maze.open_entrances(EntrancePlacement::Farthest);
+        let mut frame = maze.to_rgba_bytes();
+        if let Some(&to) = maze.neighbors((0, 0)).first() {
+            maze.fill((0, 0), to).unwrap();
+            maze.patch_rgba_bytes(&mut frame, &[(0, 0)]).unwrap();
+            prop_assert_eq!(frame, maze.to_rgba_bytes(), "{:?}", maze);
+        }
+    }
+
+    #[test]
+    fn rendering_into_reused_buffers_matches(mazes in prop::collection::vec(any_maze(), 1..8)) {
+        let mut rgba = Vec::new();
+        #[cfg(feature = "image")]
+        let mut img = image::RgbImage::new(0, 0);
+        // Sizes go up and down, so buffers are both grown and shrunk
+        for maze in mazes {
+            maze.to_rgba_bytes_into(&mut rgba);
+            prop_assert_eq!(&rgba, &maze.to_rgba_bytes());
+            #[cfg(feature = "image")]
+            {
+                maze.build_image_into(&mut img);
+                prop_assert_eq!(&img, &maze.build_image());
+            }
+        }
+    }
+}
+
+#[test]
+fn keys_fit_in_mazes_with_side_branches() {
+    let mut rng = ChaCha8Rng::seed_from_u64(12);
+    let mut maze = Maze::from_seed(12, 12, 0).unwrap();
+    maze.open_entrances(EntrancePlacement::Farthest).unwrap();
+    assert!(maze.place_keys(3, &mut rng).is_ok());
+
+    let maze = Maze::from_seed(3, 1, 0).unwrap();
+    assert!(matches!(maze.place_keys(1, &mut rng), Err(MazeError::NoRoom { placed: 0, .. })));
+}
+
+#[test]
+fn fill_fractions_stay_in_range() {
+    let full = Generator::from_seed(5).generate(9, 9).unwrap();
+    assert_eq!(Generator::from_seed(5).with_fill(1.0).unwrap().generate(9, 9).unwrap(), full);
+    for &fraction in &[0.0, -0.5, 1.5, f64::NAN] {
+        assert!(matches!(Generator::from_seed(5).with_fill(fraction), Err(MazeError::OutOfRange { .. })));
+    }
+}
+
+#[test]
+fn carving_without_rooms_needs_neighbors() {
+    let mut maze = Maze::from_seed(4, 4, 1).unwrap();
+    assert!(matches!(maze.carve_without_rooms((0, 0), (2, 0)), Err(MazeError::NotAdjacent { .. })));
+    assert!(matches!(maze.carve_without_rooms((3, 3), (4, 3)), Err(MazeError::NotAdjacent { .. })));
+}
+
+#[test]
+fn hilbert_mazes_are_one_corridor_through_every_cell() {
+    for order in 0..=5 {
+        let maze = Maze::hilbert(order).unwrap();
+        let side = 1 << order;
+        assert_eq!(maze.size(), (side, side));
+        assert_eq!(maze.validate(), Ok(()));
+        assert_eq!(maze.solution().unwrap().len(), side * side);
+        // Every cell but the two ends of the corridor has exactly two neighbors
+        let ends: Vec<_> = maze.path_cells().filter(|&cell| maze.neighbors(cell).len() < 2).collect();
+        if order > 0 {
+            assert_eq!(ends, vec![(0, 0), (side - 1, 0)]);
+        }
+        assert_eq!(Maze::hilbert(order).unwrap(), maze);
+    }
+    assert!(matches!(Maze::hilbert(64), Err(MazeError::OutOfRange { .. })));
+}
+
+
+#[test]
+fn ragged_tile_grids_are_refused() {
+    let mut ragged = Maze::from_seed(3, 2, 7).unwrap().scale(1).unwrap();
+    ragged[2].pop();
+    assert!(matches!(Maze::from_tiles(&ragged), Err(MazeError::InvalidImage(_))));
+    assert!(matches!(Maze::from_tiles(&[]), Err(MazeError::InvalidImage(_))));
+}
+
+#[test]
+fn patching_checks_the_frame_and_cells() {
+    let maze = Maze::from_seed(3, 2, 7).unwrap();
+    let mut frame = maze.to_rgba_bytes();
+    assert!(matches!(maze.patch_rgba_bytes(&mut frame[1..], &[(0, 0)]), Err(MazeError::FrameSizeMismatch { expected: 140, got: 139 })));
+    assert!(matches!(maze.patch_rgba_bytes(&mut frame, &[(0, 0), (3, 0)]), Err(MazeError::OutOfBounds { cell: (3, 0) })));
+    assert_eq!(frame, maze.to_rgba_bytes());
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn patched_images_match_build_image() {
+    let mut maze = Maze::from_seed(5, 4, 1).unwrap();
+    let mut img = maze.build_image();
+    maze.carve((0, 0), (1, 0)).ok();
+    maze.fill((2, 2), (2, 3)).ok();
+    maze.patch_image(&mut img, &[(0, 0), (2, 2)]).unwrap();
+    assert_eq!(img, maze.build_image());
+
+    let mut wrong_size = Maze::from_seed(4, 5, 1).unwrap().build_image();
+    assert!(matches!(maze.patch_image(&mut wrong_size, &[]), Err(MazeError::FrameSizeMismatch { .. })));
+}
+
+proptest! {
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_mazes_are_perfect(width in size(), height in size(), region_size in 1..=4usize, seed in any::<u64>()) {
+        let maze = Maze::from_seed_parallel(width, height, seed, region_size).unwrap();
+        prop_assert_eq!(maze.validate(), Ok(()), "{:?} with regions of {}", maze, region_size);
+        prop_assert_eq!(maze, Maze::from_seed_regions(width, height, seed, region_size).unwrap());
+    }
+}
+
+proptest! {
+    // Encoding and decoding images is slow next to generating, so these draw fewer mazes
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn images_generated_in_place_match_build_image(width in size(), height in size(), seed in any::<u64>()) {
+        // Start from an image of another size, so it has to be resized and cleared
+        let mut img = image::RgbImage::from_pixel(5, 3, image::Rgb([255, 255, 255]));
+        Generator::from_seed(seed).generate_image(width, height, &mut img).unwrap();
+        prop_assert_eq!(img, Maze::from_seed(width, height, seed).unwrap().build_image());
+    }
+
+    #[test]
+    fn generated_pixel_bits_match_the_maze(width in size(), height in size(), seed in any::<u64>()) {
+        let bits = Generator::from_seed(seed).generate_pixel_bits(width, height).unwrap();
+        prop_assert_eq!(bits.size(), (2 * width + 1, 2 * height + 1));
+        prop_assert_eq!(&bits, &Maze::from_seed(width, height, seed).unwrap().to_pixel_bits());
+        prop_assert!(!bits.is_path(bits.size()));
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn pixel_bits_render_like_build_image(mut maze in any_maze()) {
+        prop_assume!(maze.size() != (1, 1));
+        maze.open_entrances(EntrancePlacement::Farthest).unwrap();
+        prop_assert_eq!(maze.to_pixel_bits().to_image(), maze.build_image(), "{:?}", maze);
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn streamed_png_matches_build_image(mut maze in any_maze()) {
+        prop_assume!(maze.size() != (1, 1));
+        maze.open_entrances(EntrancePlacement::Farthest).unwrap();
+        let mut png = Vec::new();
+        maze.write_png(&mut png).unwrap();
+        prop_assert_eq!(image::load_from_memory(&png).unwrap().to_rgb8(), maze.build_image(), "{:?}", maze);
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn streamed_styled_png_matches_build_styled_image(maze in any_maze(), scale in 1..4usize) {
+        let (wall, path) = (image::Rgb([20, 40, 60]), image::Rgb([250, 240, 200]));
+        let mut png = Vec::new();
+        maze.write_styled_png(&mut png, scale, wall, path).unwrap();
+        prop_assert_eq!(image::load_from_memory(&png).unwrap().to_rgb8(), maze.build_styled_image(scale, wall, path).unwrap(), "{:?}", maze);
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn styled_markers_color_the_entrance_and_exit(mut maze in any_maze()) {
+        use maze_generation::ImageStyle;
+        prop_assume!(maze.size() != (1, 1));
+        let style = ImageStyle { corridor_width: 2, wall_thickness: 2, entrance: Some(image::Rgb([0, 0, 255])), exit: Some(image::Rgb([255, 128, 0])), ..ImageStyle::default() };
+        // Without entrances there's nothing to mark
+        prop_assert_eq!(maze.build_image_with_style(&style).unwrap(), maze.build_styled_image(2, style.wall, style.path).unwrap());
+
+        maze.open_entrances(EntrancePlacement::Farthest).unwrap();
+        let img = maze.build_image_with_style(&style).unwrap();
+        let (entrance, exit) = maze.entrances().unwrap();
+        for (opening, color) in [(entrance, style.entrance), (exit, style.exit)] {
+            let (x, y) = opening.pixel();
+            prop_assert_eq!(Some(*img.get_pixel(2 * x as u32 + 1, 2 * y as u32 + 1)), color, "{:?}", maze);
+        }
+
+        let mut png = Vec::new();
+        maze.write_png_with_style(&mut png, &style).unwrap();
+        prop_assert_eq!(image::load_from_memory(&png).unwrap().to_rgb8(), img, "{:?}", maze);
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn corridors_and_walls_get_their_own_widths(maze in any_maze(), corridor_width in 1..8usize, wall_thickness in 1..4usize) {
+        use maze_generation::ImageStyle;
+        let style = ImageStyle { corridor_width, wall_thickness, ..ImageStyle::default() };
+        // Where each pixel of build_image starts in the styled image, along one axis
+        let start = |px: u32| px.div_ceil(2) * wall_thickness as u32 + px / 2 * corridor_width as u32;
+        let plain = maze.build_image();
+        let img = maze.build_image_with_style(&style).unwrap();
+        prop_assert_eq!(img.dimensions(), (start(plain.width()), start(plain.height())));
+        for (x, y, pixel) in plain.enumerate_pixels() {
+            for (sx, sy) in (start(x)..start(x + 1)).flat_map(|sx| (start(y)..start(y + 1)).map(move |sy| (sx, sy))) {
+                prop_assert_eq!(img.get_pixel(sx, sy), pixel, "{:?}", maze);
+            }
+        }
+
+        let mut png = Vec::new();
+        maze.write_png_with_style(&mut png, &style).unwrap();
+        prop_assert_eq!(image::load_from_memory(&png).unwrap().to_rgb8(), img, "{:?}", maze);
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn flat_pictures_look_like_colored_paths(maze in any_maze(), width in 1..16u32, height in 1..16u32) {
+        use maze_generation::ImageStyle;
+        let style = ImageStyle { corridor_width: 3, wall_thickness: 1, wall: image::Rgb([10, 10, 10]), ..ImageStyle::default() };
+        // A flat picture of any size looks just like paths in its color
+        let flat = image::RgbImage::from_pixel(width, height, image::Rgb([200, 100, 50]));
+        let expected = maze.build_image_with_style(&ImageStyle { path: image::Rgb([200, 100, 50]), ..style }).unwrap();
+        prop_assert_eq!(maze.build_picture_image(&style, &flat).unwrap(), expected, "{:?}", maze);
+    }
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn styled_images_refuse_zero_widths() {
+    use maze_generation::ImageStyle;
+    let maze = Maze::from_seed(3, 3, 0).unwrap();
+    let (wall, path) = (image::Rgb([20, 40, 60]), image::Rgb([250, 240, 200]));
+    assert!(matches!(maze.write_styled_png(Vec::new(), 0, wall, path), Err(MazeError::InvalidScale)));
+    assert!(matches!(maze.build_image_with_style(&ImageStyle { wall_thickness: 0, ..ImageStyle::default() }), Err(MazeError::InvalidScale)));
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn pictures_show_through_the_paths() {
+    use maze_generation::ImageStyle;
+    let style = ImageStyle { corridor_width: 3, wall_thickness: 1, wall: image::Rgb([10, 10, 10]), ..ImageStyle::default() };
+
+
+    // A gradient keeps changing along the paths, and the walls stay the wall color
+    let maze = Maze::from_seed(8, 8, 3).unwrap();
+    let gradient = image::RgbImage::from_fn(64, 64, |x, y| image::Rgb([x as u8 * 4, y as u8 * 4, 0]));
+    let picture = maze.build_picture_image(&style, &gradient).unwrap();
+    let mask = maze.build_image_with_style(&style).unwrap();
+    let mut shades = std::collections::HashSet::new();
+    for (x, y, pixel) in mask.enumerate_pixels() {
+        if *pixel == style.wall {
+            assert_eq!(*picture.get_pixel(x, y), style.wall);
+        } else {
+            shades.insert(picture.get_pixel(x, y).0);
+        }
+    }
+    assert!(shades.len() > 20);
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn debug_images_highlight_the_walk_starts() {
+    use maze_generation::DebugOverlay;
+    let mut trace = Maze::generation_trace(12, 7, ChaCha8Rng::seed_from_u64(4)).unwrap();
+    let events: Vec<GenerationEvent> = trace.by_ref().collect();
+    let maze = trace.into_maze();
+    let overlay = DebugOverlay::from_events(&events);
+    assert_eq!(overlay.seed_cell, Some((0, 0)));
+    assert!(!overlay.walk_starts.is_empty());
+
+    let img = maze.build_debug_image(&overlay).unwrap();
+    let plain = maze.build_debug_image(&DebugOverlay { label_every: 0, ..overlay.clone() }).unwrap();
+    assert!(img.width() > plain.width() && img.height() > plain.height());
+
+    // Every highlighted cell has its color in the middle, away from the gridlines
+    let middle = |cell: (usize, usize)| ((2 * cell.0 as u32 + 1) * 8 + 5, (2 * cell.1 as u32 + 1) * 8 + 5);
+    for &cell in &overlay.walk_starts[1..] {
+        assert_ne!(*plain.get_pixel(middle(cell).0, middle(cell).1), image::Rgb([255, 255, 255]));
+    }
+    assert_eq!(*plain.get_pixel(middle((0, 0)).0, middle((0, 0)).1), image::Rgb([0, 110, 255]));
+
+    let outside = DebugOverlay { seed_cell: Some((12, 0)), ..DebugOverlay::default() };
+    assert!(matches!(maze.build_debug_image(&outside), Err(MazeError::OutOfBounds { .. })));
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn provenance_images_only_recolor_paths() {
+    use maze_generation::ProvenanceColoring;
+
+    let (maze, provenance) = Maze::with_provenance(9, 6, ChaCha8Rng::seed_from_u64(2)).unwrap();
+    let plain = maze.build_image();
+    for &coloring in &[ProvenanceColoring::Walk, ProvenanceColoring::Order] {
+        let img = maze.build_provenance_image(&provenance, coloring).unwrap();
+        assert_eq!(img.dimensions(), plain.dimensions());
+        for (x, y, pixel) in img.enumerate_pixels() {
+            let is_wall = plain.get_pixel(x, y).0 == [0, 0, 0];
+            assert_eq!(pixel.0 == [0, 0, 0], is_wall, "pixel ({}, {})", x, y);
+            if !is_wall && x % 2 == 1 && y % 2 == 1 {
+                assert_ne!(pixel.0, [255, 255, 255], "cell pixel ({}, {}) left uncolored", x, y);
+            }
+        }
+    }
+
+    let (_, other) = Maze::with_provenance(6, 9, ChaCha8Rng::seed_from_u64(2)).unwrap();
+    assert!(matches!(maze.build_provenance_image(&other, ProvenanceColoring::Walk), Err(MazeError::SizeMismatch { .. })));
+}
+
+proptest! {
+    #[cfg(feature = "mesh")]
+    #[test]
+    fn wall_rects_cover_every_wall_tile_once(mut maze in any_maze()) {
+        maze.open_entrances(EntrancePlacement::Farthest).unwrap();
+        let tiles = maze.scale(1).unwrap();
+        let mut covered = vec![vec![false; tiles[0].len()]; tiles.len()];
+        let rects = maze.wall_rects();
+        for rect in &rects {
+            for x in rect.x..rect.x + rect.width {
+                for y in rect.y..rect.y + rect.height {
+                    prop_assert!(tiles[x][y].is_wall(), "{:?} covers path tile ({}, {})", rect, x, y);
+                    prop_assert!(!covered[x][y], "tile ({}, {}) covered twice", x, y);
+                    covered[x][y] = true;
+                }
+            }
+        }
+        for (x, column) in tiles.iter().enumerate() {
+            for (y, tile) in column.iter().enumerate() {
+                prop_assert_eq!(covered[x][y], tile.is_wall(), "tile ({}, {})", x, y);
+            }
+        }
+
+        // Far fewer colliders than wall tiles
+        let walls = tiles.iter().flatten().filter(|tile| tile.is_wall()).count();
+        prop_assert!(rects.len() <= walls);
+        let (width, height) = maze.size();
+        if width * height >= 16 {
+            prop_assert!(rects.len() * 2 < walls, "{} rectangles for {} wall tiles", rects.len(), walls);
+        }
+
+        let mesh = maze.wall_mesh(2.0, 3.0);
+        prop_assert_eq!(mesh.vertices.len(), rects.len() * 8);
+        prop_assert_eq!(mesh.indices.len(), rects.len() * 36);
+        prop_assert!(mesh.indices.iter().all(|&index| (index as usize) < mesh.vertices.len()));
+        let (pixel_width, pixel_height) = (tiles.len() as f32 * 2.0, tiles[0].len() as f32 * 2.0);
+        prop_assert!(mesh.vertices.iter().all(|&[x, y, z]| (0.0..=pixel_width).contains(&x) && (y == 0.0 || y == 3.0) && (0.0..=pixel_height).contains(&z)));
+    }
+
+    #[cfg(feature = "petgraph")]
+    #[test]
+    fn graphs_keep_every_passage_and_distance(mut maze in any_maze()) {
+        let graph = maze.to_graph();
+        let (width, height) = maze.size();
+        let passages = (0..width).flat_map(|x| (0..height).map(move |y| (x, y)))
+            .map(|cell| [Direction::Right, Direction::Down].iter().filter(|&&direction| maze.can_move(cell, direction)).count())
+            .sum::<usize>();
+        prop_assert_eq!(graph.edge_weights().sum::<usize>(), passages);
+        if width * height > 1 {
+            // A perfect maze is a tree, so it stays one
+            prop_assert_eq!(graph.edge_count(), graph.node_count() - 1);
+        }
+
+        maze.open_entrances(EntrancePlacement::Farthest).unwrap();
+        let graph = maze.to_graph();
+        let (entrance, exit) = maze.entrances().unwrap();
+        let node = |cell| graph.node_indices().find(|&node| graph[node] == cell).unwrap();
+        let distances = petgraph::algo::dijkstra(&graph, node(entrance.cell), Some(node(exit.cell)), |edge| *edge.weight());
+        prop_assert_eq!(distances[&node(exit.cell)], maze.solution().unwrap().len() - 1);
+    }
+}
+
+#[cfg(feature = "petgraph")]
+#[test]
+fn graphs_give_rings_a_node() {
+    // A loop of corridors with no junctions still gets a node
+    let mut ring = Maze::from_seed(2, 2, 1).unwrap();
+    ring.add_loops(1, &mut ChaCha8Rng::seed_from_u64(1));
+    let graph = ring.to_graph();
+    assert_eq!((graph.node_count(), graph.edge_count()), (1, 1));
+    assert_eq!(graph.edge_weights().next(), Some(&4));
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn key_images_color_keys_and_doors() {
+    let mut maze = Maze::from_seed(10, 10, 3).unwrap();
+    maze.open_entrances(EntrancePlacement::Farthest).unwrap();
+    let layer = maze.place_keys(2, &mut ChaCha8Rng::seed_from_u64(3)).unwrap();
+    let (plain, img) = (maze.build_image(), maze.build_keys_image(&layer).unwrap());
+
+    let mut changed = 0;
+    for (x, y, pixel) in img.enumerate_pixels() {
+        if pixel != plain.get_pixel(x, y) {
+            changed += 1;
+        }
+    }
+    assert_eq!(changed, 4);
+    for &((x, y), key) in layer.keys() {
+        let door = layer.doors().iter().find(|door| door.2 == key).unwrap();
+        let door_pixel = img.get_pixel((door.0.0 + door.1.0 + 1) as u32, (door.0.1 + door.1.1 + 1) as u32).0;
+        let key_pixel = img.get_pixel(2 * x as u32 + 1, 2 * y as u32 + 1).0;
+        assert_eq!(door_pixel, [key_pixel[0] / 2, key_pixel[1] / 2, key_pixel[2] / 2]);
+    }
+
+    let mut walled = KeyLayer::new();
+    walled.add_door((0, 0), (2, 0), 0);
+    assert!(matches!(maze.build_keys_image(&walled), Err(MazeError::NotAdjacent { .. })));
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn solve_animations_end_on_the_route() {
+    use maze_generation::SolveAnimation;
+
+    let maze = Maze::with_rng(9, 7, &mut ChaCha8Rng::seed_from_u64(4)).unwrap();
+    let (start, goal) = ((0, 0), (8, 6));
+    let route = maze.solve(start, goal).unwrap();
+    for &solver in &[TracedSolver::BreadthFirst, TracedSolver::WallFollower(Hand::Left), TracedSolver::Tremaux] {
+        let animation = SolveAnimation { solver, scale: 3, frames: 10, ..SolveAnimation::default() };
+        let frames = maze.solve_animation_frames(&animation, start, goal).unwrap();
+        assert!(!frames.is_empty() && frames.len() <= 11, "{:?} made {} frames", solver, frames.len());
+        assert!(frames.iter().all(|frame| frame.dimensions() == (19 * 3, 15 * 3)));
+
+        let last = frames.last().unwrap();
+        let first = &frames[0];
+        for &(x, y) in &route {
+            let (px, py) = ((2 * x as u32 + 1) * 3, (2 * y as u32 + 1) * 3);
+            assert_eq!(last.get_pixel(px, py).0, [230, 40, 40]);
+            assert_ne!(first.get_pixel(px, py).0, [0, 0, 0]);
+        }
+    }
+
+    let mut gif = Vec::new();
+    maze.write_solve_gif(&mut gif, &SolveAnimation { frames: 4, ..SolveAnimation::default() }, start, goal).unwrap();
+    assert!(gif.starts_with(b"GIF89a"));
+
+    assert!(matches!(maze.solve_animation_frames(&SolveAnimation { scale: 0, ..SolveAnimation::default() }, start, goal), Err(MazeError::InvalidScale)));
+    assert!(matches!(maze.solve_animation_frames(&SolveAnimation { frames: 0, ..SolveAnimation::default() }, start, goal), Err(MazeError::OutOfRange { .. })));
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn tiles_reassemble_into_build_image() {
+    let maze = Maze::from_seed(23, 11, 5).unwrap();
+    let dir = std::env::temp_dir().join(format!("maze-tiles-{}", std::process::id()));
+    maze.save_tiles(&dir, 10).unwrap();
+
+    let expected = maze.build_image();
+    let manifest = std::fs::read_to_string(dir.join("tiles.csv")).unwrap();
+    let mut covered = 0;
+    for line in manifest.lines().skip(1) {
+        let fields: Vec<&str> = line.split(',').collect();
+        let [x, y, width, height] = [3, 4, 5, 6].map(|i| fields[i].parse::<u32>().unwrap());
+        let tile = image::open(dir.join(fields[0])).unwrap().to_rgb8();
+        assert_eq!(tile.dimensions(), (width, height), "{}", fields[0]);
+        assert_eq!(tile, image::imageops::crop_imm(&expected, x, y, width, height).to_image(), "{}", fields[0]);
+        covered += width * height;
+    }
+    assert_eq!(covered, expected.width() * expected.height());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+proptest! {
+    #[cfg(feature = "image")]
+    #[test]
+    fn eller_mazes_are_perfect(width in size(), height in size(), seed in any::<u64>()) {
+        use std::convert::TryFrom;
+
+        let mut png = Vec::new();
+        Maze::write_eller_png(width, height, ChaCha8Rng::seed_from_u64(seed), &mut png).unwrap();
+        let maze = Maze::try_from(&image::load_from_memory(&png).unwrap().to_rgb8()).unwrap();
+        prop_assert_eq!(maze.size(), (width, height));
+        prop_assert_eq!(maze.validate(), Ok(()), "{:?}", maze);
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn collected_eller_mazes_match_the_streamed_ones(width in size(), height in size(), seed in any::<u64>()) {
+        use std::convert::TryFrom;
+
+        let maze = Maze::from_eller(width, height, ChaCha8Rng::seed_from_u64(seed)).unwrap();
+        prop_assert_eq!(maze.validate(), Ok(()), "{:?}", maze);
+
+        let mut png = Vec::new();
+        Maze::write_eller_png(width, height, ChaCha8Rng::seed_from_u64(seed), &mut png).unwrap();
+        prop_assert_eq!(Maze::try_from(&image::load_from_memory(&png).unwrap().to_rgb8()).unwrap(), maze);
+    }
+}
+
+#[test]
+fn algorithms_generate_until_constraints_are_met() {
+    let constraints = Constraints { entrances: Some(EntrancePlacement::Farthest), min_solution_length: Some(20), ..Default::default() };
+    for &algorithm in &Algorithm::ALL {
+        assert_eq!(algorithm.name().parse::<Algorithm>().unwrap(), algorithm);
+        let (maze, attempts) = Maze::generate_until_with_rng(10, 10, algorithm, &constraints, 100, &mut ChaCha8Rng::seed_from_u64(5)).unwrap();
+        assert!(constraints.is_met_by(&maze));
+        assert_eq!(maze.validate(), Ok(()));
+        let again = Maze::generate_until_with_rng(10, 10, algorithm, &constraints, 100, &mut ChaCha8Rng::seed_from_u64(5)).unwrap();
+        assert_eq!(again, (maze, attempts));
+    }
+
+    assert_eq!("eller".parse::<Algorithm>().unwrap(), Algorithm::Eller);
+    assert!(matches!("prim".parse::<Algorithm>(), Err(MazeError::InvalidAlgorithm(_))));
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn styled_images_scale_and_recolor() {
+    let maze = Maze::from_seed(5, 4, 2).unwrap();
+    let (wall, path) = (image::Rgb([10, 20, 30]), image::Rgb([200, 210, 220]));
+    let plain = maze.build_image();
+    let styled = maze.build_styled_image(3, wall, path).unwrap();
+    assert_eq!(styled.dimensions(), (plain.width() * 3, plain.height() * 3));
+    for (x, y, pixel) in styled.enumerate_pixels() {
+        let expected = if plain.get_pixel(x / 3, y / 3).0 == [0, 0, 0] { wall } else { path };
+        assert_eq!(*pixel, expected);
+    }
+    assert!(matches!(maze.build_styled_image(0, wall, path), Err(MazeError::InvalidScale)));
+}
+
+proptest! {
+    #[test]
+    fn step_budgets_keep_the_maze_perfect(width in size(), height in size(), seed in any::<u64>()) {
+        let mut generator = Generator::from_seed(seed).with_step_budget(4);
+        let maze = generator.generate(width, height).unwrap();
+        prop_assert_eq!(maze.validate(), Ok(()), "{:?}", maze);
+        let stats = generator.walk_stats();
+        prop_assert!(stats.steps >= stats.walks, "{:?}", stats);
+    }
+
+    #[test]
+    fn direction_weights_keep_the_maze_perfect(width in size(), height in size(), seed in any::<u64>(), weight in 0.1..10.0) {
+        let mut generator = Generator::from_seed(seed).with_direction_weights(DirectionWeights::horizontal(weight)).unwrap();
+        let maze = generator.generate(width, height).unwrap();
+        prop_assert_eq!(maze.validate(), Ok(()), "{:?}", maze);
+    }
+
+    #[test]
+    fn generation_reports_count_every_cell(width in size(), height in size(), seed in any::<u64>()) {
+        let mut generator = Generator::from_seed(seed);
+        let (maze, mut report) = generator.generate_with_report(width, height).unwrap();
+        prop_assert_eq!(maze.validate(), Ok(()), "{:?}", maze);
+
+        // Every cell but the first is committed by a walk, and every step adds a cell, erases a loop or both
+        let walks = report.walks;
+        prop_assert_eq!(walks.cells_committed as usize, width * height - 1, "{:?}", report);
+        prop_assert!(walks.steps >= walks.cells_committed + walks.cells_erased, "{:?}", report);
+        prop_assert!(walks.starts_checked as usize <= width * height, "{:?}", report);
+        prop_assert_eq!(generator.walk_stats(), walks);
+
+        prop_assert_eq!(report.render_time, None);
+        let rgba = report.time_render(|| maze.to_rgba_bytes());
+        prop_assert_eq!(rgba, maze.to_rgba_bytes());
+        prop_assert!(report.render_time.is_some());
+    }
+
+    #[test]
+    fn dungeon_stairs_link_neighboring_floors(floors in 1..=4usize, seed in any::<u64>()) {
+        let dungeon = Dungeon::from_seed(5, 4, floors, 3, seed).unwrap();
+        prop_assert_eq!(&dungeon, &Dungeon::from_seed(5, 4, floors, 3, seed).unwrap());
+        prop_assert_eq!(dungeon.floors().len(), floors);
+        prop_assert_eq!(dungeon.stairs().len(), 3 * (floors - 1));
+
+        for (floor, maze) in dungeon.floors().iter().enumerate() {
+            prop_assert_eq!(maze.validate(), Ok(()));
+            let mut cells: Vec<_> = dungeon.stairs_up(floor).chain(dungeon.stairs_down(floor)).collect();
+            prop_assert!(cells.iter().all(|&cell| maze.is_path(cell)));
+            let stairs = cells.len();
+            cells.sort();
+            cells.dedup();
+            prop_assert_eq!(cells.len(), stairs, "two stairs share a cell on floor {}", floor);
+        }
+    }
+}
+
+#[test]
+fn step_budgets_restart_long_walks() {
+    let mut generator = Generator::from_seed(3).with_step_budget(4);
+    let maze = generator.generate(MAX_SIZE, MAX_SIZE).unwrap();
+    assert_eq!(maze.validate(), Ok(()));
+
+    let stats = generator.walk_stats();
+    assert!(stats.restarts > 0, "{:?}", stats);
+    assert!(stats.walks > 0 && stats.steps >= stats.walks, "{:?}", stats);
+
+    generator.reset_walk_stats();
+    assert_eq!(generator.walk_stats(), WalkStats::default());
+}
+
+#[test]
+fn direction_weights_bias_the_corridors() {
+    // Passages going right and down from each cell, so each is counted once
+    let passages = |maze: &Maze, direction: Direction| {
+        let (width, height) = maze.size();
+        (0..width).flat_map(|x| (0..height).map(move |y| (x, y))).filter(|&cell| maze.can_move(cell, direction)).count()
+    };
+    let mut generator = Generator::from_seed(9).with_direction_weights(DirectionWeights::horizontal(8.0)).unwrap();
+    let maze = generator.generate(40, 40).unwrap();
+    let (horizontal, vertical) = (passages(&maze, Direction::Right), passages(&maze, Direction::Down));
+    assert!(horizontal > 2 * vertical, "{} horizontal, {} vertical", horizontal, vertical);
+
+    let mut vertical_generator = Generator::from_seed(9).with_direction_weights(DirectionWeights::vertical(8.0)).unwrap();
+    let maze = vertical_generator.generate(40, 40).unwrap();
+    assert!(passages(&maze, Direction::Down) > 2 * passages(&maze, Direction::Right));
+
+    for &weight in &[0.0, -1.0, f64::NAN, f64::INFINITY] {
+        let weights = DirectionWeights { left: weight, ..DirectionWeights::default() };
+        assert!(matches!(Generator::from_seed(9).with_direction_weights(weights), Err(MazeError::OutOfRange { .. })));
+    }
+}
+
+#[test]
+fn generation_reports_add_up_across_mazes() {
+    let mut generator = Generator::from_seed(5);
+    let (_, first) = generator.generate_with_report(7, 5).unwrap();
+    let (_, second) = generator.generate_with_report(5, 7).unwrap();
+    let total = generator.walk_stats();
+    assert_eq!(total.walks, first.walks.walks + second.walks.walks);
+    assert_eq!(total.steps, first.walks.steps + second.walks.steps);
+    assert_eq!(total.cells_committed, 2 * 34);
+    assert!(total.starts_checked <= 2 * 35);
+    assert_eq!(total.longest_walk, first.walks.longest_walk.max(second.walks.longest_walk));
+    assert!(generator.generate_with_report(0, 3).is_err());
+}
+
+#[test]
+fn walk_starts_are_checked_once_per_cell() {
+    // Enough walks that rescanning the cells for each one would show up many times over
+    let mut generator = Generator::from_seed(2);
+    generator.generate(60, 50).unwrap();
+    let stats = generator.walk_stats();
+    assert!(stats.starts_checked <= 60 * 50, "{:?}", stats);
+    assert!(stats.walks > 100, "{:?}", stats);
+
+    // A fill target stops the pass early
+    let mut generator = Generator::from_seed(2).with_fill(0.5).unwrap();
+    generator.generate(60, 50).unwrap();
+    assert!(generator.walk_stats().starts_checked < stats.starts_checked);
+}
+
+#[test]
+fn dungeons_need_floors_and_room_for_stairs() {
+    assert!(matches!(Dungeon::from_seed(5, 4, 0, 3, 0), Err(MazeError::OutOfRange { .. })));
+    assert!(matches!(Dungeon::from_seed(5, 4, 3, 11, 0), Err(MazeError::OutOfRange { .. })));
+    assert!(Dungeon::from_seed(5, 4, 2, 20, 0).is_ok());
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn dungeons_save_an_image_per_floor() {
+    let dungeon = Dungeon::from_seed(7, 5, 3, 2, 9).unwrap();
+    let dir = std::env::temp_dir().join(format!("maze-dungeon-{}", std::process::id()));
+    dungeon.save(&dir).unwrap();
+
+    for floor in 0..3 {
+        let img = image::open(dir.join(format!("floor_{}.png", floor))).unwrap().to_rgb8();
+        assert_eq!(img, dungeon.build_floor_image(floor));
+        for (x, y) in dungeon.stairs_up(floor) {
+            assert_eq!(img.get_pixel(2 * x as u32 + 1, 2 * y as u32 + 1), &image::Rgb([0, 0, 255]));
+        }
+        for (x, y) in dungeon.stairs_down(floor) {
+            assert_eq!(img.get_pixel(2 * x as u32 + 1, 2 * y as u32 + 1), &image::Rgb([255, 128, 0]));
+        }
+    }
+
+    let json = std::fs::read_to_string(dir.join("dungeon.json")).unwrap();
+    assert!(json.contains("\"width\": 7,"));
+    assert_eq!(json.matches("\"image\"").count(), 3);
+    assert_eq!(json.matches("\"from\"").count(), 4);
+    let stair = dungeon.stairs()[0];
+    assert!(json.contains(&format!("{{ \"from\": 0, \"to\": 1, \"x\": {}, \"y\": {} }}", stair.cell.0, stair.cell.1)));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+proptest! {
+    #[test]
+    fn placed_points_follow_the_rules(seed in any::<u64>()) {
+        let steps = |maze: &Maze, a, b| maze.solve(a, b).unwrap().len() - 1;
+        let mut maze = Maze::from_seed(12, 10, seed).unwrap();
+        let (entrance, _) = maze.open_entrances(EntrancePlacement::Farthest).unwrap();
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+        let rules = PlacementRules { count: 4, min_spacing: 6, min_entrance_distance: 5, prefer_dead_ends: true };
+        let treasure = maze.place_points("treasure", &rules, &mut rng).unwrap();
+        prop_assert_eq!(treasure.len(), 4);
+        for (i, &a) in treasure.iter().enumerate() {
+            prop_assert!(maze.has_tag(a, "treasure"));
+            prop_assert_eq!(maze.neighbors(a).len(), 1, "{:?} isn't a dead end", a);
+            prop_assert!(steps(&maze, entrance, a) >= 5);
+            for &b in &treasure[i + 1..] {
+                prop_assert!(steps(&maze, a, b) >= 6);
+            }
+        }
+
+        // Spacing counts the points placed before, but not ones with other labels
+        let more = maze.place_points("treasure", &PlacementRules { count: 1, min_spacing: 6, ..rules }, &mut rng).unwrap();
+        prop_assert!(treasure.iter().all(|&a| steps(&maze, a, more[0]) >= 6));
+        let traps = maze.place_points("trap", &PlacementRules { count: 60, ..PlacementRules::default() }, &mut rng).unwrap();
+        prop_assert_eq!(traps.len(), 60);
+    }
+
+    #[test]
+    fn balanced_spawns_are_equally_far_from_the_goal(seed in any::<u64>(), (count, tolerance) in prop::sample::select(vec![(1, 0), (4, 0), (4, 2), (8, 3)])) {
+        let maze = Maze::from_seed(11, 9, seed).unwrap();
+        let goal = (5, 4);
+        let spawns = maze.balanced_spawns(goal, count, tolerance, &mut ChaCha8Rng::seed_from_u64(seed)).unwrap();
+        prop_assert_eq!(&spawns, &maze.balanced_spawns(goal, count, tolerance, &mut ChaCha8Rng::seed_from_u64(seed)).unwrap());
+        prop_assert_eq!(spawns.len(), count);
+        prop_assert!(!spawns.contains(&goal));
+
+        let distances: Vec<usize> = spawns.iter().map(|&spawn| maze.solve(spawn, goal).unwrap().len() - 1).collect();
+        let (nearest, farthest) = (distances.iter().min().unwrap(), distances.iter().max().unwrap());
+        prop_assert!(farthest - nearest <= tolerance, "{:?} with tolerance {}", distances, tolerance);
+    }
+}
+
+#[test]
+fn placed_points_stop_when_the_maze_is_full() {
+    let mut maze = Maze::from_seed(4, 4, 0).unwrap();
+    let rules = PlacementRules { count: 17, ..PlacementRules::default() };
+    assert!(matches!(maze.place_points("spawn", &rules, &mut ChaCha8Rng::seed_from_u64(0)), Err(MazeError::NoRoom { placed: 16, wanted: 17, .. })));
+    assert_eq!(maze.cells_tagged("spawn").count(), 0);
+}
+
+#[test]
+fn balanced_spawns_need_enough_cells() {
+    let maze = Maze::from_seed(3, 3, 0).unwrap();
+    assert_eq!(maze.balanced_spawns((1, 1), 8, 8, &mut ChaCha8Rng::seed_from_u64(0)).map(|spawns| spawns.len()), Some(8));
+    assert_eq!(maze.balanced_spawns((1, 1), 9, 8, &mut ChaCha8Rng::seed_from_u64(0)), None);
+    assert_eq!(maze.balanced_spawns((3, 1), 1, 0, &mut ChaCha8Rng::seed_from_u64(0)), None);
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn points_images_mark_tagged_cells() {
+    let mut maze = Maze::from_seed(9, 7, 3).unwrap();
+    let rules = PlacementRules { count: 3, min_spacing: 4, ..PlacementRules::default() };
+    let spawns = maze.place_points("spawn", &rules, &mut ChaCha8Rng::seed_from_u64(3)).unwrap();
+
+    let img = maze.build_points_image(&[("spawn", image::Rgb([255, 0, 0]))]);
+    let mut expected = maze.build_marked_image();
+    for (x, y) in spawns {
+        expected.put_pixel(2 * x as u32 + 1, 2 * y as u32 + 1, image::Rgb([255, 0, 0]));
+    }
+    assert_eq!(img, expected);
+}
+
+proptest! {
+    #[test]
+    fn partitions_split_the_maze_into_contiguous_zones(seed in any::<u64>()) {
+        let mut maze = Maze::from_seed(16, 12, seed).unwrap();
+        let zones = maze.partition(5, &mut ChaCha8Rng::seed_from_u64(seed)).unwrap();
+        prop_assert_eq!(&zones, &maze.partition(5, &mut ChaCha8Rng::seed_from_u64(seed)).unwrap());
+        prop_assert_eq!(zones.count(), 5);
+        prop_assert_eq!(zones.sizes.iter().sum::<usize>(), 16 * 12);
+
+        // Each zone is connected on its own: flood filling from any cell of a zone without leaving it reaches the whole zone
+        for zone in 0..zones.count() {
+            let cells: Vec<_> = maze.path_cells().filter(|&cell| zones.region_of(cell) == Some(zone)).collect();
+            let mut seen = vec![cells[0]];
+            let mut stack = vec![cells[0]];
+            while let Some(cell) = stack.pop() {
+                for next in maze.neighbors(cell) {
+                    if zones.region_of(next) == Some(zone) && !seen.contains(&next) {
+                        seen.push(next);
+                        stack.push(next);
+                    }
+                }
+            }
+            prop_assert_eq!(seen.len(), zones.sizes[zone]);
+        }
+
+        maze.tag_regions(&zones, "zone").unwrap();
+        for zone in 0..zones.count() {
+            prop_assert_eq!(maze.cells_tagged(&format!("zone{}", zone)).count(), zones.sizes[zone]);
+        }
+    }
+}
+
+#[test]
+fn partitions_need_as_many_cells_as_zones() {
+    let maze = Maze::from_seed(2, 2, 0).unwrap();
+    assert!(matches!(maze.partition(0, &mut ChaCha8Rng::seed_from_u64(0)), Err(MazeError::OutOfRange { .. })));
+    assert!(matches!(maze.partition(5, &mut ChaCha8Rng::seed_from_u64(0)), Err(MazeError::OutOfRange { .. })));
+    let single_cells = maze.partition(4, &mut ChaCha8Rng::seed_from_u64(0)).unwrap();
+    assert_eq!(single_cells.sizes, vec![1; 4]);
+    let other = Maze::from_seed(3, 2, 0).unwrap().partition(2, &mut ChaCha8Rng::seed_from_u64(0)).unwrap();
+    assert!(matches!(maze.clone().tag_regions(&other, "zone"), Err(MazeError::SizeMismatch { .. })));
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn regions_images_tint_each_zone() {
+    let maze = Maze::from_seed(10, 8, 2).unwrap();
+    let zones = maze.partition(3, &mut ChaCha8Rng::seed_from_u64(2)).unwrap();
+    let img = maze.build_regions_image(&zones).unwrap();
+    let plain = maze.build_image();
+
+    for (x, y) in maze.path_cells() {
+        let pixel = *img.get_pixel(2 * x as u32 + 1, 2 * y as u32 + 1);
+        assert_ne!(pixel, image::Rgb([255, 255, 255]));
+        assert_ne!(pixel, image::Rgb([0, 0, 0]));
+    }
+    // Walls are untouched
+    for (a, b) in img.pixels().zip(plain.pixels()) {
+        assert_eq!(*a == image::Rgb([0, 0, 0]), *b == image::Rgb([0, 0, 0]));
+    }
+}
+
+proptest! {
+    // Each case can take up to 200 attempts, so fewer are drawn
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn fair_mazes_balance_every_players_path(seed in any::<u64>(), (players, tolerance) in prop::sample::select(vec![(2, 0), (3, 2), (4, 4), (6, 6)])) {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let (maze, attempts) = Maze::generate_fair(13, 11, players, tolerance, 200, &mut rng).unwrap();
+        prop_assert!(attempts >= 1);
+        prop_assert_eq!(maze.validate(), Ok(()));
+        prop_assert!(maze.has_tag((6, 5), "goal"));
+
+        let lengths: Vec<usize> = (1..=players).map(|player| {
+            let opening = maze.opening(&format!("player{}", player)).unwrap();
+            assert_eq!(opening.side, maze_generation::Direction::ALL[(player - 1) % 4]);
+            maze.solve(opening.cell, (6, 5)).unwrap().len()
+        }).collect();
+        let (shortest, longest) = (lengths.iter().min().unwrap(), lengths.iter().max().unwrap());
+        prop_assert!(longest - shortest <= tolerance, "{:?} with tolerance {}", lengths, tolerance);
+    }
+
+    #[test]
+    fn ctf_arenas_are_point_symmetric(seed in any::<u64>(), (width, height, lanes) in prop::sample::select(vec![(2, 1, 1), (8, 5, 1), (8, 5, 3), (10, 6, 2), (12, 7, 7)])) {
+        let arena = Maze::ctf_arena(width, height, lanes, &mut ChaCha8Rng::seed_from_u64(seed)).unwrap();
+        let turned = arena.rotated(Rotation::Half);
+        prop_assert!(arena.cells().zip(turned.cells()).all(|((_, a), (_, b))| a == b), "{}x{} with {} lanes", width, height, lanes);
+
+        // One passage per cell but one, plus one loop for every lane past the first
+        let passages: usize = arena.path_cells().map(|cell| arena.neighbors(cell).len()).sum::<usize>() / 2;
+        prop_assert_eq!(passages, width * height - 1 + lanes - 1);
+
+        let base1 = arena.cells_tagged("base1").next().unwrap();
+        let base2 = arena.cells_tagged("base2").next().unwrap();
+        prop_assert_eq!(base2, (width - 1 - base1.0, height - 1 - base1.1));
+        let there = arena.solve(base1, base2).unwrap();
+        let back = arena.solve(base2, base1).unwrap();
+        prop_assert_eq!(there.len(), back.len());
+    }
+}
+
+#[test]
+fn fair_mazes_need_two_to_eight_players() {
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+    assert!(matches!(Maze::generate_fair(5, 5, 1, 0, 10, &mut rng), Err(MazeError::OutOfRange { setting: "players", .. })));
+    assert!(matches!(Maze::generate_fair(2, 5, 9, 0, 10, &mut rng), Err(MazeError::OutOfRange { setting: "players", .. })));
+    assert!(matches!(Maze::generate_fair(5, 5, 2, 0, 0, &mut rng), Err(MazeError::OutOfRange { setting: "max_attempts", .. })));
+}
+
+#[test]
+fn ctf_arenas_need_sizes_and_lanes_that_fit() {
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+    assert!(matches!(Maze::ctf_arena(7, 5, 1, &mut rng), Err(MazeError::OutOfRange { setting: "width", .. })));
+    assert!(matches!(Maze::ctf_arena(8, 6, 3, &mut rng), Err(MazeError::OutOfRange { setting: "lanes", .. })));
+    assert!(matches!(Maze::ctf_arena(8, 5, 0, &mut rng), Err(MazeError::OutOfRange { setting: "lanes", .. })));
+    assert!(matches!(Maze::ctf_arena(8, 5, 6, &mut rng), Err(MazeError::OutOfRange { setting: "lanes", .. })));
+}
+
+/// The first wall between two neighboring cells, scanning column by column
+fn first_wall(maze: &Maze) -> ((usize, usize), (usize, usize)) {
+    maze.path_cells()
+        .flat_map(|(x, y)| vec![((x, y), (x + 1, y)), ((x, y), (x, y + 1))])
+        .find(|&(cell, next)| maze.is_path(next) && !maze.neighbors(cell).contains(&next))
+        .unwrap()
+}
+
+proptest! {
+    #[test]
+    fn secret_doors_only_open_for_the_secret_view(seed in any::<u64>()) {
+        let maze = Maze::from_seed(10, 8, seed).unwrap();
+        let (start, closed) = first_wall(&maze);
+        let goal = (9, 7);
+        let normal = maze.solve(start, goal).unwrap();
+
+        // A door next to the start can only make the path shorter
+        let mut layer = SecretLayer::new();
+        layer.add_door(start, closed);
+
+        let secret = maze.solve_with_secrets(&layer, start, goal).unwrap().unwrap();
+        prop_assert!(secret.len() <= normal.len());
+        prop_assert_eq!(maze.solve(start, goal).unwrap(), normal);
+
+        let opened = maze.with_secrets_opened(&layer).unwrap();
+        prop_assert!(opened.neighbors(start).contains(&closed));
+        prop_assert!(!maze.neighbors(start).contains(&closed));
+        prop_assert_eq!(opened.solve(start, goal).unwrap(), secret);
+    }
+}
+
+#[test]
+fn secret_doors_must_join_neighbors() {
+    let maze = Maze::from_seed(4, 4, 0).unwrap();
+    let mut layer = SecretLayer::new();
+    layer.add_door((0, 0), (2, 0));
+    assert!(matches!(maze.solve_with_secrets(&layer, (0, 0), (3, 3)), Err(MazeError::NotAdjacent { .. })));
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn secret_images_mark_hidden_doors() {
+    let maze = Maze::from_seed(6, 6, 4).unwrap();
+    let (cell, closed) = first_wall(&maze);
+    let mut layer = SecretLayer::new();
+    layer.add_door(cell, closed);
+
+    let img = maze.build_secret_image(&layer).unwrap();
+    let mut expected = maze.build_image();
+    expected.put_pixel((cell.0 + closed.0 + 1) as u32, (cell.1 + closed.1 + 1) as u32, image::Rgb([160, 0, 200]));
+    assert_eq!(img, expected);
+}
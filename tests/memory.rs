@@ -0,0 +1,38 @@
+//! The memory counters are shared by the whole process, so they're checked in one test of their own.
+//! Run with `cargo test --features memory-stats`.
+#![cfg(feature = "memory-stats")]
+
+use maze_generation::{Generator, memory_stats, reset_memory_stats};
+
+#[test]
+fn memory_counters_add_up() {
+    reset_memory_stats();
+
+    // 100 cells take 9 words of 12 cells each, or 22 words when they're rounded up to four 8x8 tiles
+    let mut generator = Generator::from_seed(1);
+    let maze = generator.generate(10, 10).unwrap();
+    let after_generation = memory_stats();
+    assert_eq!(after_generation.peak_grid_bytes, if cfg!(feature = "z-order") { 176 } else { 72 });
+    assert!(after_generation.walk_buffer_allocations > 0);
+    assert_eq!(after_generation.exports, 0);
+
+    // Generating the same size again reuses every buffer
+    let mut reused = maze.clone();
+    generator.generate_into(&mut reused, 10, 10).unwrap();
+    assert_eq!(memory_stats().walk_buffer_allocations, after_generation.walk_buffer_allocations);
+
+    let saved = maze.to_bytes();
+    assert_eq!(memory_stats().last_export_bytes, saved.len());
+
+    let mut written = Vec::new();
+    maze.write_raw(&mut written).unwrap();
+    let mut png = Vec::new();
+    maze.write_png(&mut png).unwrap();
+    let stats = memory_stats();
+    assert_eq!(stats.exports, 3);
+    assert_eq!(stats.last_export_bytes, png.len());
+    assert_eq!(stats.bytes_exported, saved.len() + written.len() + png.len());
+
+    reset_memory_stats();
+    assert_eq!(memory_stats(), Default::default());
+}
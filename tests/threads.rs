@@ -0,0 +1,99 @@
+//! Mazes and everything made from them must stay usable across threads, e.g. from rayon or tokio tasks.
+//! The assertions are checked at compile time, so a type that stops being Send or Sync fails to build here.
+
+use std::thread;
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use maze_generation::*;
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn public_types_are_send_and_sync() {
+    assert_send_sync::<Maze>();
+    assert_send_sync::<Cell>();
+    assert_send_sync::<MazeError>();
+    assert_send_sync::<Generator>();
+    assert_send_sync::<Generator<ChaCha8Rng>>();
+    assert_send_sync::<GenerationTrace<ChaCha8Rng>>();
+    assert_send_sync::<GenerationHandle>();
+    assert_send_sync::<SolveTrace<'static>>();
+    assert_send_sync::<Piece<'static>>();
+    assert_send_sync::<Crop>();
+    assert_send_sync::<Regions>();
+    assert_send_sync::<DistanceMatrix>();
+    assert_send_sync::<Tremaux>();
+    assert_send_sync::<DeadEndFill>();
+    assert_send_sync::<AStarSolver<Manhattan>>();
+    assert_send_sync::<Constraints>();
+    assert_send_sync::<SweepResult>();
+    assert_send_sync::<Job>();
+    assert_send_sync::<JobResult>();
+}
+
+#[test]
+fn mazes_can_be_generated_and_shared_across_threads() {
+    let mazes: Vec<Maze> = (0..4)
+        .map(|seed| thread::spawn(move || Generator::from_seed(seed).generate(20, 20).unwrap()))
+        .map(|handle| handle.join().unwrap())
+        .collect();
+
+    let maze = &mazes[0];
+    let solutions: Vec<_> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..4).map(|i| scope.spawn(move || maze.solve((0, 0), (19, i)))).collect();
+        handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+    });
+    assert!(solutions.iter().all(Option::is_some));
+    assert_eq!(mazes[1], Maze::from_seed(20, 20, 1).unwrap());
+}
+
+#[test]
+fn job_queues_generate_every_job() {
+    let mut queue = JobQueue::new(3, 2).unwrap();
+    for id in 0..20 {
+        queue.submit(Job { id, width: 1 + id as usize, height: 7, seed: id * 31 });
+    }
+    queue.submit(Job { id: 20, width: 0, height: 7, seed: 0 });
+    assert_eq!(queue.outstanding(), 21);
+
+    let mut results: Vec<JobResult> = queue.results().collect();
+    results.sort_by_key(|result| result.id);
+    assert_eq!(results.len(), 21);
+    for result in &results[..20] {
+        assert_eq!(result.result.as_ref().unwrap(), &Maze::from_seed(1 + result.id as usize, 7, result.id * 31).unwrap());
+    }
+    assert!(matches!(results[20].result, Err(MazeError::InvalidDimensions { got: (0, 7) })));
+    assert_eq!(queue.outstanding(), 0);
+    assert!(queue.recv().is_none());
+
+    assert!(matches!(JobQueue::new(0, 2), Err(MazeError::OutOfRange { .. })));
+}
+
+#[test]
+fn cancelling_stops_a_walk_part_way() {
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    // The first walk on a grid this big takes far more steps than the generator goes between checks,
+    // so the flag set at the first check inside it has to stop that walk
+    let flag = AtomicBool::new(false);
+    let checks = AtomicUsize::new(0);
+    let mut generator = Generator::from_seed(7);
+    let result = generator.generate_with_cancellation(300, 300, || {
+        if checks.fetch_add(1, Ordering::Relaxed) == 1 {
+            flag.store(true, Ordering::Relaxed);
+        }
+        flag.load(Ordering::Relaxed)
+    });
+    match result {
+        Err(MazeError::Cancelled { partial }) => assert_eq!(partial.path_cells().count(), 1),
+        other => panic!("expected a cancelled generation, got {:?}", other),
+    }
+    assert_eq!(checks.load(Ordering::Relaxed), 2);
+
+    // The generator's buffers were left clean for the next maze
+    assert_eq!(generator.generate(20, 20).unwrap().validate(), Ok(()));
+    let uncancelled = Maze::with_cancellation(5, 5, &mut ChaCha8Rng::seed_from_u64(1), || false).unwrap();
+    assert_eq!(uncancelled, Maze::from_seed(5, 5, 1).unwrap());
+}
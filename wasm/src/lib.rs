@@ -0,0 +1,53 @@
+//! WebAssembly bindings for maze-generation, exported with wasm-bindgen
+//! Randomness always comes from a seed passed in from JavaScript, since `thread_rng` has no entropy source on wasm32-unknown-unknown.
+
+use wasm_bindgen::prelude::*;
+
+use maze_generation::{Maze, EntrancePlacement};
+
+/// A generated maze, with its entrance and exit already opened
+#[wasm_bindgen]
+pub struct WasmMaze {
+    maze: Maze,
+    entrance: (usize, usize),
+    exit: (usize, usize),
+}
+
+#[wasm_bindgen]
+impl WasmMaze {
+    /// Generates a maze of the given size in cells. The same seed and size always give the same maze.
+    #[wasm_bindgen(constructor)]
+    pub fn generate(width: usize, height: usize, seed: u64) -> Result<WasmMaze, JsValue> {
+        let mut maze = Maze::from_seed(width, height, seed).map_err(|err| JsValue::from_str(&err.to_string()))?;
+        // Safe to unwrap since every generated maze has path cells all along its border
+        let (entrance, exit) = maze.open_entrances(EntrancePlacement::Farthest).unwrap();
+
+        Ok(WasmMaze { maze, entrance, exit })
+    }
+
+    /// Width of the rendered maze in pixels
+    #[wasm_bindgen(getter)]
+    pub fn pixel_width(&self) -> usize {
+        self.maze.size().0 * 2 + 1
+    }
+
+    /// Height of the rendered maze in pixels
+    #[wasm_bindgen(getter)]
+    pub fn pixel_height(&self) -> usize {
+        self.maze.size().1 * 2 + 1
+    }
+
+    /// The solution from entrance to exit, flattened as [x0, y0, x1, y1, ...] in cell coordinates
+    pub fn solve(&self) -> Vec<u32> {
+        self.maze.solve(self.entrance, self.exit)
+            .unwrap_or_default()
+            .into_iter()
+            .flat_map(|(x, y)| [x as u32, y as u32])
+            .collect()
+    }
+
+    /// The rendered maze as RGBA bytes, ready for `new ImageData(new Uint8ClampedArray(bytes), pixelWidth)`
+    pub fn render_rgba(&self) -> Vec<u8> {
+        self.maze.to_rgba_bytes()
+    }
+}